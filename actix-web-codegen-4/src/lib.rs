@@ -0,0 +1,474 @@
+#![cfg_attr(feature = "native", recursion_limit = "512")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("actix-web-codegen.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
+#[doc = " Creates resource handler, allowing multiple HTTP method guards."]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+#[doc = " #[route(\"path\", method=\"HTTP_METHOD\"[, attributes])]"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the function"]
+#[doc = "   name of handler is used."]
+#[doc = " - `method = \"HTTP_METHOD\"`: Registers HTTP method to provide guard for. Upper-case string,"]
+#[doc = "   \"GET\", \"POST\" for example."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the generate"]
+#[doc = " code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+#[doc = " # use actix_web_codegen::route;"]
+#[doc = " #[route(\"/test\", method = \"GET\", method = \"HEAD\", method = \"CUSTOM\")]"]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn route(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(route), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Creates resource handler, allowing multiple HTTP methods and paths."]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+#[doc = " #[routes]"]
+#[doc = " #[<method>(\"path\", ...)]"]
+#[doc = " #[<method>(\"path\", ...)]"]
+#[doc = " ..."]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " The `routes` macro itself has no parameters, but allows specifying the attribute macros for"]
+#[doc = " the multiple paths and/or methods, e.g. [`GET`](macro@get) and [`POST`](macro@post)."]
+#[doc = ""]
+#[doc = " These helper attributes take the same parameters as the [single method handlers](crate#single-method-handler)."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+#[doc = " # use actix_web_codegen::routes;"]
+#[doc = " #[routes]"]
+#[doc = " #[get(\"/test\")]"]
+#[doc = " #[get(\"/test2\")]"]
+#[doc = " #[delete(\"/test\")]"]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn routes(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(routes), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Get) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (get) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (get) , ";")]
+# [doc = concat ! ("#[" , stringify ! (get) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn get(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(get), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Post) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (post) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (post) , ";")]
+# [doc = concat ! ("#[" , stringify ! (post) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn post(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(post), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Put) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (put) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (put) , ";")]
+# [doc = concat ! ("#[" , stringify ! (put) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn put(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(put), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Delete) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (delete) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (delete) , ";")]
+# [doc = concat ! ("#[" , stringify ! (delete) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn delete(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(delete), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Head) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (head) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (head) , ";")]
+# [doc = concat ! ("#[" , stringify ! (head) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn head(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(head), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Connect) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (connect) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (connect) , ";")]
+# [doc = concat ! ("#[" , stringify ! (connect) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn connect(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(connect), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Options) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (options) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (options) , ";")]
+# [doc = concat ! ("#[" , stringify ! (options) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn options(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(options), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Trace) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (trace) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (trace) , ";")]
+# [doc = concat ! ("#[" , stringify ! (trace) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn trace(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(trace), args, input)
+}
+#[cfg(not(feature = "native"))]
+# [doc = concat ! ("Creates route handler with `actix_web::guard::" , stringify ! (Patch) , "`.")]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = " ```plain"]
+# [doc = concat ! ("#[" , stringify ! (patch) , r#"("path"[, attributes])]"#)]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Attributes"]
+#[doc = " - `\"path\"`: Raw literal string with path for which to register handler."]
+#[doc = " - `name = \"resource_name\"`: Specifies resource name for the handler. If not set, the"]
+#[doc = "   function name of handler is used."]
+#[doc = " - `guard = \"function_name\"`: Registers function as guard using `actix_web::guard::fn_guard`."]
+#[doc = " - `wrap = \"Middleware\"`: Registers a resource middleware."]
+#[doc = ""]
+#[doc = " # Notes"]
+#[doc = " Function name can be specified as any expression that is going to be accessible to the"]
+#[doc = " generate code, e.g `my_guard` or `my_module::my_guard`."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " # use actix_web::HttpResponse;"]
+# [doc = concat ! ("# use actix_web_codegen::" , stringify ! (patch) , ";")]
+# [doc = concat ! ("#[" , stringify ! (patch) , r#"("/")]"#)]
+#[doc = " async fn example() -> HttpResponse {"]
+#[doc = "     HttpResponse::Ok().finish()"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn patch(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(patch), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Prepends a path prefix to all handlers using routing macros inside the attached module."]
+#[doc = ""]
+#[doc = " # Syntax"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use actix_web_codegen::scope;"]
+#[doc = " #[scope(\"/prefix\")]"]
+#[doc = " mod api {"]
+#[doc = "     // ..."]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Arguments"]
+#[doc = ""]
+#[doc = " - `\"/prefix\"` - Raw literal string to be prefixed onto contained handlers' paths."]
+#[doc = ""]
+#[doc = " # Example"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use actix_web_codegen::{scope, get};"]
+#[doc = " # use actix_web::Responder;"]
+#[doc = " #[scope(\"/api\")]"]
+#[doc = " mod api {"]
+#[doc = "     # use super::*;"]
+#[doc = "     #[get(\"/hello\")]"]
+#[doc = "     pub async fn hello() -> impl Responder {"]
+#[doc = "         // this has path /api/hello"]
+#[doc = "         \"Hello, world!\""]
+#[doc = "     }"]
+#[doc = " }"]
+#[doc = " # fn main() {}"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn scope(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(scope), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Marks async main function as the Actix Web system entry-point."]
+#[doc = ""]
+#[doc = " Note that Actix Web also works under `#[tokio::main]` since version 4.0. However, this macro is"]
+#[doc = " still necessary for actor support (since actors use a `System`). Read more in the"]
+#[doc = " [`actix_web::rt`](https://docs.rs/actix-web/4/actix_web/rt) module docs."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " #[actix_web::main]"]
+#[doc = " async fn main() {"]
+#[doc = "     async { println!(\"Hello world\"); }.await"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn main(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(main), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Marks async test functions to use the Actix Web system entry-point."]
+#[doc = ""]
+#[doc = " # Examples"]
+#[doc = " ```"]
+#[doc = " #[actix_web::test]"]
+#[doc = " async fn test() {"]
+#[doc = "     assert_eq!(async { \"Hello world\" }.await, \"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn test(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(test), args, input)
+}
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,104 @@
+use actix_web::test::{call_and_read_body, call_service, init_service, TestRequest};
+use actix_web::{guard::GuardContext, http::StatusCode, web, App, HttpResponse, Responder};
+use actix_web_codegen::{route, routes, scope};
+
+fn has_token(ctx: &GuardContext<'_>) -> bool {
+    ctx.head().headers().contains_key("x-token")
+}
+
+#[route("/item", method = "GET", method = "PUT", guard = "has_token")]
+async fn item() -> impl Responder {
+    HttpResponse::Ok().body("item")
+}
+
+#[routes]
+#[get("/a")]
+#[post("/b")]
+async fn both() -> impl Responder {
+    HttpResponse::Ok().body("both")
+}
+
+#[scope("/api")]
+mod api {
+    use actix_web::{get, HttpResponse, Responder};
+
+    #[get("/ping")]
+    pub async fn ping() -> impl Responder {
+        HttpResponse::Ok().body("pong")
+    }
+}
+
+fn app() -> App<
+    impl actix_web::dev::ServiceFactory<
+        actix_web::dev::ServiceRequest,
+        Config = (),
+        Response = actix_web::dev::ServiceResponse,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    App::new().service(item).service(both).service(api::ping)
+}
+
+#[actix_web::test]
+async fn test_route() {
+    let app = init_service(app()).await;
+
+    let req = TestRequest::put()
+        .uri("/item")
+        .insert_header(("x-token", "1"))
+        .to_request();
+    assert_eq!(call_and_read_body(&app, req).await, "item");
+
+    let req = TestRequest::get().uri("/item").to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let req = TestRequest::post()
+        .uri("/item")
+        .insert_header(("x-token", "1"))
+        .to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_routes() {
+    let app = init_service(app()).await;
+
+    let req = TestRequest::get().uri("/a").to_request();
+    assert_eq!(call_and_read_body(&app, req).await, "both");
+    let req = TestRequest::post().uri("/b").to_request();
+    assert_eq!(call_and_read_body(&app, req).await, "both");
+
+    let req = TestRequest::post().uri("/a").to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_scope() {
+    let app = init_service(app()).await;
+
+    let req = TestRequest::get().uri("/api/ping").to_request();
+    assert_eq!(call_and_read_body(&app, req).await, "pong");
+
+    let req = TestRequest::get().uri("/ping").to_request();
+    let res = call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::main]
+async fn serve(path: &'static str) -> StatusCode {
+    let app = init_service(App::new().route("/", web::get().to(HttpResponse::NoContent))).await;
+    let req = TestRequest::get().uri(path).to_request();
+    call_service(&app, req).await.status()
+}
+
+#[test]
+fn test_main() {
+    // `#[actix_web::main]` runs the body on a new system, so it can't be
+    // called from within another one.
+    assert_eq!(serve("/"), StatusCode::NO_CONTENT);
+    assert_eq!(serve("/missing"), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,6 @@
+#[actix_web::test]
+async fn my_test() {
+    assert!(async { 1 }.await, 1);
+}
+
+fn main() {}
@@ -1,5 +1,10 @@
-static WASM: &[u8] = include_bytes!("actix-web-codegen.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#![cfg_attr(feature = "native", recursion_limit = "512")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("actix-web-codegen.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `GET` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[get(\"path\"[, attributes])]`"]
@@ -16,6 +21,7 @@ pub fn get(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(get), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `POST` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[post(\"path\"[, attributes])]`"]
@@ -28,6 +34,7 @@ pub fn post(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(post), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `PUT` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[put(\"path\"[, attributes])]`"]
@@ -40,6 +47,7 @@ pub fn put(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(put), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `DELETE` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[delete(\"path\"[, attributes])]`"]
@@ -52,6 +60,7 @@ pub fn delete(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(delete), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `HEAD` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[head(\"path\"[, attributes])]`"]
@@ -64,6 +73,7 @@ pub fn head(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(head), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `CONNECT` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[connect(\"path\"[, attributes])]`"]
@@ -76,6 +86,7 @@ pub fn connect(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(connect), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `OPTIONS` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[options(\"path\"[, attributes])]`"]
@@ -88,6 +99,7 @@ pub fn options(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(options), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `TRACE` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[trace(\"path\"[, attributes])]`"]
@@ -100,6 +112,7 @@ pub fn trace(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(trace), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Creates route handler with `PATCH` method guard."]
 #[doc = ""]
 #[doc = " Syntax: `#[patch(\"path\"[, attributes])]`"]
@@ -112,6 +125,7 @@ pub fn patch(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(patch), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Marks async main function as the actix system entry-point."]
 #[doc = ""]
 #[doc = " ## Usage"]
@@ -130,3 +144,5 @@ pub fn main(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(main), args, input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -1,12 +1,23 @@
-static WASM: &[u8] = include_bytes!("actix_derive.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#![cfg_attr(feature = "native", recursion_limit = "128")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("actix_derive.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro = watt_contrib_runtime::WasmMacro::new(
+    env!("CARGO_PKG_NAME"),
+    env!("CARGO_PKG_VERSION"),
+    WASM,
+);
+#[cfg(not(feature = "native"))]
 #[proc_macro_derive(Message, attributes(rtype))]
 pub fn message_derive_rtype(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(message_derive_rtype), input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_derive(MessageResponse)]
 pub fn message_response_derive_rtype(
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(message_response_derive_rtype), input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -1,5 +1,10 @@
-static WASM: &[u8] = include_bytes!("ctor.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#![cfg_attr(feature = "native", recursion_limit = "256")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("ctor.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[doc = " Marks a function or static variable as a library/executable constructor."]
 #[doc = " This uses OS-specific linker sections to call a specific function at"]
 #[doc = " load time."]
@@ -85,6 +90,7 @@ pub fn ctor(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(ctor), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Marks a function as a library/executable destructor. This uses OS-specific"]
 #[doc = " linker sections to call a specific function at termination time."]
 #[doc = ""]
@@ -110,3 +116,5 @@ pub fn dtor(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(dtor), args, input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
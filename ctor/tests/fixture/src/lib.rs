@@ -0,0 +1,37 @@
+//! Exposes what its constructors did to the program that loads it.
+
+use ctor::{ctor, dtor};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static LOADED: AtomicBool = AtomicBool::new(false);
+
+#[ctor]
+fn fixture_ctor_fn() {
+    LOADED.store(true, Ordering::SeqCst);
+}
+
+#[ctor]
+static FIXTURE_CTOR_STATIC: Vec<u32> = (1..=4).collect();
+
+// Runs at `dlclose`, or when the process exits if the library stays loaded.
+#[dtor]
+fn fixture_dtor_fn() {
+    let message = b"library dtor\n";
+    unsafe {
+        write(1, message.as_ptr(), message.len());
+    }
+}
+
+extern "C" {
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+}
+
+#[no_mangle]
+pub extern "C" fn fixture_loaded() -> bool {
+    LOADED.load(Ordering::SeqCst)
+}
+
+#[no_mangle]
+pub extern "C" fn fixture_static_sum() -> u32 {
+    FIXTURE_CTOR_STATIC.iter().sum()
+}
@@ -0,0 +1,97 @@
+//! Counts the constructors that run before `main` and prints what they set
+//! up, followed by a line from the destructor.
+//!
+//! With `load LIBRARY`, it also loads the fixture library, prints what its
+//! constructors set up and unloads it again.
+
+use ctor::{ctor, dtor};
+use std::collections::HashMap;
+use std::env;
+use std::ffi::{c_void, CStr};
+use std::os::raw::{c_char, c_int};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static MAIN_STARTED: AtomicBool = AtomicBool::new(false);
+static EARLY_CTORS: AtomicUsize = AtomicUsize::new(0);
+
+fn record() {
+    if !MAIN_STARTED.load(Ordering::SeqCst) {
+        EARLY_CTORS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[ctor]
+fn fixture_ctor_fn() {
+    record();
+}
+
+#[ctor]
+unsafe fn fixture_ctor_unsafe_fn() {
+    record();
+}
+
+#[ctor]
+static FIXTURE_CTOR_STATIC: HashMap<u32, String> = {
+    record();
+    (0..3).map(|i| (i, format!("x*100={}", i * 100))).collect()
+};
+
+// stdout can't be used once `main` has returned, so this writes to the file
+// descriptor directly.
+#[dtor]
+fn fixture_dtor_fn() {
+    let message = b"dtor\n";
+    unsafe {
+        write(1, message.as_ptr(), message.len());
+    }
+}
+
+const RTLD_NOW: c_int = 2;
+
+// `libdl` is part of `libc` since glibc 2.34 and an empty stub after that.
+#[link(name = "dl")]
+extern "C" {
+    fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlclose(handle: *mut c_void) -> c_int;
+    fn dlerror() -> *const c_char;
+}
+
+extern "C" {
+    fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+}
+
+unsafe fn load(library: &str) {
+    let path = format!("{}\0", library);
+    let handle = dlopen(path.as_ptr().cast(), RTLD_NOW);
+    assert!(
+        !handle.is_null(),
+        "{}",
+        CStr::from_ptr(dlerror()).to_string_lossy(),
+    );
+    let symbol = |name: &[u8]| {
+        let symbol = dlsym(handle, name.as_ptr().cast());
+        assert!(!symbol.is_null());
+        symbol
+    };
+    let loaded: extern "C" fn() -> bool = std::mem::transmute(symbol(b"fixture_loaded\0"));
+    let static_sum: extern "C" fn() -> u32 = std::mem::transmute(symbol(b"fixture_static_sum\0"));
+    println!("loaded {}", loaded());
+    println!("static sum {}", static_sum());
+    assert_eq!(dlclose(handle), 0);
+    println!("closed");
+}
+
+fn main() {
+    MAIN_STARTED.store(true, Ordering::SeqCst);
+    println!("ctors {}", EARLY_CTORS.load(Ordering::SeqCst));
+    let mut entries: Vec<_> = FIXTURE_CTOR_STATIC.iter().collect();
+    entries.sort();
+    println!("static {:?}", entries);
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let [mode, library] = &args[..] {
+        assert_eq!(mode, "load");
+        unsafe { load(library) };
+    }
+}
@@ -0,0 +1,194 @@
+//! Builds the program and library in `tests/fixture` and checks that their
+//! constructors and destructors run, since a broken `ctor.wasm` would still
+//! compile and only leave the functions uncalled.
+//!
+//! ctor 0.1.15 puts a pointer to every constructor into `.init_array`. A
+//! destructor gets an `.init_array` entry as well, which registers it with
+//! `atexit`, so nothing ends up in `.fini_array`.
+
+#![cfg(all(
+    target_os = "linux",
+    target_pointer_width = "64",
+    target_endian = "little"
+))]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ENTRIES: &[&str] = &[
+    "fixture_ctor_fn",
+    "FIXTURE_CTOR_STATIC___rust_ctor___ctor",
+    "fixture_dtor_fn",
+];
+
+fn build(kind: &str) -> PathBuf {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixture");
+    let target_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("fixture");
+    let mut cargo = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo
+        .args(&["build", kind, "--manifest-path"])
+        .arg(fixture.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir);
+    if cfg!(feature = "native") {
+        cargo.args(&["--features", "native"]);
+    }
+    assert!(cargo.status().unwrap().success());
+    let file = match kind {
+        "--bins" => "ctor-fixture",
+        _ => "libctor_fixture.so",
+    };
+    target_dir.join("debug").join(file)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> usize {
+    u16::from_le_bytes([data[offset], data[offset + 1]]) as usize
+}
+
+fn u32_at(data: &[u8], offset: usize) -> usize {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes) as usize
+}
+
+fn u64_at(data: &[u8], offset: usize) -> usize {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes) as usize
+}
+
+fn c_str(data: &[u8], offset: usize) -> &str {
+    let len = data[offset..].iter().position(|&b| b == 0).unwrap();
+    std::str::from_utf8(&data[offset..offset + len]).unwrap()
+}
+
+/// A section header of a 64-bit little endian ELF file.
+struct Section {
+    name: usize,
+    kind: usize,
+    address: usize,
+    offset: usize,
+    size: usize,
+    link: usize,
+}
+
+fn sections(data: &[u8]) -> Vec<Section> {
+    assert_eq!(
+        &data[..6],
+        b"\x7fELF\x02\x01",
+        "not a 64-bit little endian ELF"
+    );
+    let offset = u64_at(data, 0x28);
+    let size = u16_at(data, 0x3a);
+    let count = u16_at(data, 0x3c);
+    (0..count)
+        .map(|i| {
+            let header = offset + i * size;
+            Section {
+                name: u32_at(data, header),
+                kind: u32_at(data, header + 0x04),
+                address: u64_at(data, header + 0x10),
+                offset: u64_at(data, header + 0x18),
+                size: u64_at(data, header + 0x20),
+                link: u32_at(data, header + 0x28),
+            }
+        })
+        .collect()
+}
+
+/// The symbols of the statics in `.init_array` that contain `name`.
+fn init_array_entries(path: &Path, name: &str) -> Vec<String> {
+    const SYMTAB: usize = 2;
+    const SYMBOL_SIZE: usize = 24;
+
+    let data = fs::read(path).unwrap();
+    let sections = sections(&data);
+    let names = &sections[u16_at(&data, 0x3e)];
+    let init_array = sections
+        .iter()
+        .find(|section| c_str(&data, names.offset + section.name) == ".init_array")
+        .expect("no .init_array section");
+    let range = init_array.address..init_array.address + init_array.size;
+    let symtab = sections
+        .iter()
+        .find(|section| section.kind == SYMTAB)
+        .expect("no symbol table");
+    let strtab = &sections[symtab.link];
+    (0..symtab.size / SYMBOL_SIZE)
+        .map(|i| symtab.offset + i * SYMBOL_SIZE)
+        .filter(|&symbol| range.contains(&u64_at(&data, symbol + 8)))
+        .map(|symbol| c_str(&data, strtab.offset + u32_at(&data, symbol)))
+        .filter(|symbol| symbol.contains(name))
+        .map(str::to_owned)
+        .collect()
+}
+
+fn assert_entries(path: &Path) {
+    for name in ENTRIES {
+        let entries = init_array_entries(path, name);
+        assert_eq!(
+            entries.len(),
+            1,
+            "{} in the .init_array of {}: {:?}",
+            name,
+            path.display(),
+            entries,
+        );
+    }
+}
+
+const PROGRAM_OUTPUT: &str = "\
+ctors 3
+static [(0, \"x*100=0\"), (1, \"x*100=100\"), (2, \"x*100=200\")]
+";
+
+#[test]
+fn program() {
+    let program = build("--bins");
+    let output = Command::new(&program).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{}dtor\n", PROGRAM_OUTPUT),
+    );
+    assert_entries(&program);
+    assert_eq!(
+        init_array_entries(&program, "fixture_ctor_unsafe_fn").len(),
+        1
+    );
+}
+
+#[test]
+fn library() {
+    let library = build("--lib");
+    assert_entries(&library);
+
+    let program = build("--bins");
+    let output = Command::new(&program)
+        .arg("load")
+        .arg(&library)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr),
+    );
+    let loaded = format!("{}loaded true\nstatic sum 10\n", PROGRAM_OUTPUT);
+    // The library's destructor runs when it's unloaded, or at exit if glibc
+    // keeps it loaded, but exactly once either way.
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout == format!("{}library dtor\nclosed\ndtor\n", loaded)
+            || stdout == format!("{}closed\ndtor\nlibrary dtor\n", loaded)
+            || stdout == format!("{}closed\nlibrary dtor\ndtor\n", loaded),
+        "{}",
+        stdout,
+    );
+}
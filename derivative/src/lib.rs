@@ -1,6 +1,12 @@
-static WASM: &[u8] = include_bytes!("derivative.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("derivative.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[cfg_attr(not(test), proc_macro_derive(Derivative, attributes(derivative)))]
 pub fn derivative(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(derivative), input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,13 @@
+#![cfg_attr(feature = "native", recursion_limit = "192")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("derive-new.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
+#[proc_macro_derive(new, attributes(new))]
+pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(derive), input)
+}
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
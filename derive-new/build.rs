@@ -0,0 +1,4 @@
+fn main() {
+    watt_contrib_build::check_entry_points();
+    watt_contrib_build::write_snapshot();
+}
@@ -0,0 +1,13 @@
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("enum-as-inner.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
+#[doc = " Derive functions on an Enum for easily accessing individual items in the Enum"]
+#[proc_macro_derive(EnumAsInner)]
+pub fn enum_as_inner(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_as_inner), input)
+}
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -1,23 +1,45 @@
-static WASM: &[u8] = include_bytes!("futures-macro.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
-use proc_macro_hack::proc_macro_hack;
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("futures-macro.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[doc = " The `join!` macro."]
-#[proc_macro_hack]
+#[proc_macro]
 pub fn join_internal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro(stringify!(join_internal), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " The `try_join!` macro."]
-#[proc_macro_hack]
+#[proc_macro]
 pub fn try_join_internal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro(stringify!(try_join_internal), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " The `select!` macro."]
-#[proc_macro_hack]
+#[proc_macro]
 pub fn select_internal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro(stringify!(select_internal), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " The `select_biased!` macro."]
-#[proc_macro_hack]
+#[proc_macro]
 pub fn select_biased_internal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro(stringify!(select_biased_internal), input)
 }
+#[cfg(not(feature = "native"))]
+#[proc_macro_attribute]
+pub fn test_internal(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(test_internal), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " The `stream_select!` macro."]
+#[proc_macro]
+pub fn stream_select_internal(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro(stringify!(stream_select_internal), input)
+}
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
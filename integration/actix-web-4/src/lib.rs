@@ -0,0 +1,51 @@
+//! Routes of the project built with actix-web 4.
+
+use actix_web::guard::GuardContext;
+use actix_web::http::Method;
+use actix_web::test::{call_service, init_service, read_body, TestRequest};
+use actix_web::{route, routes, scope, App, HttpResponse, Responder};
+
+fn is_admin(ctx: &GuardContext) -> bool {
+    ctx.head().headers().contains_key("x-admin")
+}
+
+/// The number of users, for admins only.
+#[route("/count", method = "GET", method = "HEAD", guard = "is_admin")]
+async fn count() -> impl Responder {
+    HttpResponse::Ok().body("2")
+}
+
+/// Reports that the service is up.
+#[routes]
+#[get("/health")]
+#[get("/healthz")]
+async fn health() -> impl Responder {
+    HttpResponse::NoContent()
+}
+
+#[scope("/api")]
+mod api {
+    use actix_web::{get, web, HttpResponse, Responder};
+
+    #[get("/users/{id}")]
+    pub async fn user(id: web::Path<u32>) -> impl Responder {
+        HttpResponse::Ok().body(format!("user {}", id))
+    }
+}
+
+/// Sends a request to the routes on a new actix system and returns the
+/// status and body of the response.
+#[actix_web::main]
+pub async fn request(method: &'static str, path: &'static str, admin: bool) -> (u16, String) {
+    let app = init_service(App::new().service(count).service(health).service(api::user)).await;
+    let mut req = TestRequest::default()
+        .method(Method::from_bytes(method.as_bytes()).unwrap())
+        .uri(path);
+    if admin {
+        req = req.insert_header(("x-admin", "1"));
+    }
+    let res = call_service(&app, req.to_request()).await;
+    let status = res.status().as_u16();
+    let body = read_body(res).await;
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
@@ -0,0 +1,35 @@
+//! `#[derive(Describe)]`, which lists the fields of a struct and rejects
+//! anything else through `proc-macro-error`.
+
+use proc_macro::TokenStream;
+use proc_macro_error::{abort, proc_macro_error};
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+#[proc_macro_derive(Describe)]
+#[proc_macro_error]
+pub fn derive_describe(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => abort!(input.ident, "`Describe` can only be derived for structs"),
+    };
+    let names: Vec<String> = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| field.ident.as_ref().unwrap().to_string())
+            .collect(),
+        _ => abort!(fields, "`Describe` needs named fields"),
+    };
+    let description = format!("{} {{ {} }}", input.ident, names.join(", "));
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    TokenStream::from(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn describe() -> &'static str {
+                #description
+            }
+        }
+    })
+}
@@ -0,0 +1,69 @@
+//! Futures of the project built with pin-project 1.
+
+use pin_project::{pin_project, pinned_drop};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// One of two futures with the same output.
+#[pin_project(project = EitherProj, project_ref = EitherProjRef)]
+pub enum Either<A, B> {
+    Left(#[pin] A),
+    Right(#[pin] B),
+}
+
+impl<A, B> Either<A, B> {
+    pub fn is_left(self: Pin<&Self>) -> bool {
+        matches!(self.project_ref(), EitherProjRef::Left(_))
+    }
+}
+
+impl<A: Future, B: Future<Output = A::Output>> Future for Either<A, B> {
+    type Output = A::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match self.project() {
+            EitherProj::Left(future) => future.poll(cx),
+            EitherProj::Right(future) => future.poll(cx),
+        }
+    }
+}
+
+/// Resolves to the output of `inner`, and tells `on_drop` whether it had
+/// finished when it is dropped.
+#[pin_project(PinnedDrop)]
+pub struct Guarded<F, D: FnMut(bool)> {
+    #[pin]
+    inner: F,
+    finished: bool,
+    on_drop: D,
+}
+
+impl<F, D: FnMut(bool)> Guarded<F, D> {
+    pub fn new(inner: F, on_drop: D) -> Self {
+        Guarded {
+            inner,
+            finished: false,
+            on_drop,
+        }
+    }
+}
+
+impl<F: Future, D: FnMut(bool)> Future for Guarded<F, D> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        let output = this.inner.poll(cx);
+        *this.finished = output.is_ready();
+        output
+    }
+}
+
+#[pinned_drop]
+impl<F, D: FnMut(bool)> PinnedDrop for Guarded<F, D> {
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        (this.on_drop)(*this.finished);
+    }
+}
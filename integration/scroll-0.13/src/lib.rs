@@ -0,0 +1,20 @@
+//! Records of the project's binary format, read and written with scroll 0.13.
+
+pub use scroll;
+use scroll::{Pread, Pwrite, SizeWith, BE};
+
+#[derive(Pread, Pwrite, SizeWith, Debug, Clone, Copy, PartialEq)]
+pub struct Version {
+    pub major: u8,
+    pub minor: u8,
+}
+
+/// A record with a body of any type, whose length is big-endian whatever the
+/// endianness of the rest.
+#[derive(Pread, Pwrite, Debug, PartialEq)]
+pub struct Record<T> {
+    pub version: Version,
+    #[scroll(ctx = BE)]
+    pub length: u32,
+    pub body: T,
+}
@@ -0,0 +1,8 @@
+//! A small user service written against every macro crate of watt-contrib,
+//! which the tests in `tests/` exercise. Its only purpose is to put all of
+//! the shims into one dependency graph and use them the way a project
+//! would, several of them on the same item.
+
+pub mod models;
+pub mod service;
+pub mod wire;
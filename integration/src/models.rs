@@ -0,0 +1,66 @@
+//! Data types, deriving the serde, strum, num, derivative, derive-new and
+//! enum-as-inner traits side by side.
+
+// num-derive 0.3 wraps its impls in a `const`, which rustc now lints.
+#![allow(non_local_definitions)]
+
+use derivative::Derivative;
+use derive_new::new;
+use enum_as_inner::EnumAsInner;
+use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use strum::{Display, EnumIter, EnumString};
+use watt_contrib_integration_derive::Describe;
+
+#[derive(
+    Serialize_repr,
+    Deserialize_repr,
+    Display,
+    EnumString,
+    EnumIter,
+    FromPrimitive,
+    ToPrimitive,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+)]
+#[repr(u8)]
+#[strum(serialize_all = "snake_case")]
+pub enum Role {
+    Member = 1,
+    Moderator = 2,
+    Admin = 3,
+}
+
+#[derive(Serialize, Deserialize, Describe, Debug, Clone, PartialEq, new)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: u32,
+    pub display_name: String,
+    #[new(value = "Role::Member")]
+    pub role: Role,
+    #[new(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, EnumAsInner, Debug, Clone, PartialEq)]
+#[serde(tag = "type", content = "data")]
+pub enum Event {
+    Joined(User),
+    Promoted(u32, Role),
+    Left(u32),
+}
+
+#[derive(Derivative, Clone)]
+#[derivative(Debug, Default, PartialEq)]
+pub struct Settings {
+    #[derivative(Default(value = "8080"))]
+    pub port: u16,
+    #[derivative(Default(value = "Role::Member"))]
+    pub default_role: Role,
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub token: String,
+}
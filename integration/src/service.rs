@@ -0,0 +1,93 @@
+//! An async service, implemented with async-trait and `#[instrument]` on
+//! the same methods, failing with thiserror errors.
+
+// pin-project 0.4 rejects `#[repr(packed)]` structs by borrowing each field
+// without using the borrow, which rustc now lints.
+#![allow(unused_must_use)]
+
+use crate::models::{Event, Role, User};
+use async_trait::async_trait;
+use pin_project::pin_project;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use thiserror::Error;
+use tracing::instrument;
+
+#[derive(Error, Debug)]
+pub enum ServiceError {
+    #[error("unknown user {0}")]
+    UnknownUser(u32),
+    #[error("user {id} already is {role}")]
+    AlreadyInRole { id: u32, role: Role },
+    #[error("invalid request")]
+    Invalid(#[from] serde_json::Error),
+}
+
+#[async_trait]
+pub trait Directory: Send + Sync {
+    async fn join(&self, user: User) -> Result<Event, ServiceError>;
+
+    async fn promote(&self, id: u32, role: Role) -> Result<Event, ServiceError>;
+
+    /// Joins the user in the JSON `request`.
+    async fn handle(&self, request: &str) -> Result<Event, ServiceError> {
+        let user: User = serde_json::from_str(request)?;
+        self.join(user).await
+    }
+}
+
+#[derive(Default)]
+pub struct InMemory {
+    users: Mutex<HashMap<u32, User>>,
+}
+
+#[async_trait]
+impl Directory for InMemory {
+    #[instrument(skip(self))]
+    async fn join(&self, user: User) -> Result<Event, ServiceError> {
+        let mut users = self.users.lock().unwrap();
+        users.insert(user.id, user.clone());
+        Ok(Event::Joined(user))
+    }
+
+    #[instrument(skip(self))]
+    async fn promote(&self, id: u32, role: Role) -> Result<Event, ServiceError> {
+        let mut users = self.users.lock().unwrap();
+        let user = users.get_mut(&id).ok_or(ServiceError::UnknownUser(id))?;
+        if user.role == role {
+            return Err(ServiceError::AlreadyInRole { id, role });
+        }
+        user.role = role;
+        Ok(Event::Promoted(id, role))
+    }
+}
+
+/// Resolves to the output of `inner` and the number of times it was polled.
+#[pin_project]
+pub struct Counted<F> {
+    #[pin]
+    inner: F,
+    polls: u32,
+}
+
+impl<F> Counted<F> {
+    pub fn new(inner: F) -> Self {
+        Counted { inner, polls: 0 }
+    }
+}
+
+impl<F: Future> Future for Counted<F> {
+    type Output = (F::Output, u32);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+        *this.polls += 1;
+        match this.inner.poll(cx) {
+            Poll::Ready(output) => Poll::Ready((output, *this.polls)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
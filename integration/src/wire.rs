@@ -0,0 +1,13 @@
+//! A binary header read and written with scroll.
+
+use scroll::{Pread, Pwrite, SizeWith};
+
+pub const MAGIC: u32 = 0x7761_7474;
+
+#[derive(Pread, Pwrite, SizeWith, Debug, Clone, Copy, PartialEq)]
+pub struct Header {
+    pub magic: u32,
+    pub version: u16,
+    pub flags: u16,
+    pub length: u64,
+}
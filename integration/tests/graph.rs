@@ -0,0 +1,69 @@
+//! Checks that every crate of the repository is patched in and that cargo
+//! actually uses the patches, which it otherwise only warns about.
+//!
+//! The crates that only the opt-in wasm-bindgen member uses may be missing
+//! from the graph when that member is left out of the workspace.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const WASM_BINDGEN_MEMBER: &str = "watt-contrib-integration-wasm-bindgen";
+const WASM_BINDGEN_ONLY: &[&str] = &["wasm-bindgen-macro", "wasm-bindgen-test-macro"];
+
+#[test]
+fn every_crate_is_patched_in() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let repo = root.parent().unwrap();
+
+    let mut hosted = Vec::new();
+    for entry in fs::read_dir(repo).unwrap() {
+        let path = entry.unwrap().path();
+        if path.join("Cargo.watt.lock").is_file() {
+            hosted.push(path.file_name().unwrap().to_string_lossy().into_owned());
+        }
+    }
+    hosted.sort();
+    let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    let mut patched: Vec<&str> = manifest
+        .lines()
+        .filter_map(|line| line.split("path = \"../").nth(1)?.split('"').next())
+        .collect();
+    patched.sort();
+    assert_eq!(patched, hosted);
+
+    let output = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .args([
+            "tree",
+            "--workspace",
+            "--prefix",
+            "none",
+            "--edges",
+            "normal,build,dev",
+        ])
+        .current_dir(root)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let tree = String::from_utf8(output.stdout).unwrap();
+    let wasm_bindgen = tree
+        .lines()
+        .any(|line| line.starts_with(&format!("{} ", WASM_BINDGEN_MEMBER)));
+    for dir in &hosted {
+        if !wasm_bindgen && WASM_BINDGEN_ONLY.contains(&dir.as_str()) {
+            continue;
+        }
+        let path = format!("({})", repo.join(dir).display());
+        assert!(
+            tree.lines().any(|line| line.contains(&path)),
+            "{} is patched in but not used:\n{}",
+            dir,
+            tree,
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Expands different derives of the same shims one after another in this one
+//! rustc process. watt runs every expansion of a module in a thread on the
+//! same instance without resetting its memory, so each of them has to come out
+//! the same as on a fresh instance, whatever the expansions before it were.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use strum::{
+    EnumCount, EnumIter, EnumString, EnumVariantNames, IntoEnumIterator, IntoStaticStr,
+    VariantNames,
+};
+
+macro_rules! wide {
+    ($($field:ident)*) => {
+        #[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+        struct Wide {
+            $($field: u32,)*
+        }
+    };
+}
+
+wide!(a b c d e f g h i j k l m n o p q r s t u v w x y z);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct Limits {
+    max_size: u32,
+    #[serde(default)]
+    min_size: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Shape {
+    Circle { radius: u8 },
+    Empty,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Wrapper<T> {
+    #[serde(rename = "v")]
+    value: T,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Unit;
+
+#[derive(EnumString, IntoStaticStr, EnumIter, EnumCount, Debug, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+enum Planet {
+    Mercury,
+    Venus,
+    #[strum(serialize = "terra")]
+    Earth,
+}
+
+#[derive(EnumVariantNames, EnumIter, EnumCount, Debug, PartialEq)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+enum Level {
+    Low,
+    High,
+}
+
+#[derive(EnumString, IntoStaticStr, Debug, PartialEq)]
+enum Answer {
+    #[strum(serialize = "y", serialize = "yes")]
+    Yes,
+    No,
+}
+
+#[test]
+fn serde_derive() {
+    let wide = Wide {
+        z: 26,
+        ..Wide::default()
+    };
+    let value = serde_json::to_value(&wide).unwrap();
+    assert_eq!(value.as_object().unwrap().len(), 26);
+    assert_eq!(value["z"], 26);
+    assert_eq!(serde_json::from_value::<Wide>(value).unwrap(), wide);
+
+    let limits = Limits {
+        max_size: 10,
+        min_size: 0,
+    };
+    assert_eq!(
+        serde_json::to_value(&limits).unwrap(),
+        json!({"MAX_SIZE": 10, "MIN_SIZE": 0})
+    );
+    assert_eq!(
+        serde_json::from_value::<Limits>(json!({"MAX_SIZE": 10})).unwrap(),
+        limits
+    );
+    assert!(serde_json::from_value::<Limits>(json!({"max_size": 10})).is_err());
+
+    let circle = Shape::Circle { radius: 2 };
+    assert_eq!(
+        serde_json::to_value(&circle).unwrap(),
+        json!({"kind": "circle", "radius": 2})
+    );
+    assert_eq!(
+        serde_json::from_value::<Shape>(json!({"kind": "empty"})).unwrap(),
+        Shape::Empty
+    );
+
+    let wrapper = Wrapper { value: Unit };
+    assert_eq!(serde_json::to_value(&wrapper).unwrap(), json!({"v": null}));
+    assert_eq!(
+        serde_json::from_value::<Wrapper<Shape>>(json!({"v": {"kind": "empty"}})).unwrap(),
+        Wrapper {
+            value: Shape::Empty
+        }
+    );
+}
+
+#[test]
+fn strum_macros() {
+    assert_eq!("venus".parse::<Planet>().unwrap(), Planet::Venus);
+    assert_eq!("terra".parse::<Planet>().unwrap(), Planet::Earth);
+    assert!("earth".parse::<Planet>().is_err());
+    let names: Vec<&'static str> = Planet::iter().map(Into::into).collect();
+    assert_eq!(names, ["mercury", "venus", "terra"]);
+    assert_eq!(Planet::COUNT, 3);
+
+    assert_eq!(Level::VARIANTS, ["LOW", "HIGH"]);
+    assert_eq!(Level::iter().collect::<Vec<_>>(), [Level::Low, Level::High]);
+    assert_eq!(Level::COUNT, 2);
+
+    assert_eq!("yes".parse::<Answer>().unwrap(), Answer::Yes);
+    assert_eq!("y".parse::<Answer>().unwrap(), Answer::Yes);
+    assert_eq!(<&'static str>::from(Answer::No), "No");
+}
@@ -0,0 +1,207 @@
+use derive_new_0_7::new;
+use enum_as_inner_0_6::EnumAsInner;
+use num_traits::{FromPrimitive, ToPrimitive};
+use scroll::ctx::SizeWith;
+use scroll::{Pread, Pwrite, LE};
+use strum::IntoEnumIterator;
+use strum_0_27::{VariantArray, VariantNames};
+use strum_macros_0_27::{EnumIs, EnumTable, EnumTryAs, FromRepr, VariantArray, VariantNames};
+use watt_contrib_integration::models::{Event, Role, Settings, User};
+use watt_contrib_integration::wire::{Header, MAGIC};
+
+#[test]
+fn json() {
+    let mut user = User::new(1, "Ada".to_owned());
+    let json = serde_json::to_string(&user).unwrap();
+    assert_eq!(json, r#"{"id":1,"displayName":"Ada","role":1}"#);
+    assert_eq!(serde_json::from_str::<User>(&json).unwrap(), user);
+
+    user.tags.push("founder".to_owned());
+    let event = Event::Joined(user);
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(
+        json,
+        r#"{"type":"Joined","data":{"id":1,"displayName":"Ada","role":1,"tags":["founder"]}}"#
+    );
+    assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+
+    let event: Event = serde_json::from_str(r#"{"type":"Promoted","data":[1,3]}"#).unwrap();
+    assert_eq!(event, Event::Promoted(1, Role::Admin));
+    assert!(serde_json::from_str::<Role>("4").is_err());
+}
+
+#[test]
+fn roles() {
+    assert_eq!(Role::Moderator.to_string(), "moderator");
+    assert_eq!("admin".parse::<Role>().unwrap(), Role::Admin);
+    assert!("root".parse::<Role>().is_err());
+    assert_eq!(
+        Role::iter().collect::<Vec<_>>(),
+        [Role::Member, Role::Moderator, Role::Admin]
+    );
+    assert_eq!(Role::from_u8(2), Some(Role::Moderator));
+    assert_eq!(Role::from_u8(0), None);
+    assert_eq!(Role::Admin.to_u8(), Some(3));
+}
+
+/// Derives strum 0.27's traits through `strum_0_27`, since `strum` is 0.19.
+#[derive(
+    FromRepr, EnumIs, VariantArray, VariantNames, EnumTable, Debug, Clone, Copy, PartialEq,
+)]
+#[strum(crate = "strum_0_27", serialize_all = "snake_case")]
+#[repr(u8)]
+enum Permission {
+    Read = 1,
+    Write,
+    ManageUsers,
+}
+
+#[derive(EnumIs, EnumTryAs, Debug)]
+enum Grant {
+    Role(Role),
+    User(u32, Permission),
+}
+
+#[test]
+fn permissions() {
+    assert_eq!(Permission::from_repr(2), Some(Permission::Write));
+    assert_eq!(Permission::from_repr(0), None);
+    assert!(Permission::ManageUsers.is_manage_users());
+    assert_eq!(
+        <Permission as VariantArray>::VARIANTS,
+        [Permission::Read, Permission::Write, Permission::ManageUsers]
+    );
+    assert_eq!(
+        <Permission as VariantNames>::VARIANTS,
+        ["read", "write", "manage_users"]
+    );
+
+    let mut granted = PermissionTable::filled(false);
+    granted[Permission::Read] = true;
+    assert_eq!(granted, PermissionTable::new(true, false, false));
+
+    let grant = Grant::User(1, Permission::Write);
+    assert!(grant.is_user());
+    assert_eq!(grant.try_as_user(), Some((1, Permission::Write)));
+    let grant = Grant::Role(Role::Admin);
+    assert!(grant.try_as_user_ref().is_none());
+    assert_eq!(grant.try_as_role(), Some(Role::Admin));
+}
+
+#[test]
+fn events() {
+    let event = Event::Left(4);
+    assert!(event.as_joined().is_none());
+    assert_eq!(event.as_left(), Some(&4));
+    assert_eq!(event.into_left().unwrap(), 4);
+}
+
+/// The accessors of enum-as-inner 0.6, for every kind of variant.
+#[derive(EnumAsInner, Debug, PartialEq)]
+enum Change<T> {
+    Reset,
+    Renamed(String),
+    Moved(u32, u32),
+    Tagged { id: u32, tag: T },
+}
+
+#[test]
+fn changes() {
+    assert!(Change::<()>::Reset.is_reset());
+    assert!(!Change::<()>::Reset.is_renamed());
+
+    let mut renamed = Change::<()>::Renamed("Ada".to_owned());
+    renamed.as_renamed_mut().unwrap().push_str(" L.");
+    assert_eq!(renamed.as_renamed().map(String::as_str), Some("Ada L."));
+    assert_eq!(renamed.into_renamed(), Ok("Ada L.".to_owned()));
+
+    let moved = Change::<()>::Moved(1, 2);
+    assert_eq!(moved.as_moved(), Some((&1, &2)));
+    assert_eq!(moved.into_renamed(), Err(Change::Moved(1, 2)));
+
+    let mut tagged = Change::Tagged { id: 3, tag: 'a' };
+    if let Some((_, tag)) = tagged.as_tagged_mut() {
+        *tag = 'b';
+    }
+    assert!(tagged.is_tagged());
+    assert_eq!(tagged.into_tagged(), Ok((3, 'b')));
+}
+
+/// The constructors of derive-new 0.7, with `into` and `into_iter` fields.
+#[derive(new, Debug, PartialEq)]
+struct Team {
+    #[new(into)]
+    name: String,
+    #[new(into_iter = "u32")]
+    members: Vec<u32>,
+}
+
+#[derive(new, Debug, PartialEq)]
+enum Invite {
+    Open,
+    Member {
+        #[new(into)]
+        email: String,
+        #[new(value = "Role::Member")]
+        role: Role,
+    },
+}
+
+#[test]
+fn constructors() {
+    assert_eq!(
+        Team::new("core", [1, 2]),
+        Team {
+            name: "core".to_owned(),
+            members: vec![1, 2],
+        }
+    );
+    assert!(Team::new("empty", None).members.is_empty());
+
+    assert_eq!(Invite::new_open(), Invite::Open);
+    assert_eq!(
+        Invite::new_member("ada@example.com"),
+        Invite::Member {
+            email: "ada@example.com".to_owned(),
+            role: Role::Member,
+        }
+    );
+}
+
+#[test]
+fn settings() {
+    let settings = Settings::default();
+    assert_eq!(settings.port, 8080);
+    assert_eq!(settings.default_role, Role::Member);
+
+    let secret = Settings {
+        token: "secret".to_owned(),
+        ..settings.clone()
+    };
+    assert_eq!(secret, settings);
+    assert_eq!(
+        format!("{:?}", secret),
+        "Settings { port: 8080, default_role: Member }"
+    );
+}
+
+#[test]
+fn describe() {
+    assert_eq!(User::describe(), "User { id, display_name, role, tags }");
+}
+
+#[test]
+fn header() {
+    let header = Header {
+        magic: MAGIC,
+        version: 2,
+        flags: 0x8001,
+        length: 1 << 40,
+    };
+    assert_eq!(Header::size_with(&LE), 16);
+    let mut bytes = [0; 16];
+    assert_eq!(bytes.pwrite_with(header, 0, LE).unwrap(), 16);
+    assert_eq!(&bytes[..4], b"ttaw");
+    assert_eq!(bytes.pread_with::<Header>(0, LE).unwrap(), header);
+    assert!(bytes[..15].pread_with::<Header>(0, LE).is_err());
+}
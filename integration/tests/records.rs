@@ -0,0 +1,20 @@
+use watt_contrib_integration_scroll_0_13::scroll::{Pread, Pwrite, BE, LE};
+use watt_contrib_integration_scroll_0_13::{Record, Version};
+
+#[test]
+fn records() {
+    let record = Record {
+        version: Version { major: 1, minor: 2 },
+        length: 4,
+        body: 0x0102_0304_u32,
+    };
+    let mut bytes = [0; 10];
+    assert_eq!(bytes.pwrite_with(&record, 0, LE).unwrap(), 10);
+    assert_eq!(bytes, [1, 2, 0, 0, 0, 4, 4, 3, 2, 1]);
+    assert_eq!(bytes.pread_with::<Record<u32>>(0, LE).unwrap(), record);
+
+    let nested: Record<Version> = bytes[..8].pread_with(0, BE).unwrap();
+    assert_eq!(nested.length, 4);
+    assert_eq!(nested.body, Version { major: 4, minor: 3 });
+    assert!(bytes[..7].pread_with::<Record<Version>>(0, BE).is_err());
+}
@@ -0,0 +1,116 @@
+use actix::prelude::*;
+use actix_web::{get, test, web, App, Responder};
+use ctor::ctor;
+use futures::future::{self, FutureExt};
+use futures::{join, select, select_biased, try_join};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+#[ctor]
+fn start() {
+    STARTED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[ctor]
+static NAMES: HashMap<u32, &'static str> = {
+    let mut names = HashMap::new();
+    names.insert(1, "Ada");
+    names
+};
+
+#[test]
+fn constructors() {
+    assert_eq!(STARTED.load(Ordering::SeqCst), 1);
+    assert_eq!(NAMES[&1], "Ada");
+}
+
+#[rustversion::since(1.39)]
+const ASYNC_AWAIT: bool = true;
+#[rustversion::before(1.39)]
+const ASYNC_AWAIT: bool = false;
+
+#[rustversion::stable]
+fn channel() -> &'static str {
+    "stable"
+}
+
+#[rustversion::not(stable)]
+#[rustversion::attr(nightly, inline(never))]
+fn channel() -> &'static str {
+    "beta or nightly"
+}
+
+#[test]
+#[allow(clippy::assertions_on_constants)]
+fn versions() {
+    assert!(ASYNC_AWAIT);
+    assert!(!channel().is_empty());
+}
+
+#[test]
+fn futures_macros() {
+    futures::executor::block_on(async {
+        let (a, b) = join!(async { 1 }, async { 2 });
+        assert_eq!(a + b, 3);
+
+        let ok = try_join!(async { Ok::<_, ()>(1) }, async { Ok(2) });
+        assert_eq!(ok, Ok((1, 2)));
+        let err = try_join!(async { Ok::<u32, _>(1) }, async { Err::<u32, _>("no") });
+        assert_eq!(err, Err("no"));
+
+        let mut ready = future::ready(4).fuse();
+        let mut pending = future::pending::<u32>().fuse();
+        let value = select! {
+            value = ready => value,
+            value = pending => value,
+        };
+        assert_eq!(value, 4);
+
+        let mut first = future::ready(5).fuse();
+        let mut second = future::ready(6).fuse();
+        let value = select_biased! {
+            value = first => value,
+            value = second => value,
+        };
+        assert_eq!(value, 5);
+    });
+}
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Ping(usize);
+
+struct Counter(usize);
+
+impl Actor for Counter {
+    type Context = Context<Self>;
+}
+
+impl Handler<Ping> for Counter {
+    type Result = usize;
+
+    fn handle(&mut self, Ping(n): Ping, _: &mut Context<Self>) -> usize {
+        self.0 += n;
+        self.0
+    }
+}
+
+#[get("/users/{id}")]
+async fn user(id: web::Path<u32>) -> impl Responder {
+    format!("user {}", id)
+}
+
+#[test]
+fn actors_and_routes() {
+    System::new("test").block_on(async {
+        let counter = Counter(0).start();
+        counter.send(Ping(2)).await.unwrap();
+        assert_eq!(counter.send(Ping(3)).await.unwrap(), 5);
+
+        let mut app = test::init_service(App::new().service(user)).await;
+        let request = test::TestRequest::get().uri("/users/7").to_request();
+        assert_eq!(test::read_response(&mut app, request).await, "user 7");
+    });
+}
@@ -0,0 +1,178 @@
+use std::error::Error as _;
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use watt_contrib_integration::models::{Role, User};
+use watt_contrib_integration::service::{Counted, Directory, InMemory, ServiceError};
+use watt_contrib_integration_actix_web_4::request;
+use watt_contrib_integration_pin_project_1::{Either, Guarded};
+
+#[tokio::test]
+async fn join_and_promote() {
+    let directory = InMemory::default();
+    let event = directory
+        .join(User::new(1, "Ada".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(event.as_joined().map(|user| user.role), Some(Role::Member));
+
+    let event = directory.promote(1, Role::Admin).await.unwrap();
+    assert_eq!(event.as_promoted(), Some((&1, &Role::Admin)));
+
+    let err = directory.promote(1, Role::Admin).await.unwrap_err();
+    assert_eq!(err.to_string(), "user 1 already is admin");
+    let err = directory.promote(2, Role::Admin).await.unwrap_err();
+    assert!(matches!(err, ServiceError::UnknownUser(2)));
+}
+
+/// The `Error` impl needs `E: Error + 'static`, which thiserror infers.
+#[derive(thiserror::Error, Debug)]
+enum BatchError<E> {
+    #[error("step {0} failed")]
+    Step(usize, #[source] E),
+    #[error(transparent)]
+    Service(#[from] ServiceError),
+}
+
+#[tokio::test]
+async fn batch_errors() {
+    let directory = InMemory::default();
+    let err = directory.promote(3, Role::Admin).await.unwrap_err();
+    let step = BatchError::Step(1, err);
+    assert_eq!(step.to_string(), "step 1 failed");
+    assert_eq!(step.source().unwrap().to_string(), "unknown user 3");
+
+    let err = directory.promote(4, Role::Admin).await.unwrap_err();
+    let service = BatchError::<std::io::Error>::from(err);
+    assert_eq!(service.to_string(), "unknown user 4");
+    assert!(service.source().is_none());
+}
+
+#[tokio::test]
+async fn default_method() {
+    let directory = InMemory::default();
+    let event = directory
+        .handle(r#"{"id":3,"displayName":"Grace","role":2}"#)
+        .await
+        .unwrap();
+    assert_eq!(event.into_joined().unwrap().role, Role::Moderator);
+
+    let err = directory.handle("{").await.unwrap_err();
+    assert_eq!(err.to_string(), "invalid request");
+    assert!(err.source().is_some());
+}
+
+#[tokio::test]
+async fn pinned_future() {
+    let (value, polls) = Counted::new(async {
+        // tokio 0.2 marks yield_now itself `#[must_use]`, for the future.
+        let _ = tokio::task::yield_now().await;
+        7
+    })
+    .await;
+    assert_eq!(value, 7);
+    assert_eq!(polls, 2);
+}
+
+/// Records the names of the spans that are created.
+struct Recorder(Arc<Mutex<Vec<&'static str>>>);
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+        let mut spans = self.0.lock().unwrap();
+        spans.push(span.metadata().name());
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[tokio::main(basic_scheduler)]
+async fn onboard(directory: &InMemory) -> Result<(), ServiceError> {
+    directory.join(User::new(1, "Ada".to_owned())).await?;
+    directory.promote(1, Role::Moderator).await?;
+    Ok(())
+}
+
+#[test]
+fn instrumented_spans() {
+    let spans = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Recorder(Arc::clone(&spans));
+    tracing::subscriber::with_default(recorder, || onboard(&InMemory::default())).unwrap();
+    assert_eq!(*spans.lock().unwrap(), ["join", "promote"]);
+}
+
+#[tokio1::test(crate = "tokio1", flavor = "multi_thread", worker_threads = 2)]
+async fn tokio_1() {
+    let directory = Arc::new(InMemory::default());
+    let joined = tokio1::spawn({
+        let directory = Arc::clone(&directory);
+        async move { directory.join(User::new(4, "Edsger".to_owned())).await }
+    });
+    joined.await.unwrap().unwrap();
+    directory.promote(4, Role::Admin).await.unwrap();
+}
+
+#[tokio1::test(crate = "tokio1")]
+async fn pin_project_1() {
+    let finished = Arc::new(Mutex::new(Vec::new()));
+    let guard = |finished: &Arc<Mutex<Vec<bool>>>| {
+        let finished = Arc::clone(finished);
+        move |done| finished.lock().unwrap().push(done)
+    };
+
+    let directory = InMemory::default();
+    directory
+        .join(User::new(5, "Grace".to_owned()))
+        .await
+        .unwrap();
+    // Both sides are the boxed future of an async-trait method.
+    let promote = |admin| {
+        if admin {
+            Either::Left(directory.promote(5, Role::Admin))
+        } else {
+            Either::Right(directory.promote(5, Role::Moderator))
+        }
+    };
+
+    let promotion = promote(true);
+    tokio1::pin!(promotion);
+    assert!(promotion.as_ref().is_left());
+    Guarded::new(promotion, guard(&finished)).await.unwrap();
+
+    let never = Guarded::new(std::future::pending::<()>(), guard(&finished));
+    tokio1::select! {
+        () = never => unreachable!(),
+        event = promote(false) => {
+            event.unwrap();
+        }
+    }
+    assert_eq!(*finished.lock().unwrap(), [true, false]);
+}
+
+#[test]
+fn actix_web_4() {
+    assert_eq!(request("GET", "/count", true), (200, "2".to_owned()));
+    assert_eq!(request("HEAD", "/count", true).0, 200);
+    assert_eq!(request("GET", "/count", false).0, 404);
+    assert_eq!(request("POST", "/count", true).0, 404);
+    assert_eq!(request("GET", "/health", false).0, 204);
+    assert_eq!(request("GET", "/healthz", false).0, 204);
+    assert_eq!(
+        request("GET", "/api/users/7", false),
+        (200, "user 7".to_owned())
+    );
+    assert_eq!(request("GET", "/users/7", false).0, 404);
+}
@@ -0,0 +1,26 @@
+//! Bindings of the project built with wasm-bindgen.
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[wasm_bindgen]
+pub struct Tally {
+    count: u32,
+}
+
+#[wasm_bindgen]
+impl Tally {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Tally {
+        Tally { count: 0 }
+    }
+
+    pub fn bump(&mut self, by: u32) -> u32 {
+        self.count = add(self.count, by);
+        self.count
+    }
+}
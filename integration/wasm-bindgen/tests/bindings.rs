@@ -0,0 +1,16 @@
+use wasm_bindgen_test::wasm_bindgen_test;
+use watt_contrib_integration_wasm_bindgen::{add, Tally};
+
+#[test]
+fn bindings_outside_wasm() {
+    let mut tally = Tally::new();
+    tally.bump(2);
+    assert_eq!(tally.bump(3), 5);
+}
+
+// Only run by `wasm-bindgen-test-runner`, this checks that the test
+// attribute expands.
+#[wasm_bindgen_test]
+fn in_wasm() {
+    assert_eq!(add(1, 2), 3);
+}
@@ -1,5 +1,10 @@
-static WASM: &[u8] = include_bytes!("num-derive.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#![cfg_attr(feature = "native", recursion_limit = "512")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("num-derive.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[doc = " Derives [`num_traits::FromPrimitive`][from] for simple enums and newtypes."]
 #[doc = ""]
 #[doc = " [from]: https://docs.rs/num-traits/0.2/num_traits/cast/trait.FromPrimitive.html"]
@@ -52,6 +57,7 @@ static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
 pub fn from_primitive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(from_primitive), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Derives [`num_traits::ToPrimitive`][to] for simple enums and newtypes."]
 #[doc = ""]
 #[doc = " [to]: https://docs.rs/num-traits/0.2/num_traits/cast/trait.ToPrimitive.html"]
@@ -104,6 +110,7 @@ pub fn from_primitive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn to_primitive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(to_primitive), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Derives [`num_traits::NumOps`][num_ops] for newtypes.  The inner type must already implement"]
 #[doc = " `NumOps`."]
 #[doc = ""]
@@ -116,6 +123,7 @@ pub fn to_primitive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn num_ops(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(num_ops), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Derives [`num_traits::NumCast`][num_cast] for newtypes.  The inner type must already implement"]
 #[doc = " `NumCast`."]
 #[doc = ""]
@@ -124,6 +132,7 @@ pub fn num_ops(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn num_cast(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(num_cast), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Derives [`num_traits::Zero`][zero] for newtypes.  The inner type must already implement `Zero`."]
 #[doc = ""]
 #[doc = " [zero]: https://docs.rs/num-traits/0.2/num_traits/identities/trait.Zero.html"]
@@ -131,6 +140,7 @@ pub fn num_cast(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn zero(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(zero), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Derives [`num_traits::One`][one] for newtypes.  The inner type must already implement `One`."]
 #[doc = ""]
 #[doc = " [one]: https://docs.rs/num-traits/0.2/num_traits/identities/trait.One.html"]
@@ -138,6 +148,7 @@ pub fn zero(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn one(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(one), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Derives [`num_traits::Num`][num] for newtypes.  The inner type must already implement `Num`."]
 #[doc = ""]
 #[doc = " [num]: https://docs.rs/num-traits/0.2/num_traits/trait.Num.html"]
@@ -145,6 +156,7 @@ pub fn one(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn num(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(num), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Derives [`num_traits::Float`][float] for newtypes.  The inner type must already implement"]
 #[doc = " `Float`."]
 #[doc = ""]
@@ -153,3 +165,5 @@ pub fn num(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn float(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(float), input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,212 @@
+use pin_project::{pin_project, pinned_drop, UnsafeUnpin};
+use std::cell::Cell;
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+
+fn assert_unpin<T: Unpin>() {}
+
+/// Fails to compile if `$ty` is `Unpin`, since both impls then apply.
+macro_rules! assert_not_unpin {
+    ($ty:ty) => {{
+        trait AmbiguousIfUnpin<A> {
+            fn some_item() {}
+        }
+        impl<T: ?Sized> AmbiguousIfUnpin<()> for T {}
+        impl<T: ?Sized + Unpin> AmbiguousIfUnpin<u8> for T {}
+        let _ = <$ty as AmbiguousIfUnpin<_>>::some_item;
+    }};
+}
+
+#[pin_project(project = StructProj, project_ref = StructProjRef, project_replace = StructProjOwn)]
+struct Struct<T, U> {
+    #[pin]
+    pinned: T,
+    unpinned: U,
+}
+
+#[test]
+fn struct_projections() {
+    let mut s = Box::pin(Struct {
+        pinned: 1,
+        unpinned: "a",
+    });
+
+    let StructProj { pinned, unpinned } = s.as_mut().project();
+    let _: Pin<&mut i32> = pinned;
+    *unpinned = "b";
+
+    let StructProjRef { pinned, unpinned } = s.as_ref().project_ref();
+    let _: Pin<&i32> = pinned;
+    assert_eq!(*unpinned, "b");
+
+    let StructProjOwn { pinned, unpinned } = s.as_mut().project_replace(Struct {
+        pinned: 2,
+        unpinned: "c",
+    });
+    let _: std::marker::PhantomData<i32> = pinned;
+    assert_eq!(unpinned, "b");
+    assert_eq!(s.pinned, 2);
+    assert_eq!(s.unpinned, "c");
+}
+
+#[pin_project]
+struct TupleStruct<T, U>(#[pin] T, U);
+
+#[test]
+fn tuple_struct_projections() {
+    let mut s = Box::pin(TupleStruct(1, 2));
+    let this = s.as_mut().project();
+    let _: Pin<&mut i32> = this.0;
+    *this.1 = 3;
+    let this = s.as_ref().project_ref();
+    assert_eq!(*this.0, 1);
+    assert_eq!(*this.1, 3);
+}
+
+#[pin_project(project = EnumProj, project_ref = EnumProjRef, project_replace = EnumProjOwn)]
+enum Enum<T, U> {
+    Tuple(#[pin] T, U),
+    Struct {
+        #[pin]
+        pinned: T,
+        unpinned: U,
+    },
+    Unit,
+}
+
+#[test]
+fn enum_projections() {
+    let mut e = Box::pin(Enum::Tuple(1, 2));
+    match e.as_mut().project() {
+        EnumProj::Tuple(pinned, unpinned) => {
+            let _: Pin<&mut i32> = pinned;
+            *unpinned = 3;
+        }
+        EnumProj::Struct { .. } | EnumProj::Unit => unreachable!(),
+    }
+    match e.as_ref().project_ref() {
+        EnumProjRef::Tuple(pinned, unpinned) => assert_eq!((*pinned, *unpinned), (1, 3)),
+        EnumProjRef::Struct { .. } | EnumProjRef::Unit => unreachable!(),
+    }
+
+    let old = e.as_mut().project_replace(Enum::Struct {
+        pinned: 4,
+        unpinned: 5,
+    });
+    assert!(matches!(old, EnumProjOwn::Tuple(_, 3)));
+    match e.as_mut().project() {
+        EnumProj::Struct { pinned, unpinned } => assert_eq!((*pinned, *unpinned), (4, 5)),
+        EnumProj::Tuple(..) | EnumProj::Unit => unreachable!(),
+    }
+
+    let old = e.as_mut().project_replace(Enum::Unit);
+    assert!(matches!(old, EnumProjOwn::Struct { unpinned: 5, .. }));
+    assert!(matches!(e.as_ref().project_ref(), EnumProjRef::Unit));
+}
+
+#[test]
+fn unpin_follows_pinned_fields() {
+    assert_unpin::<Struct<(), PhantomPinned>>();
+    assert_unpin::<Enum<(), PhantomPinned>>();
+    assert_not_unpin!(Struct<PhantomPinned, ()>);
+    assert_not_unpin!(Enum<PhantomPinned, ()>);
+}
+
+#[pin_project(!Unpin)]
+struct NotUnpin<T> {
+    #[pin]
+    pinned: T,
+}
+
+#[test]
+fn not_unpin() {
+    assert_not_unpin!(NotUnpin<()>);
+    let mut s = Box::pin(NotUnpin { pinned: 1 });
+    let _: Pin<&mut i32> = s.as_mut().project().pinned;
+}
+
+#[pin_project(UnsafeUnpin)]
+struct UnsafeUnpinStruct<T, U> {
+    #[pin]
+    pinned: T,
+    unpinned: U,
+}
+
+// SAFETY: Unpin whenever `T` is, which is the only pinned field, regardless
+// of `U`.
+unsafe impl<T: Unpin, U> UnsafeUnpin for UnsafeUnpinStruct<T, U> {}
+
+#[pin_project(UnsafeUnpin)]
+struct NeverUnpin<T> {
+    #[pin]
+    pinned: T,
+}
+
+#[test]
+fn unsafe_unpin() {
+    assert_unpin::<UnsafeUnpinStruct<(), PhantomPinned>>();
+    assert_not_unpin!(UnsafeUnpinStruct<PhantomPinned, ()>);
+    // Without an impl of `UnsafeUnpin`, the struct is never `Unpin`.
+    assert_not_unpin!(NeverUnpin<()>);
+
+    let mut s = Box::pin(UnsafeUnpinStruct {
+        pinned: 1,
+        unpinned: 2,
+    });
+    *s.as_mut().project().unpinned = 3;
+    assert_eq!(s.unpinned, 3);
+}
+
+#[pin_project(PinnedDrop)]
+struct Dropped<'a> {
+    #[pin]
+    pinned: u8,
+    drops: &'a Cell<Vec<u8>>,
+}
+
+#[pinned_drop]
+impl PinnedDrop for Dropped<'_> {
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        let mut drops = this.drops.take();
+        drops.push(*this.pinned);
+        this.drops.set(drops);
+    }
+}
+
+#[pin_project(PinnedDrop, project = DroppedEnumProj)]
+enum DroppedEnum<'a> {
+    Counted(&'a Cell<usize>),
+}
+
+#[pinned_drop]
+impl PinnedDrop for DroppedEnum<'_> {
+    fn drop(self: Pin<&mut Self>) {
+        let DroppedEnumProj::Counted(drops) = self.project();
+        drops.set(drops.get() + 1);
+    }
+}
+
+#[test]
+fn pinned_drop() {
+    let drops = Cell::new(Vec::new());
+    drop(Box::pin(Dropped {
+        pinned: 1,
+        drops: &drops,
+    }));
+    {
+        let _first = Box::pin(Dropped {
+            pinned: 2,
+            drops: &drops,
+        });
+        let _second = Box::pin(Dropped {
+            pinned: 3,
+            drops: &drops,
+        });
+    }
+    assert_eq!(drops.take(), [1, 3, 2]);
+
+    let drops = Cell::new(0);
+    drop(Box::pin(DroppedEnum::Counted(&drops)));
+    assert_eq!(drops.get(), 1);
+}
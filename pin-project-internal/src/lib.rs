@@ -1,5 +1,9 @@
-static WASM: &[u8] = include_bytes!("pin-project-internal.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("pin-project-internal.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[doc = " An attribute that creates projection types covering all the fields of"]
 #[doc = " struct or enum."]
 #[doc = ""]
@@ -425,6 +429,7 @@ pub fn pin_project(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(pin_project), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " An attribute for annotating an impl block that implements [`Drop`]."]
 #[doc = ""]
 #[doc = " This attribute is only needed when you wish to provide a [`Drop`]"]
@@ -506,6 +511,7 @@ pub fn pinned_drop(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(pinned_drop), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " (deprecated) An attribute to provide way to refer to the projected type returned by"]
 #[doc = " `project` method."]
 #[doc = ""]
@@ -676,6 +682,7 @@ pub fn project(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(project), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " (deprecated) An attribute to provide way to refer to the projected type returned by"]
 #[doc = " `project_ref` method."]
 #[doc = ""]
@@ -707,6 +714,7 @@ pub fn project_ref(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(project_ref), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " (deprecated) An attribute to provide way to refer to the projected type returned by"]
 #[doc = " `project_replace` method."]
 #[doc = ""]
@@ -738,8 +746,11 @@ pub fn project_replace(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(project_replace), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc(hidden)]
 #[proc_macro_derive(__PinProjectInternalDerive, attributes(pin))]
 pub fn __pin_project_internal_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(__pin_project_internal_derive), input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -1,4 +1,7 @@
 fn main() {
+    watt_contrib_build::check_entry_points();
+    watt_contrib_build::write_snapshot();
+
     if version_check::is_max_version("1.36.0").unwrap_or(false) {
         println!("cargo:rustc-cfg=always_assert_unwind");
     }
@@ -1,5 +1,9 @@
-static WASM: &[u8] = include_bytes!("proc-macro-error-attr.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("proc-macro-error-attr.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn proc_macro_error(
     args: proc_macro::TokenStream,
@@ -7,3 +11,5 @@ pub fn proc_macro_error(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(proc_macro_error), args, input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -1,5 +1,10 @@
-static WASM: &[u8] = include_bytes!("proc-macro-hack.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#![cfg_attr(feature = "native", recursion_limit = "512")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("proc-macro-hack.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn proc_macro_hack(
     args: proc_macro::TokenStream,
@@ -7,11 +12,13 @@ pub fn proc_macro_hack(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(proc_macro_hack), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc(hidden)]
 #[proc_macro_derive(ProcMacroHack)]
 pub fn enum_hack(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(enum_hack), input)
 }
+#[cfg(not(feature = "native"))]
 #[doc(hidden)]
 #[proc_macro_attribute]
 pub fn fake_call_site(
@@ -20,3 +27,5 @@ pub fn fake_call_site(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(fake_call_site), args, input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -7,6 +7,9 @@ use std::path::Path;
 use std::process::{self, Command};
 
 fn main() {
+    watt_contrib_build::check_entry_points();
+    watt_contrib_build::write_snapshot();
+
     let rustc = env::var_os("RUSTC").unwrap_or_else(|| OsString::from("rustc"));
     let output = match Command::new(&rustc).arg("--version").output() {
         Ok(output) => output,
@@ -1,5 +1,9 @@
-static WASM: &[u8] = include_bytes!("rustversion.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("rustversion.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn stable(
     args: proc_macro::TokenStream,
@@ -7,6 +11,7 @@ pub fn stable(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(stable), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn beta(
     args: proc_macro::TokenStream,
@@ -14,6 +19,7 @@ pub fn beta(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(beta), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn nightly(
     args: proc_macro::TokenStream,
@@ -21,6 +27,7 @@ pub fn nightly(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(nightly), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn since(
     args: proc_macro::TokenStream,
@@ -28,6 +35,7 @@ pub fn since(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(since), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn before(
     args: proc_macro::TokenStream,
@@ -35,6 +43,7 @@ pub fn before(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(before), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn not(
     args: proc_macro::TokenStream,
@@ -42,6 +51,7 @@ pub fn not(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(not), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn any(
     args: proc_macro::TokenStream,
@@ -49,6 +59,7 @@ pub fn any(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(any), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn all(
     args: proc_macro::TokenStream,
@@ -56,6 +67,7 @@ pub fn all(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(all), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn attr(
     args: proc_macro::TokenStream,
@@ -63,3 +75,5 @@ pub fn attr(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(attr), args, input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
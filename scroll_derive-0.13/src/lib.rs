@@ -0,0 +1,33 @@
+#![cfg_attr(feature = "native", recursion_limit = "1024")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("scroll_derive.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
+#[proc_macro_derive(Pread, attributes(scroll))]
+pub fn derive_pread(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(derive_pread), input)
+}
+#[cfg(not(feature = "native"))]
+#[proc_macro_derive(Pwrite, attributes(scroll))]
+pub fn derive_pwrite(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(derive_pwrite), input)
+}
+#[cfg(not(feature = "native"))]
+#[proc_macro_derive(SizeWith, attributes(scroll))]
+pub fn derive_sizewith(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(derive_sizewith), input)
+}
+#[cfg(not(feature = "native"))]
+#[proc_macro_derive(IOread, attributes(scroll))]
+pub fn derive_ioread(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(derive_ioread), input)
+}
+#[cfg(not(feature = "native"))]
+#[proc_macro_derive(IOwrite, attributes(scroll))]
+pub fn derive_iowrite(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(derive_iowrite), input)
+}
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,160 @@
+use scroll::ctx::{SizeWith, StrCtx};
+use scroll::{BE, Cread, Cwrite, LE, Pread, Pwrite};
+use scroll_derive::{IOread, IOwrite, Pread, Pwrite, SizeWith};
+
+#[derive(Debug, PartialEq, Clone, Copy, Pread, Pwrite, SizeWith, IOread, IOwrite)]
+struct Version {
+    major: u16,
+    minor: u16,
+}
+
+// Not `Copy`: the by-value `TryIntoCtx` of a generic struct calls itself
+// instead of the by-reference one, so it's written through a reference.
+#[derive(Debug, PartialEq, Pread, Pwrite)]
+struct Tagged<T> {
+    tag: u8,
+    value: T,
+}
+
+#[test]
+fn test_generic() {
+    let bytes = [7, 0xef, 0xbe, 0xad, 0xde];
+    let data: Tagged<u32> = bytes.pread_with(0, LE).unwrap();
+    assert_eq!(
+        data,
+        Tagged {
+            tag: 7,
+            value: 0xdeadbeef
+        }
+    );
+    let mut bytes2 = [0; 5];
+    assert_eq!(bytes2.pwrite_with(&data, 0, LE).unwrap(), 5);
+    assert_eq!(bytes2, bytes);
+
+    let data: Tagged<i16> = bytes.pread_with(0, BE).unwrap();
+    assert_eq!(
+        data,
+        Tagged {
+            tag: 7,
+            value: -0x1042
+        }
+    );
+}
+
+#[test]
+fn test_generic_nested() {
+    let bytes = [1, 2, 0, 3, 0];
+    let data: Tagged<Version> = bytes.pread_with(0, LE).unwrap();
+    assert_eq!(
+        data,
+        Tagged {
+            tag: 1,
+            value: Version { major: 2, minor: 3 }
+        }
+    );
+    let mut bytes2 = [0; 5];
+    bytes2.pwrite_with(&data, 0, LE).unwrap();
+    assert_eq!(bytes2, bytes);
+}
+
+#[derive(Debug, PartialEq, Pread, Pwrite, SizeWith)]
+struct Header {
+    magic: [u8; 4],
+    version: Version,
+    flags: u32,
+}
+
+#[test]
+fn test_nested() {
+    let header = Header {
+        magic: *b"scrl",
+        version: Version { major: 1, minor: 2 },
+        flags: 0x8000_0001,
+    };
+    assert_eq!(Header::size_with(&LE), 12);
+    let mut bytes = [0; 12];
+    assert_eq!(bytes.pwrite_with(&header, 0, BE).unwrap(), 12);
+    assert_eq!(bytes, *b"scrl\0\x01\0\x02\x80\0\0\x01");
+    let header2: Header = bytes.pread_with(0, BE).unwrap();
+    assert_eq!(header2, header);
+    // the nested struct reads with the outer endianness
+    let header3: Header = bytes.pread_with(0, LE).unwrap();
+    assert_eq!(
+        header3.version,
+        Version {
+            major: 0x100,
+            minor: 0x200
+        }
+    );
+    assert!(bytes[..11].pread_with::<Header>(0, BE).is_err());
+}
+
+#[derive(Debug, PartialEq, Pread, Pwrite, SizeWith, IOread, IOwrite)]
+struct Mixed {
+    #[scroll(ctx = BE)]
+    length: u32,
+    kind: u16,
+    #[scroll(ctx = LE)]
+    version: Version,
+}
+
+#[test]
+fn test_ctx() {
+    let mixed = Mixed {
+        length: 0x0102_0304,
+        kind: 0x0506,
+        version: Version { major: 7, minor: 8 },
+    };
+    assert_eq!(Mixed::size_with(&BE), 10);
+    let mut bytes = [0; 10];
+    bytes.pwrite_with(&mixed, 0, LE).unwrap();
+    assert_eq!(bytes, [1, 2, 3, 4, 6, 5, 7, 0, 8, 0]);
+    assert_eq!(bytes.pread_with::<Mixed>(0, LE).unwrap(), mixed);
+
+    // only the field without an override follows the given endianness
+    let mut bytes2 = [0; 10];
+    bytes2.pwrite_with(&mixed, 0, BE).unwrap();
+    assert_eq!(bytes2, [1, 2, 3, 4, 5, 6, 7, 0, 8, 0]);
+    assert_eq!(bytes2.pread_with::<Mixed>(0, BE).unwrap(), mixed);
+}
+
+#[test]
+fn test_ctx_io() {
+    let mixed = Mixed {
+        length: 1,
+        kind: 2,
+        version: Version { major: 3, minor: 4 },
+    };
+    let mut bytes = [0; 10];
+    bytes.cwrite_with(mixed, 0, BE);
+    assert_eq!(bytes, [0, 0, 0, 1, 0, 2, 3, 0, 4, 0]);
+    let mixed: Mixed = bytes.cread_with(0, BE);
+    assert_eq!(mixed.length, 1);
+    assert_eq!(mixed.kind, 2);
+    assert_eq!(mixed.version, Version { major: 3, minor: 4 });
+}
+
+#[derive(Debug, PartialEq, Pread, Pwrite)]
+struct Entry<'a> {
+    id: u16,
+    #[scroll(ctx = StrCtx::Length(4))]
+    name: &'a str,
+    value: i32,
+}
+
+#[test]
+fn test_ctx_borrowed() {
+    let bytes = b"\x01\x00main\xff\xff\xff\xff";
+    let entry: Entry = bytes.pread_with(0, LE).unwrap();
+    assert_eq!(
+        entry,
+        Entry {
+            id: 1,
+            name: "main",
+            value: -1
+        }
+    );
+    let mut bytes2 = [0; 10];
+    assert_eq!(bytes2.pwrite_with(&entry, 0, LE).unwrap(), 10);
+    assert_eq!(&bytes2, bytes);
+}
@@ -6,6 +6,9 @@ use std::str;
 // opening a GitHub issue if your build environment requires some way to enable
 // these cfgs other than by executing our build script.
 fn main() {
+    watt_contrib_build::check_entry_points();
+    watt_contrib_build::write_snapshot();
+
     let minor = match rustc_minor_version() {
         Some(minor) => minor,
         None => return,
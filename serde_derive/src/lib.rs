@@ -1,10 +1,17 @@
-static WASM: &[u8] = include_bytes!("serde_derive.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("serde_derive.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[proc_macro_derive(Serialize, attributes(serde))]
 pub fn derive_serialize(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(derive_serialize), input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_derive(Deserialize, attributes(serde))]
 pub fn derive_deserialize(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(derive_deserialize), input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,863 @@
+#![cfg_attr(feature = "native", recursion_limit = "128")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("strum_macros.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
+#[doc = " Converts strings to enum variants based on their name."]
+#[doc = ""]
+#[doc = " auto-derives `std::str::FromStr` on the enum (for Rust 1.34 and above, `std::convert::TryFrom<&str>`"]
+#[doc = " will be derived as well). Each variant of the enum will match on it's own name."]
+#[doc = " This can be overridden using `serialize=\"DifferentName\"` or `to_string=\"DifferentName\"`"]
+#[doc = " on the attribute as shown below."]
+#[doc = " Multiple deserializations can be added to the same variant. If the variant contains additional data,"]
+#[doc = " they will be set to their default values upon deserialization."]
+#[doc = ""]
+#[doc = " The `default` attribute can be applied to a tuple variant with a single data parameter. When a match isn't"]
+#[doc = " found, the given variant will be returned and the input string will be captured in the parameter."]
+#[doc = ""]
+#[doc = " Note that the implementation of `FromStr` by default only matches on the name of the"]
+#[doc = " variant. There is an option to match on different case conversions through the"]
+#[doc = " `#[strum(serialize_all = \"snake_case\")]` type attribute."]
+#[doc = ""]
+#[doc = " See the [Additional Attributes](https://docs.rs/strum/latest/strum/additional_attributes/index.html)"]
+#[doc = " Section for more information on using this feature."]
+#[doc = ""]
+#[doc = " If you have a large enum, you may want to consider using the `use_phf` attribute here."]
+#[doc = " PHF (Perfect Hash Functions) use a hash lookup instead of a linear search that may perform faster "]
+#[doc = " for large enums. Note: as with all optimizations, you should test this for your specific usecase"]
+#[doc = " rather than just assume it will be faster. With SIMD + pipelining, linear string search (aka memcmp)"]
+#[doc = " can be very fast for enums with a surprisingly large number of enum variants."]
+#[doc = ""]
+#[doc = " The default error type is `strum::ParseError`. This can be overriden by applying both the"]
+#[doc = " `parse_err_ty` and `parse_err_fn` attributes at the type level.  `parse_error_fn` should be a"]
+#[doc = " function that accepts an `&str` and returns the type `parse_error_ty`. See"]
+#[doc = " [this test case](https://github.com/Peternator7/strum/blob/9db3c4dc9b6f585aeb9f5f15f9cc18b6cf4fd780/strum_tests/tests/from_str.rs#L233)"]
+#[doc = " for an example."]
+#[doc = ""]
+#[doc = " # Example how to use `EnumString`"]
+#[doc = " ```"]
+#[doc = " use std::str::FromStr;"]
+#[doc = " use strum_macros::EnumString;"]
+#[doc = ""]
+#[doc = " #[derive(Debug, PartialEq, EnumString)]"]
+#[doc = " enum Color {"]
+#[doc = "     Red,"]
+#[doc = "     // The Default value will be inserted into range if we match \"Green\"."]
+#[doc = "     Green {"]
+#[doc = "         range: usize,"]
+#[doc = "     },"]
+#[doc = ""]
+#[doc = "     // We can match on multiple different patterns."]
+#[doc = "     #[strum(serialize = \"blue\", serialize = \"b\")]"]
+#[doc = "     Blue(usize),"]
+#[doc = ""]
+#[doc = "     // Notice that we can disable certain variants from being found"]
+#[doc = "     #[strum(disabled)]"]
+#[doc = "     Yellow,"]
+#[doc = ""]
+#[doc = "     // We can make the comparison case insensitive (however Unicode is not supported at the moment)"]
+#[doc = "     #[strum(ascii_case_insensitive)]"]
+#[doc = "     Black,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " /*"]
+#[doc = " //The generated code will look like:"]
+#[doc = " impl std::str::FromStr for Color {"]
+#[doc = "     type Err = ::strum::ParseError;"]
+#[doc = ""]
+#[doc = "     fn from_str(s: &str) -> ::core::result::Result<Color, Self::Err> {"]
+#[doc = "         match s {"]
+#[doc = "             \"Red\" => ::core::result::Result::Ok(Color::Red),"]
+#[doc = "             \"Green\" => ::core::result::Result::Ok(Color::Green { range:Default::default() }),"]
+#[doc = "             \"blue\" => ::core::result::Result::Ok(Color::Blue(Default::default())),"]
+#[doc = "             \"b\" => ::core::result::Result::Ok(Color::Blue(Default::default())),"]
+#[doc = "             s if s.eq_ignore_ascii_case(\"Black\") => ::core::result::Result::Ok(Color::Black),"]
+#[doc = "             _ => ::core::result::Result::Err(::strum::ParseError::VariantNotFound),"]
+#[doc = "         }"]
+#[doc = "     }"]
+#[doc = " }"]
+#[doc = " */"]
+#[doc = ""]
+#[doc = " // simple from string"]
+#[doc = " let color_variant = Color::from_str(\"Red\").unwrap();"]
+#[doc = " assert_eq!(Color::Red, color_variant);"]
+#[doc = " // short version works too"]
+#[doc = " let color_variant = Color::from_str(\"b\").unwrap();"]
+#[doc = " assert_eq!(Color::Blue(0), color_variant);"]
+#[doc = " // was disabled for parsing = returns parse-error"]
+#[doc = " let color_variant = Color::from_str(\"Yellow\");"]
+#[doc = " assert!(color_variant.is_err());"]
+#[doc = " // however the variant is still normally usable"]
+#[doc = " println!(\"{:?}\", Color::Yellow);"]
+#[doc = " let color_variant = Color::from_str(\"bLACk\").unwrap();"]
+#[doc = " assert_eq!(Color::Black, color_variant);"]
+#[doc = " ```"]
+#[proc_macro_derive(EnumString, attributes(strum))]
+pub fn from_string(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(from_string), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Converts enum variants to `&'a str`, where `'a` is the lifetime of the input enum reference."]
+#[doc = ""]
+#[doc = " Implements `AsRef<str>` on your enum using the same rules as"]
+#[doc = " `Display` for determining what string is returned. The difference is that `as_ref()` returns"]
+#[doc = " a `&str` instead of a `String` so you don't allocate any additional memory with each call."]
+#[doc = ""]
+#[doc = " If you require a `&'static str`, you can use"]
+#[doc = " [`strum::IntoStaticStr`](crate::IntoStaticStr) instead."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " // You need to bring the AsRef trait into scope to use it"]
+#[doc = " use std::convert::AsRef;"]
+#[doc = " use strum_macros::AsRefStr;"]
+#[doc = ""]
+#[doc = " #[derive(AsRefStr, Debug)]"]
+#[doc = " enum Color {"]
+#[doc = "     #[strum(serialize = \"redred\")]"]
+#[doc = "     Red,"]
+#[doc = "     Green {"]
+#[doc = "         range: usize,"]
+#[doc = "     },"]
+#[doc = "     Blue(usize),"]
+#[doc = "     Yellow,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // uses the serialize string for Display"]
+#[doc = " let red = Color::Red;"]
+#[doc = " assert_eq!(\"redred\", red.as_ref());"]
+#[doc = " // by default the variants Name"]
+#[doc = " let yellow = Color::Yellow;"]
+#[doc = " assert_eq!(\"Yellow\", yellow.as_ref());"]
+#[doc = " // or for string formatting"]
+#[doc = " assert_eq!("]
+#[doc = "    \"blue: Blue green: Green\","]
+#[doc = "    format!("]
+#[doc = "        \"blue: {} green: {}\","]
+#[doc = "        Color::Blue(10).as_ref(),"]
+#[doc = "        Color::Green { range: 42 }.as_ref()"]
+#[doc = "    )"]
+#[doc = " );"]
+#[doc = ""]
+#[doc = " // With prefix on all variants"]
+#[doc = " #[derive(AsRefStr, Debug)]"]
+#[doc = " #[strum(prefix = \"/\")]"]
+#[doc = " enum ColorWithPrefix {"]
+#[doc = "     #[strum(serialize = \"redred\")]"]
+#[doc = "     Red,"]
+#[doc = "     Green,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!(\"/redred\", ColorWithPrefix::Red.as_ref());"]
+#[doc = " assert_eq!(\"/Green\", ColorWithPrefix::Green.as_ref());"]
+#[doc = ""]
+#[doc = " // With suffix on all variants"]
+#[doc = " #[derive(AsRefStr, Debug)]"]
+#[doc = " #[strum(suffix = \".rs\")]"]
+#[doc = " enum ColorWithSuffix {"]
+#[doc = "     #[strum(serialize = \"redred\")]"]
+#[doc = "     Red,"]
+#[doc = "     Green,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!(\"redred.rs\", ColorWithSuffix::Red.as_ref());"]
+#[doc = " assert_eq!(\"Green.rs\", ColorWithSuffix::Green.as_ref());"]
+#[doc = " ```"]
+#[proc_macro_derive(AsRefStr, attributes(strum))]
+pub fn as_ref_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(as_ref_str), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Implements `Strum::VariantNames` which adds an associated constant `VARIANTS` which is a `'static` slice of discriminant names."]
+#[doc = ""]
+#[doc = " Adds an `impl` block for the `enum` that adds a static `VARIANTS` array of `&'static str` that are the discriminant names."]
+#[doc = " This will respect the `serialize_all` attribute on the `enum` (like `#[strum(serialize_all = \"snake_case\")]`."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " // import the macros needed"]
+#[doc = " use strum_macros::{EnumString};"]
+#[doc = " // You need to import the trait, to have access to VARIANTS"]
+#[doc = " use strum::VariantNames;"]
+#[doc = ""]
+#[doc = " #[derive(Debug, EnumString, strum_macros::VariantNames)]"]
+#[doc = " #[strum(serialize_all = \"kebab-case\")]"]
+#[doc = " enum Color {"]
+#[doc = "     Red,"]
+#[doc = "     Blue,"]
+#[doc = "     Yellow,"]
+#[doc = "     RebeccaPurple,"]
+#[doc = " }"]
+#[doc = " assert_eq!([\"red\", \"blue\", \"yellow\", \"rebecca-purple\"], Color::VARIANTS);"]
+#[doc = " ```"]
+#[proc_macro_derive(VariantNames, attributes(strum))]
+pub fn variant_names(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(variant_names), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc(hidden)]
+#[proc_macro_derive(EnumVariantNames, attributes(strum))]
+#[deprecated(
+    since = "0.26.0",
+    note = "please use `#[derive(VariantNames)]` instead"
+)]
+pub fn variant_names_deprecated(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(variant_names_deprecated), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Adds a `'static` slice with all of the Enum's variants."]
+#[doc = ""]
+#[doc = " Implements `strum::VariantArray` which adds an associated constant `VARIANTS`."]
+#[doc = " This constant contains an array with all the variants of the enumerator."]
+#[doc = ""]
+#[doc = " This trait can only be autoderived if the enumerator is composed only of unit-type variants,"]
+#[doc = " meaning that the variants must not have any data."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " use strum::VariantArray as _;"]
+#[doc = " use strum_macros::VariantArray;"]
+#[doc = ""]
+#[doc = " #[derive(VariantArray, Debug, PartialEq, Eq)]"]
+#[doc = " enum Op {"]
+#[doc = "     Add,"]
+#[doc = "     Sub,"]
+#[doc = "     Mul,"]
+#[doc = "     Div,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!(Op::VARIANTS, &[Op::Add, Op::Sub, Op::Mul, Op::Div]);"]
+#[doc = " ```"]
+#[proc_macro_derive(VariantArray, attributes(strum))]
+pub fn static_variants_array(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(static_variants_array), input)
+}
+#[cfg(not(feature = "native"))]
+#[proc_macro_derive(AsStaticStr, attributes(strum))]
+#[doc(hidden)]
+#[deprecated(
+    since = "0.22.0",
+    note = "please use `#[derive(IntoStaticStr)]` instead"
+)]
+pub fn as_static_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(as_static_str), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Implements `From<MyEnum> for &'static str` on an enum."]
+#[doc = ""]
+#[doc = " Implements `From<YourEnum>` and `From<&'a YourEnum>` for `&'static str`. This is"]
+#[doc = " useful for turning an enum variant into a static string."]
+#[doc = " The Rust `std` provides a blanket impl of the reverse direction - i.e. `impl Into<&'static str> for YourEnum`."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " use strum_macros::IntoStaticStr;"]
+#[doc = ""]
+#[doc = " #[derive(IntoStaticStr)]"]
+#[doc = " enum State<'a> {"]
+#[doc = "     Initial(&'a str),"]
+#[doc = "     Finished,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " fn verify_state<'a>(s: &'a str) {"]
+#[doc = "     let mut state = State::Initial(s);"]
+#[doc = "     // The following won't work because the lifetime is incorrect:"]
+#[doc = "     // let wrong: &'static str = state.as_ref();"]
+#[doc = "     // using the trait implemented by the derive works however:"]
+#[doc = "     let right: &'static str = state.into();"]
+#[doc = "     assert_eq!(\"Initial\", right);"]
+#[doc = "     state = State::Finished;"]
+#[doc = "     let done: &'static str = state.into();"]
+#[doc = "     assert_eq!(\"Finished\", done);"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " verify_state(&\"hello world\".to_string());"]
+#[doc = " ```"]
+#[proc_macro_derive(IntoStaticStr, attributes(strum))]
+pub fn into_static_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(into_static_str), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " implements `std::string::ToString` on an enum"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " // You need to bring the ToString trait into scope to use it"]
+#[doc = " use std::string::ToString;"]
+#[doc = " use strum_macros;"]
+#[doc = ""]
+#[doc = " #[derive(strum_macros::ToString, Debug)]"]
+#[doc = " enum Color {"]
+#[doc = "     #[strum(serialize = \"redred\")]"]
+#[doc = "     Red,"]
+#[doc = "     Green {"]
+#[doc = "         range: usize,"]
+#[doc = "     },"]
+#[doc = "     Blue(usize),"]
+#[doc = "     Yellow,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // uses the serialize string for Display"]
+#[doc = " let red = Color::Red;"]
+#[doc = " assert_eq!(String::from(\"redred\"), red.to_string());"]
+#[doc = " // by default the variants Name"]
+#[doc = " let yellow = Color::Yellow;"]
+#[doc = " assert_eq!(String::from(\"Yellow\"), yellow.to_string());"]
+#[doc = " ```"]
+#[deprecated(
+    since = "0.22.0",
+    note = "please use `#[derive(Display)]` instead. See issue https://github.com/Peternator7/strum/issues/132"
+)]
+#[doc(hidden)]
+#[proc_macro_derive(ToString, attributes(strum))]
+pub fn to_string(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(to_string), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Converts enum variants to strings."]
+#[doc = ""]
+#[doc = " Deriving `Display` on an enum prints out the given enum. This enables you to perform round"]
+#[doc = " trip style conversions from enum into string and back again for unit style variants. `Display`"]
+#[doc = " choose which serialization to used based on the following criteria:"]
+#[doc = ""]
+#[doc = " 1. If there is a `to_string` property, this value will be used. There can only be one per variant."]
+#[doc = " 2. Of the various `serialize` properties, the value with the longest length is chosen. If that"]
+#[doc = "    behavior isn't desired, you should use `to_string`."]
+#[doc = " 3. The name of the variant will be used if there are no `serialize` or `to_string` attributes."]
+#[doc = " 4. If the enum has a `strum(prefix = \"some_value_\")`, every variant will have that prefix prepended"]
+#[doc = "    to the serialization."]
+#[doc = " 5. If the enum has a `strum(suffix = \"_another_value\")`, every variant will have that suffix appended"]
+#[doc = "    to the serialization."]
+#[doc = " 6. Enums with fields support string interpolation."]
+#[doc = "    Note this means the variant will not \"round trip\" if you then deserialize the string."]
+#[doc = ""]
+#[doc = "    ```rust"]
+#[doc = "    #[derive(strum_macros::Display)]"]
+#[doc = "    pub enum Color {"]
+#[doc = "        #[strum(to_string = \"saturation is {sat}\")]"]
+#[doc = "        Red { sat: usize },"]
+#[doc = "        #[strum(to_string = \"hue is {1}, saturation is {0}\")]"]
+#[doc = "        Blue(usize, usize),"]
+#[doc = "    }"]
+#[doc = "    ```"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " // You need to bring the ToString trait into scope to use it"]
+#[doc = " use std::string::ToString;"]
+#[doc = " use strum_macros::Display;"]
+#[doc = ""]
+#[doc = " #[derive(Display, Debug)]"]
+#[doc = " enum Color {"]
+#[doc = "     #[strum(serialize = \"redred\")]"]
+#[doc = "     Red,"]
+#[doc = "     Green {"]
+#[doc = "         range: usize,"]
+#[doc = "     },"]
+#[doc = "     Blue(usize),"]
+#[doc = "     Yellow,"]
+#[doc = "     #[strum(to_string = \"purple with {sat} saturation\")]"]
+#[doc = "     Purple {"]
+#[doc = "         sat: usize,"]
+#[doc = "     },"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // uses the serialize string for Display"]
+#[doc = " let red = Color::Red;"]
+#[doc = " assert_eq!(String::from(\"redred\"), format!(\"{}\", red));"]
+#[doc = " // by default the variants Name"]
+#[doc = " let yellow = Color::Yellow;"]
+#[doc = " assert_eq!(String::from(\"Yellow\"), yellow.to_string());"]
+#[doc = " // or for string formatting"]
+#[doc = " assert_eq!("]
+#[doc = "    \"blue: Blue green: Green\","]
+#[doc = "    format!("]
+#[doc = "        \"blue: {} green: {}\","]
+#[doc = "        Color::Blue(10),"]
+#[doc = "        Color::Green { range: 42 }"]
+#[doc = "    )"]
+#[doc = " );"]
+#[doc = " // you can also use named fields in message"]
+#[doc = " let purple = Color::Purple { sat: 10 };"]
+#[doc = " assert_eq!(String::from(\"purple with 10 saturation\"), purple.to_string());"]
+#[doc = " ```"]
+#[proc_macro_derive(Display, attributes(strum))]
+pub fn display(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(display), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Creates a new type that iterates over the variants of an enum."]
+#[doc = ""]
+#[doc = " Iterate over the variants of an Enum. Any additional data on your variants will be set to `Default::default()`."]
+#[doc = " The macro implements [`strum::IntoEnumIterator`](https://docs.rs/strum/latest/strum/trait.IntoEnumIterator.html) on your enum and creates a new type called `YourEnumIter` that is the iterator object."]
+#[doc = " You cannot derive `EnumIter` on any type with a lifetime bound (`<'a>`) because the iterator would surely"]
+#[doc = " create [unbounded lifetimes](https://doc.rust-lang.org/nightly/nomicon/unbounded-lifetimes.html)."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " // You need to bring the trait into scope to use it!"]
+#[doc = " use strum::IntoEnumIterator;"]
+#[doc = " use strum_macros::EnumIter;"]
+#[doc = ""]
+#[doc = " #[derive(EnumIter, Debug, PartialEq)]"]
+#[doc = " enum Color {"]
+#[doc = "     Red,"]
+#[doc = "     Green { range: usize },"]
+#[doc = "     Blue(usize),"]
+#[doc = "     Yellow,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // It's simple to iterate over the variants of an enum."]
+#[doc = " for color in Color::iter() {"]
+#[doc = "     println!(\"My favorite color is {:?}\", color);"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " let mut ci = Color::iter();"]
+#[doc = " assert_eq!(Some(Color::Red), ci.next());"]
+#[doc = " assert_eq!(Some(Color::Green {range: 0}), ci.next());"]
+#[doc = " assert_eq!(Some(Color::Blue(0)), ci.next());"]
+#[doc = " assert_eq!(Some(Color::Yellow), ci.next());"]
+#[doc = " assert_eq!(None, ci.next());"]
+#[doc = " ```"]
+#[proc_macro_derive(EnumIter, attributes(strum))]
+pub fn enum_iter(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_iter), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Generated `is_*()` methods for each variant."]
+#[doc = " E.g. `Color.is_red()`."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " use strum_macros::EnumIs;"]
+#[doc = ""]
+#[doc = " #[derive(EnumIs, Debug)]"]
+#[doc = " enum Color {"]
+#[doc = "     Red,"]
+#[doc = "     Green { range: usize },"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert!(Color::Red.is_red());"]
+#[doc = " assert!(Color::Green{range: 0}.is_green());"]
+#[doc = " ```"]
+#[proc_macro_derive(EnumIs, attributes(strum))]
+pub fn enum_is(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_is), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Generated `try_as_*()` methods for all tuple-style variants."]
+#[doc = " E.g. `Message.try_as_write()`."]
+#[doc = ""]
+#[doc = " These methods will only be generated for tuple-style variants, not for named or unit variants."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " use strum_macros::EnumTryAs;"]
+#[doc = ""]
+#[doc = " #[derive(EnumTryAs, Debug)]"]
+#[doc = " enum Message {"]
+#[doc = "     Quit,"]
+#[doc = "     Move { x: i32, y: i32 },"]
+#[doc = "     Write(String),"]
+#[doc = "     ChangeColor(i32, i32, i32),"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!("]
+#[doc = "     Message::Write(String::from(\"Hello\")).try_as_write(),"]
+#[doc = "     Some(String::from(\"Hello\"))"]
+#[doc = " );"]
+#[doc = " assert_eq!("]
+#[doc = "     Message::ChangeColor(1, 2, 3).try_as_change_color(),"]
+#[doc = "     Some((1, 2, 3))"]
+#[doc = " );"]
+#[doc = " ```"]
+#[proc_macro_derive(EnumTryAs, attributes(strum))]
+pub fn enum_try_as(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_try_as), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Creates a new type that maps all the variants of an enum to another generic value."]
+#[doc = ""]
+#[doc = " This macro only supports enums with unit type variants.A new type called `YourEnumTable<T>`. Essentially, it's a wrapper"]
+#[doc = " `[T; YourEnum::Count]` where gets/sets are infallible. Some important caveats to note:"]
+#[doc = ""]
+#[doc = " * The size of `YourEnumTable<T>` increases with the number of variants, not the number of values because it's always"]
+#[doc = "   fully populated. This means it may not be a good choice for sparsely populated maps."]
+#[doc = ""]
+#[doc = " * Lookups are basically constant time since it's functionally an array index."]
+#[doc = ""]
+#[doc = " * Your variants cannot have associated data. You can use `EnumDiscriminants` to generate an Enum with the same"]
+#[doc = "   names to work around this."]
+#[doc = ""]
+#[doc = " # Stability"]
+#[doc = ""]
+#[doc = " Several people expressed interest in a data structure like this and pushed the PR through to completion, but the api"]
+#[doc = " seems incomplete, and I reserve the right to deprecate it in the future if it becomes clear the design is flawed."]
+#[doc = ""]
+#[doc = " # Example"]
+#[doc = " ```rust"]
+#[doc = " use strum_macros::EnumTable;"]
+#[doc = ""]
+#[doc = " #[derive(EnumTable)]"]
+#[doc = " enum Color {"]
+#[doc = "     Red,"]
+#[doc = "     Yellow,"]
+#[doc = "     Green,"]
+#[doc = "     Blue,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!(ColorTable::default(), ColorTable::new(0, 0, 0, 0));"]
+#[doc = " assert_eq!(ColorTable::filled(2), ColorTable::new(2, 2, 2, 2));"]
+#[doc = " assert_eq!(ColorTable::from_closure(|_| 3), ColorTable::new(3, 3, 3, 3));"]
+#[doc = " assert_eq!(ColorTable::default().transform(|_, val| val + 2), ColorTable::new(2, 2, 2, 2));"]
+#[doc = ""]
+#[doc = " let mut complex_map = ColorTable::from_closure(|color| match color {"]
+#[doc = "     Color::Red => 0,"]
+#[doc = "     _ => 3"]
+#[doc = " });"]
+#[doc = ""]
+#[doc = " complex_map[Color::Green] = complex_map[Color::Red];"]
+#[doc = " assert_eq!(complex_map, ColorTable::new(0, 3, 0, 3));"]
+#[doc = " ```"]
+#[doc(hidden)]
+#[proc_macro_derive(EnumTable, attributes(strum))]
+pub fn enum_table(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_table), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Add a function to enum that allows accessing variants by its discriminant"]
+#[doc = ""]
+#[doc = " This macro adds a standalone function to obtain an enum variant by its discriminant. The macro adds"]
+#[doc = " `from_repr(discriminant: usize) -> Option<YourEnum>` as a standalone function on the enum. For"]
+#[doc = " variants with additional data, the returned variant will use the `Default` trait to fill the"]
+#[doc = " data. The discriminant follows the same rules as `rustc`. The first discriminant is zero and each"]
+#[doc = " successive variant has a discriminant of one greater than the previous variant, except where an"]
+#[doc = " explicit discriminant is specified. The type of the discriminant will match the `repr` type if"]
+#[doc = " it is specified."]
+#[doc = ""]
+#[doc = " When the macro is applied using rustc >= 1.46 and when there is no additional data on any of"]
+#[doc = " the variants, the `from_repr` function is marked `const`. rustc >= 1.46 is required"]
+#[doc = " to allow `match` statements in `const fn`. The no additional data requirement is due to the"]
+#[doc = " inability to use `Default::default()` in a `const fn`."]
+#[doc = ""]
+#[doc = " You cannot derive `FromRepr` on any type with a lifetime bound (`<'a>`) because the function would surely"]
+#[doc = " create [unbounded lifetimes](https://doc.rust-lang.org/nightly/nomicon/unbounded-lifetimes.html)."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " use strum_macros::FromRepr;"]
+#[doc = ""]
+#[doc = " #[derive(FromRepr, Debug, PartialEq)]"]
+#[doc = " enum Color {"]
+#[doc = "     Red,"]
+#[doc = "     Green { range: usize },"]
+#[doc = "     Blue(usize),"]
+#[doc = "     Yellow,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!(Some(Color::Red), Color::from_repr(0));"]
+#[doc = " assert_eq!(Some(Color::Green {range: 0}), Color::from_repr(1));"]
+#[doc = " assert_eq!(Some(Color::Blue(0)), Color::from_repr(2));"]
+#[doc = " assert_eq!(Some(Color::Yellow), Color::from_repr(3));"]
+#[doc = " assert_eq!(None, Color::from_repr(4));"]
+#[doc = ""]
+#[doc = " // Custom discriminant tests"]
+#[doc = " #[derive(FromRepr, Debug, PartialEq)]"]
+#[doc = " #[repr(u8)]"]
+#[doc = " enum Vehicle {"]
+#[doc = "     Car = 1,"]
+#[doc = "     Truck = 3,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!(None, Vehicle::from_repr(0));"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " On versions of rust >= 1.46, the `from_repr` function is marked `const`."]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " use strum_macros::FromRepr;"]
+#[doc = ""]
+#[doc = " #[derive(FromRepr, Debug, PartialEq)]"]
+#[doc = " #[repr(u8)]"]
+#[doc = " enum Number {"]
+#[doc = "     One = 1,"]
+#[doc = "     Three = 3,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " const fn number_from_repr(d: u8) -> Option<Number> {"]
+#[doc = "     Number::from_repr(d)"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!(None, number_from_repr(0));"]
+#[doc = " assert_eq!(Some(Number::One), number_from_repr(1));"]
+#[doc = " assert_eq!(None, number_from_repr(2));"]
+#[doc = " assert_eq!(Some(Number::Three), number_from_repr(3));"]
+#[doc = " assert_eq!(None, number_from_repr(4));"]
+#[doc = " ```"]
+#[proc_macro_derive(FromRepr, attributes(strum))]
+pub fn from_repr(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(from_repr), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Add a verbose message to an enum variant."]
+#[doc = ""]
+#[doc = " Encode strings into the enum itself. The `strum_macros::EmumMessage` macro implements the `strum::EnumMessage` trait."]
+#[doc = " `EnumMessage` looks for `#[strum(message=\"...\")]` attributes on your variants."]
+#[doc = " You can also provided a `detailed_message=\"...\"` attribute to create a separate more detailed message than the first."]
+#[doc = ""]
+#[doc = " `EnumMessage` also exposes the variants doc comments through `get_documentation()`. This is useful in some scenarios,"]
+#[doc = " but `get_message` should generally be preferred. Rust doc comments are intended for developer facing documentation,"]
+#[doc = " not end user messaging."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " // You need to bring the trait into scope to use it"]
+#[doc = " use strum::EnumMessage;"]
+#[doc = " use strum_macros;"]
+#[doc = ""]
+#[doc = " #[derive(strum_macros::EnumMessage, Debug)]"]
+#[doc = " #[allow(dead_code)]"]
+#[doc = " enum Color {"]
+#[doc = "     /// Danger color."]
+#[doc = "     #[strum(message = \"Red\", detailed_message = \"This is very red\")]"]
+#[doc = "     Red,"]
+#[doc = "     #[strum(message = \"Simply Green\")]"]
+#[doc = "     Green { range: usize },"]
+#[doc = "     #[strum(serialize = \"b\", serialize = \"blue\")]"]
+#[doc = "     Blue(usize),"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // Generated code looks like more or less like this:"]
+#[doc = " /*"]
+#[doc = " impl ::strum::EnumMessage for Color {"]
+#[doc = "     fn get_message(&self) -> ::core::option::Option<&'static str> {"]
+#[doc = "         match self {"]
+#[doc = "             &Color::Red => ::core::option::Option::Some(\"Red\"),"]
+#[doc = "             &Color::Green {..} => ::core::option::Option::Some(\"Simply Green\"),"]
+#[doc = "             _ => None"]
+#[doc = "         }"]
+#[doc = "     }"]
+#[doc = ""]
+#[doc = "     fn get_detailed_message(&self) -> ::core::option::Option<&'static str> {"]
+#[doc = "         match self {"]
+#[doc = "             &Color::Red => ::core::option::Option::Some(\"This is very red\"),"]
+#[doc = "             &Color::Green {..}=> ::core::option::Option::Some(\"Simply Green\"),"]
+#[doc = "             _ => None"]
+#[doc = "         }"]
+#[doc = "     }"]
+#[doc = ""]
+#[doc = "     fn get_documentation(&self) -> ::std::option::Option<&'static str> {"]
+#[doc = "         match self {"]
+#[doc = "             &Color::Red => ::std::option::Option::Some(\"Danger color.\"),"]
+#[doc = "             _ => None"]
+#[doc = "         }"]
+#[doc = "     }"]
+#[doc = ""]
+#[doc = "     fn get_serializations(&self) -> &'static [&'static str] {"]
+#[doc = "         match self {"]
+#[doc = "             &Color::Red => {"]
+#[doc = "                 static ARR: [&'static str; 1] = [\"Red\"];"]
+#[doc = "                 &ARR"]
+#[doc = "             },"]
+#[doc = "             &Color::Green {..}=> {"]
+#[doc = "                 static ARR: [&'static str; 1] = [\"Green\"];"]
+#[doc = "                 &ARR"]
+#[doc = "             },"]
+#[doc = "             &Color::Blue (..) => {"]
+#[doc = "                 static ARR: [&'static str; 2] = [\"b\", \"blue\"];"]
+#[doc = "                 &ARR"]
+#[doc = "             },"]
+#[doc = "         }"]
+#[doc = "     }"]
+#[doc = " }"]
+#[doc = " */"]
+#[doc = ""]
+#[doc = " let c = Color::Red;"]
+#[doc = " assert_eq!(\"Red\", c.get_message().unwrap());"]
+#[doc = " assert_eq!(\"This is very red\", c.get_detailed_message().unwrap());"]
+#[doc = " assert_eq!(\"Danger color.\", c.get_documentation().unwrap());"]
+#[doc = " assert_eq!([\"Red\"], c.get_serializations());"]
+#[doc = " ```"]
+#[proc_macro_derive(EnumMessage, attributes(strum))]
+pub fn enum_messages(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_messages), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Add custom properties to enum variants."]
+#[doc = ""]
+#[doc = " Enables the encoding of arbitrary constants into enum variants. This method"]
+#[doc = " currently only supports adding additional string values. Other types of literals are still"]
+#[doc = " experimental in the rustc compiler. The generated code works by nesting match statements."]
+#[doc = " The first match statement matches on the type of the enum, and the inner match statement"]
+#[doc = " matches on the name of the property requested. This design works well for enums with a small"]
+#[doc = " number of variants and properties, but scales linearly with the number of variants so may not"]
+#[doc = " be the best choice in all situations."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " use strum_macros;"]
+#[doc = " // bring the trait into scope"]
+#[doc = " use strum::EnumProperty;"]
+#[doc = ""]
+#[doc = " #[derive(strum_macros::EnumProperty, Debug)]"]
+#[doc = " #[allow(dead_code)]"]
+#[doc = " enum Color {"]
+#[doc = "     #[strum(props(Red = \"255\", Blue = \"255\", Green = \"255\"))]"]
+#[doc = "     White,"]
+#[doc = "     #[strum(props(Red = \"0\", Blue = \"0\", Green = \"0\"))]"]
+#[doc = "     Black,"]
+#[doc = "     #[strum(props(Red = \"0\", Blue = \"255\", Green = \"0\"))]"]
+#[doc = "     Blue,"]
+#[doc = "     #[strum(props(Red = \"255\", Blue = \"0\", Green = \"0\"))]"]
+#[doc = "     Red,"]
+#[doc = "     #[strum(props(Red = \"0\", Blue = \"0\", Green = \"255\"))]"]
+#[doc = "     Green,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " let my_color = Color::Red;"]
+#[doc = " let display = format!("]
+#[doc = "     \"My color is {:?}. It's RGB is {},{},{}\","]
+#[doc = "     my_color,"]
+#[doc = "     my_color.get_str(\"Red\").unwrap(),"]
+#[doc = "     my_color.get_str(\"Green\").unwrap(),"]
+#[doc = "     my_color.get_str(\"Blue\").unwrap()"]
+#[doc = " );"]
+#[doc = " assert_eq!(\"My color is Red. It\\'s RGB is 255,0,0\", &display);"]
+#[doc = " ```"]
+#[proc_macro_derive(EnumProperty, attributes(strum))]
+pub fn enum_properties(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_properties), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Generate a new type with only the discriminant names."]
+#[doc = ""]
+#[doc = " Given an enum named `MyEnum`, generates another enum called `MyEnumDiscriminants` with the same"]
+#[doc = " variants but without any data fields. This is useful when you wish to determine the variant of"]
+#[doc = " an `enum` but one or more of the variants contains a non-`Default` field. `From`"]
+#[doc = " implementations are generated so that you can easily convert from `MyEnum` to"]
+#[doc = " `MyEnumDiscriminants`."]
+#[doc = ""]
+#[doc = " By default, the generated enum has the following derives: `Clone, Copy, Debug, PartialEq, Eq`."]
+#[doc = " You can add additional derives using the `#[strum_discriminants(derive(AdditionalDerive))]`"]
+#[doc = " attribute."]
+#[doc = ""]
+#[doc = " Note, the variant attributes passed to the discriminant enum are filtered to avoid compilation"]
+#[doc = " errors due to the derives mismatches, thus only `#[doc]`, `#[cfg]`, `#[allow]`, and `#[deny]`"]
+#[doc = " are passed through by default. If you want to specify a custom attribute on the discriminant"]
+#[doc = " variant, wrap it with `#[strum_discriminants(...)]` attribute."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " // Bring trait into scope"]
+#[doc = " use std::str::FromStr;"]
+#[doc = " use strum::{IntoEnumIterator, EnumMessage as _};"]
+#[doc = " use strum_macros::{EnumDiscriminants, EnumIter, EnumString, EnumMessage};"]
+#[doc = ""]
+#[doc = " #[derive(Debug)]"]
+#[doc = " struct NonDefault;"]
+#[doc = ""]
+#[doc = " // simple example"]
+#[doc = " # #[allow(dead_code)]"]
+#[doc = " #[derive(Debug, EnumDiscriminants)]"]
+#[doc = " #[strum_discriminants(derive(EnumString, EnumMessage))]"]
+#[doc = " #[strum_discriminants(doc = \"This is the docstring on the generated type.\")]"]
+#[doc = " enum MyEnum {"]
+#[doc = "     #[strum_discriminants(strum(message = \"Variant zero\"))]"]
+#[doc = "     Variant0(NonDefault),"]
+#[doc = "     Variant1 { a: NonDefault },"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // You can rename the generated enum using the `#[strum_discriminants(name(OtherName))]` attribute:"]
+#[doc = " # #[allow(dead_code)]"]
+#[doc = " #[derive(Debug, EnumDiscriminants)]"]
+#[doc = " #[strum_discriminants(derive(EnumIter))]"]
+#[doc = " #[strum_discriminants(name(MyVariants))]"]
+#[doc = " enum MyEnumR {"]
+#[doc = "     Variant0(bool),"]
+#[doc = "     Variant1 { a: bool },"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // test simple example"]
+#[doc = " assert_eq!("]
+#[doc = "     MyEnumDiscriminants::Variant0,"]
+#[doc = "     MyEnumDiscriminants::from_str(\"Variant0\").unwrap()"]
+#[doc = " );"]
+#[doc = " // test rename example combined with EnumIter"]
+#[doc = " assert_eq!("]
+#[doc = "     vec![MyVariants::Variant0, MyVariants::Variant1],"]
+#[doc = "     MyVariants::iter().collect::<Vec<_>>()"]
+#[doc = " );"]
+#[doc = ""]
+#[doc = " // Make use of the auto-From conversion to check whether an instance of `MyEnum` matches a"]
+#[doc = " // `MyEnumDiscriminants` discriminant."]
+#[doc = " assert_eq!("]
+#[doc = "     MyEnumDiscriminants::Variant0,"]
+#[doc = "     MyEnum::Variant0(NonDefault).into()"]
+#[doc = " );"]
+#[doc = " assert_eq!("]
+#[doc = "     MyEnumDiscriminants::Variant0,"]
+#[doc = "     MyEnumDiscriminants::from(MyEnum::Variant0(NonDefault))"]
+#[doc = " );"]
+#[doc = ""]
+#[doc = " // Make use of the EnumMessage on the `MyEnumDiscriminants` discriminant."]
+#[doc = " assert_eq!("]
+#[doc = "     MyEnumDiscriminants::Variant0.get_message(),"]
+#[doc = "     Some(\"Variant zero\")"]
+#[doc = " );"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " It is also possible to specify the visibility (e.g. `pub`/`pub(crate)`/etc.)"]
+#[doc = " of the generated enum. By default, the generated enum inherits the"]
+#[doc = " visibility of the parent enum it was generated from."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " use strum_macros::EnumDiscriminants;"]
+#[doc = ""]
+#[doc = " // You can set the visibility of the generated enum using the `#[strum_discriminants(vis(..))]` attribute:"]
+#[doc = " mod inner {"]
+#[doc = "     use strum_macros::EnumDiscriminants;"]
+#[doc = ""]
+#[doc = "     # #[allow(dead_code)]"]
+#[doc = "     #[derive(Debug, EnumDiscriminants)]"]
+#[doc = "     #[strum_discriminants(vis(pub))]"]
+#[doc = "     #[strum_discriminants(name(PubDiscriminants))]"]
+#[doc = "     enum PrivateEnum {"]
+#[doc = "         Variant0(bool),"]
+#[doc = "         Variant1 { a: bool },"]
+#[doc = "     }"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // test visibility example, `PrivateEnum` should not be accessible here"]
+#[doc = " assert_ne!("]
+#[doc = "     inner::PubDiscriminants::Variant0,"]
+#[doc = "     inner::PubDiscriminants::Variant1,"]
+#[doc = " );"]
+#[doc = " ```"]
+#[proc_macro_derive(EnumDiscriminants, attributes(strum, strum_discriminants))]
+pub fn enum_discriminants(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_discriminants), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Add a constant `usize` equal to the number of variants."]
+#[doc = ""]
+#[doc = " For a given enum generates implementation of `strum::EnumCount`,"]
+#[doc = " which adds a static property `COUNT` of type usize that holds the number of variants."]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " use strum::{EnumCount, IntoEnumIterator};"]
+#[doc = " use strum_macros::{EnumCount as EnumCountMacro, EnumIter};"]
+#[doc = ""]
+#[doc = " #[derive(Debug, EnumCountMacro, EnumIter)]"]
+#[doc = " enum Week {"]
+#[doc = "     Sunday,"]
+#[doc = "     Monday,"]
+#[doc = "     Tuesday,"]
+#[doc = "     Wednesday,"]
+#[doc = "     Thursday,"]
+#[doc = "     Friday,"]
+#[doc = "     Saturday,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " assert_eq!(7, Week::COUNT);"]
+#[doc = " assert_eq!(Week::iter().count(), Week::COUNT);"]
+#[doc = ""]
+#[doc = " ```"]
+#[proc_macro_derive(EnumCount, attributes(strum))]
+pub fn enum_count(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(enum_count), input)
+}
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
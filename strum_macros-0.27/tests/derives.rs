@@ -0,0 +1,124 @@
+use strum::{VariantArray, VariantNames};
+use strum_macros::{EnumIs, EnumTable, EnumTryAs, FromRepr, VariantArray, VariantNames};
+
+#[derive(FromRepr, Debug, PartialEq)]
+#[repr(u8)]
+enum Opcode {
+    Nop,
+    Push(u32),
+    Jump { target: usize },
+    Halt = 10,
+    Trap,
+}
+
+#[test]
+fn from_repr() {
+    assert_eq!(Opcode::from_repr(0), Some(Opcode::Nop));
+    assert_eq!(Opcode::from_repr(1), Some(Opcode::Push(0)));
+    assert_eq!(Opcode::from_repr(2), Some(Opcode::Jump { target: 0 }));
+    assert_eq!(Opcode::from_repr(3), None);
+    assert_eq!(Opcode::from_repr(10), Some(Opcode::Halt));
+    assert_eq!(Opcode::from_repr(11), Some(Opcode::Trap));
+}
+
+#[derive(FromRepr, Debug, PartialEq)]
+#[repr(i8)]
+enum Sign {
+    Negative = -1,
+    Zero,
+    Positive,
+}
+
+const fn sign(value: i8) -> Option<Sign> {
+    Sign::from_repr(value)
+}
+
+#[test]
+fn const_from_repr() {
+    const NEGATIVE: Option<Sign> = sign(-1);
+    assert_eq!(NEGATIVE, Some(Sign::Negative));
+    assert_eq!(sign(0), Some(Sign::Zero));
+    assert_eq!(sign(1), Some(Sign::Positive));
+    assert_eq!(sign(2), None);
+}
+
+#[derive(EnumIs, EnumTryAs, Debug)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect(u32, u32),
+    Polygon {
+        sides: u8,
+    },
+    #[strum(disabled)]
+    Hidden(u8),
+}
+
+#[test]
+fn enum_is() {
+    assert!(Shape::Empty.is_empty());
+    assert!(!Shape::Empty.is_circle());
+    assert!(Shape::Circle(1.0).is_circle());
+    assert!(Shape::Rect(1, 2).is_rect());
+    let polygon = Shape::Polygon { sides: 5 };
+    assert!(polygon.is_polygon());
+    assert!(matches!(polygon, Shape::Polygon { sides: 5 }));
+    // Disabled variants get no method, but still count as other variants.
+    let hidden = Shape::Hidden(1);
+    assert!(!hidden.is_polygon());
+    assert!(matches!(hidden, Shape::Hidden(1)));
+}
+
+#[test]
+fn enum_try_as() {
+    assert_eq!(Shape::Circle(1.5).try_as_circle(), Some(1.5));
+    assert_eq!(Shape::Empty.try_as_circle(), None);
+    assert_eq!(Shape::Rect(3, 4).try_as_rect(), Some((3, 4)));
+
+    let mut rect = Shape::Rect(3, 4);
+    if let Some((width, _)) = rect.try_as_rect_mut() {
+        *width = 6;
+    }
+    assert_eq!(rect.try_as_rect_ref(), Some((&6, &4)));
+    assert_eq!(Shape::Polygon { sides: 3 }.try_as_circle_ref(), None);
+}
+
+#[derive(VariantArray, VariantNames, EnumTable, Clone, Copy, Debug, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+enum Weekday {
+    Monday,
+    Tuesday,
+    #[strum(serialize = "wed")]
+    Wednesday,
+}
+
+#[test]
+fn variant_array() {
+    assert_eq!(
+        <Weekday as VariantArray>::VARIANTS,
+        [Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday]
+    );
+}
+
+#[test]
+fn variant_names() {
+    assert_eq!(
+        <Weekday as VariantNames>::VARIANTS,
+        ["monday", "tuesday", "wed"]
+    );
+}
+
+#[test]
+fn enum_table() {
+    let mut hours = WeekdayTable::filled(8);
+    hours[Weekday::Wednesday] = 4;
+    assert_eq!(hours, WeekdayTable::new(8, 8, 4));
+
+    let doubled = hours.transform(|_, hours| hours * 2);
+    assert_eq!(doubled[Weekday::Monday], 16);
+    assert_eq!(doubled[Weekday::Wednesday], 8);
+
+    let index = WeekdayTable::from_closure(|day| day as usize);
+    assert_eq!(index, WeekdayTable::new(0, 1, 2));
+    assert_eq!(WeekdayTable::<u8>::default(), WeekdayTable::new(0, 0, 0));
+}
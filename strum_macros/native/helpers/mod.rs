@@ -0,0 +1,10 @@
+pub use self::case_style::CaseStyleHelpers;
+pub use self::meta_helpers::{LitHelpers, MetaHelpers, NestedMetaHelpers};
+pub use self::type_props::HasTypeProperties;
+pub use self::variant_props::HasStrumVariantProperties;
+
+pub mod case_style;
+mod has_metadata;
+mod meta_helpers;
+pub mod type_props;
+pub mod variant_props;
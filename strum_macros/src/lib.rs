@@ -1,5 +1,10 @@
-static WASM: &[u8] = include_bytes!("strum_macros.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#![cfg_attr(feature = "native", recursion_limit = "128")]
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("strum_macros.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     not(feature = "verbose-enumstring-name"),
     proc_macro_derive(EnumString, attributes(strum))
@@ -11,6 +16,7 @@ static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
 pub fn from_string(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(from_string), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     not(feature = "verbose-asrefstr-name"),
     proc_macro_derive(AsRefStr, attributes(strum))
@@ -22,6 +28,7 @@ pub fn from_string(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn as_ref_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(as_ref_str), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     not(feature = "verbose-variant-names"),
     proc_macro_derive(EnumVariantNames, attributes(strum))
@@ -33,6 +40,7 @@ pub fn as_ref_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn variant_names(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(variant_names), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-asstaticstr-name",
     proc_macro_derive(StrumAsStaticStr, attributes(strum))
@@ -44,6 +52,7 @@ pub fn variant_names(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn as_static_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(as_static_str), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-intostaticstr-name",
     proc_macro_derive(StrumIntoStaticStr, attributes(strum))
@@ -55,6 +64,7 @@ pub fn as_static_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn into_static_str(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(into_static_str), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-tostring-name",
     proc_macro_derive(StrumToString, attributes(strum))
@@ -66,6 +76,7 @@ pub fn into_static_str(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 pub fn to_string(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(to_string), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-display-name",
     proc_macro_derive(StrumDisplay, attributes(strum))
@@ -77,6 +88,7 @@ pub fn to_string(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn display(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(display), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-enumiter-name",
     proc_macro_derive(StrumEnumIter, attributes(strum))
@@ -88,6 +100,7 @@ pub fn display(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn enum_iter(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(enum_iter), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-enummessage-name",
     proc_macro_derive(StrumEnumMessage, attributes(strum))
@@ -99,6 +112,7 @@ pub fn enum_iter(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 pub fn enum_messages(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(enum_messages), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-enumproperty-name",
     proc_macro_derive(StrumEnumProperty, attributes(strum))
@@ -110,6 +124,7 @@ pub fn enum_messages(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 pub fn enum_properties(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(enum_properties), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-enumdiscriminants-name",
     proc_macro_derive(StrumEnumDiscriminants, attributes(strum, strum_discriminants))
@@ -121,6 +136,7 @@ pub fn enum_properties(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 pub fn enum_discriminants(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(enum_discriminants), input)
 }
+#[cfg(not(feature = "native"))]
 #[cfg_attr(
     feature = "verbose-enumcount-name",
     proc_macro_derive(StrumEnumCount, attributes(strum))
@@ -132,3 +148,5 @@ pub fn enum_discriminants(input: proc_macro::TokenStream) -> proc_macro::TokenSt
 pub fn enum_count(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro_derive(stringify!(enum_count), input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,20 @@
+extern crate proc_macro;
+
+mod ast;
+mod attr;
+mod expand;
+mod fmt;
+mod generics;
+mod prop;
+mod scan_expr;
+mod span;
+mod valid;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+#[proc_macro_derive(Error, attributes(backtrace, error, from, source))]
+pub fn derive_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::derive(&input).into()
+}
@@ -0,0 +1,475 @@
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("tokio-macros.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
+#[doc = " Marks async function to be executed by the selected runtime. This macro"]
+#[doc = " helps set up a `Runtime` without requiring the user to use"]
+#[doc = " [Runtime](../tokio/runtime/struct.Runtime.html) or"]
+#[doc = " [Builder](../tokio/runtime/struct.Builder.html) directly."]
+#[doc = ""]
+#[doc = " Note: This macro is designed to be simplistic and targets applications that"]
+#[doc = " do not require a complex setup. If the provided functionality is not"]
+#[doc = " sufficient, you may be interested in using"]
+#[doc = " [Builder](../tokio/runtime/struct.Builder.html), which provides a more"]
+#[doc = " powerful interface."]
+#[doc = ""]
+#[doc = " Note: This macro can be used on any function and not just the `main`"]
+#[doc = " function. Using it on a non-main function makes the function behave as if it"]
+#[doc = " was synchronous by starting a new runtime each time it is called. If the"]
+#[doc = " function is called often, it is preferable to create the runtime using the"]
+#[doc = " runtime builder so the runtime can be reused across calls."]
+#[doc = ""]
+#[doc = " # Multi-threaded runtime"]
+#[doc = ""]
+#[doc = " To use the multi-threaded runtime, the macro can be configured using"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " #[tokio::main(flavor = \"multi_thread\", worker_threads = 10)]"]
+#[doc = " # async fn main() {}"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " The `worker_threads` option configures the number of worker threads, and"]
+#[doc = " defaults to the number of cpus on the system. This is the default flavor."]
+#[doc = ""]
+#[doc = " Note: The multi-threaded runtime requires the `rt-multi-thread` feature"]
+#[doc = " flag."]
+#[doc = ""]
+#[doc = " # Current thread runtime"]
+#[doc = ""]
+#[doc = " To use the single-threaded runtime known as the `current_thread` runtime,"]
+#[doc = " the macro can be configured using"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " #[tokio::main(flavor = \"current_thread\")]"]
+#[doc = " # async fn main() {}"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ## Function arguments:"]
+#[doc = ""]
+#[doc = " Arguments are allowed for any functions aside from `main` which is special"]
+#[doc = ""]
+#[doc = " ## Usage"]
+#[doc = ""]
+#[doc = " ### Using the multi-thread runtime"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " #[tokio::main]"]
+#[doc = " async fn main() {"]
+#[doc = "     println!(\"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::main]`"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " fn main() {"]
+#[doc = "     tokio::runtime::Builder::new_multi_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             println!(\"Hello world\");"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ### Using current thread runtime"]
+#[doc = ""]
+#[doc = " The basic scheduler is single-threaded."]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " #[tokio::main(flavor = \"current_thread\")]"]
+#[doc = " async fn main() {"]
+#[doc = "     println!(\"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::main]`"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " fn main() {"]
+#[doc = "     tokio::runtime::Builder::new_current_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             println!(\"Hello world\");"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ### Set number of worker threads"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " #[tokio::main(worker_threads = 2)]"]
+#[doc = " async fn main() {"]
+#[doc = "     println!(\"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::main]`"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " fn main() {"]
+#[doc = "     tokio::runtime::Builder::new_multi_thread()"]
+#[doc = "         .worker_threads(2)"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             println!(\"Hello world\");"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ### Configure the runtime to start with time paused"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " #[tokio::main(flavor = \"current_thread\", start_paused = true)]"]
+#[doc = " async fn main() {"]
+#[doc = "     println!(\"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::main]`"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " fn main() {"]
+#[doc = "     tokio::runtime::Builder::new_current_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .start_paused(true)"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             println!(\"Hello world\");"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Note that `start_paused` requires the `test-util` feature to be enabled."]
+#[doc = ""]
+#[doc = " ### Rename package"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " use tokio as tokio1;"]
+#[doc = ""]
+#[doc = " #[tokio1::main(crate = \"tokio1\")]"]
+#[doc = " async fn main() {"]
+#[doc = "     println!(\"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::main]`"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " use tokio as tokio1;"]
+#[doc = ""]
+#[doc = " fn main() {"]
+#[doc = "     tokio1::runtime::Builder::new_multi_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             println!(\"Hello world\");"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+#[cfg(not(test))]
+pub fn main(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(main), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Marks async function to be executed by selected runtime. This macro helps set up a `Runtime`"]
+#[doc = " without requiring the user to use [Runtime](../tokio/runtime/struct.Runtime.html) or"]
+#[doc = " [Builder](../tokio/runtime/struct.builder.html) directly."]
+#[doc = ""]
+#[doc = " ## Function arguments:"]
+#[doc = ""]
+#[doc = " Arguments are allowed for any functions aside from `main` which is special"]
+#[doc = ""]
+#[doc = " ## Usage"]
+#[doc = ""]
+#[doc = " ### Using default"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " #[tokio::main(flavor = \"current_thread\")]"]
+#[doc = " async fn main() {"]
+#[doc = "     println!(\"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::main]`"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " fn main() {"]
+#[doc = "     tokio::runtime::Builder::new_current_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             println!(\"Hello world\");"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ### Rename package"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " use tokio as tokio1;"]
+#[doc = ""]
+#[doc = " #[tokio1::main(crate = \"tokio1\")]"]
+#[doc = " async fn main() {"]
+#[doc = "     println!(\"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::main]`"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " use tokio as tokio1;"]
+#[doc = ""]
+#[doc = " fn main() {"]
+#[doc = "     tokio1::runtime::Builder::new_multi_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             println!(\"Hello world\");"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+#[cfg(not(test))]
+pub fn main_rt(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(main_rt), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Marks async function to be executed by runtime, suitable to test environment."]
+#[doc = " This macro helps set up a `Runtime` without requiring the user to use"]
+#[doc = " [Runtime](../tokio/runtime/struct.Runtime.html) or"]
+#[doc = " [Builder](../tokio/runtime/struct.Builder.html) directly."]
+#[doc = ""]
+#[doc = " Note: This macro is designed to be simplistic and targets applications that"]
+#[doc = " do not require a complex setup. If the provided functionality is not"]
+#[doc = " sufficient, you may be interested in using"]
+#[doc = " [Builder](../tokio/runtime/struct.Builder.html), which provides a more"]
+#[doc = " powerful interface."]
+#[doc = ""]
+#[doc = " # Multi-threaded runtime"]
+#[doc = ""]
+#[doc = " To use the multi-threaded runtime, the macro can be configured using"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[tokio::test(flavor = \"multi_thread\", worker_threads = 1)]"]
+#[doc = " async fn my_test() {"]
+#[doc = "     assert!(true);"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " The `worker_threads` option configures the number of worker threads, and"]
+#[doc = " defaults to the number of cpus on the system. This is the default"]
+#[doc = " flavor."]
+#[doc = ""]
+#[doc = " Note: The multi-threaded runtime requires the `rt-multi-thread` feature"]
+#[doc = " flag."]
+#[doc = ""]
+#[doc = " # Current thread runtime"]
+#[doc = ""]
+#[doc = " The default test runtime is single-threaded. Each test gets a"]
+#[doc = " separate current-thread runtime."]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[tokio::test]"]
+#[doc = " async fn my_test() {"]
+#[doc = "     assert!(true);"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ## Usage"]
+#[doc = ""]
+#[doc = " ### Using the multi-thread runtime"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[tokio::test(flavor = \"multi_thread\")]"]
+#[doc = " async fn my_test() {"]
+#[doc = "     assert!(true);"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::test]`"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[test]"]
+#[doc = " fn my_test() {"]
+#[doc = "     tokio::runtime::Builder::new_multi_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             assert!(true);"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ### Using current thread runtime"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[tokio::test]"]
+#[doc = " async fn my_test() {"]
+#[doc = "     assert!(true);"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::test]`"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[test]"]
+#[doc = " fn my_test() {"]
+#[doc = "     tokio::runtime::Builder::new_current_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             assert!(true);"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ### Set number of worker threads"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[tokio::test(flavor =\"multi_thread\", worker_threads = 2)]"]
+#[doc = " async fn my_test() {"]
+#[doc = "     assert!(true);"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::test]`"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[test]"]
+#[doc = " fn my_test() {"]
+#[doc = "     tokio::runtime::Builder::new_multi_thread()"]
+#[doc = "         .worker_threads(2)"]
+#[doc = "         .enable_all()"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             assert!(true);"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " ### Configure the runtime to start with time paused"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[tokio::test(start_paused = true)]"]
+#[doc = " async fn my_test() {"]
+#[doc = "     assert!(true);"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Equivalent code not using `#[tokio::test]`"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[test]"]
+#[doc = " fn my_test() {"]
+#[doc = "     tokio::runtime::Builder::new_current_thread()"]
+#[doc = "         .enable_all()"]
+#[doc = "         .start_paused(true)"]
+#[doc = "         .build()"]
+#[doc = "         .unwrap()"]
+#[doc = "         .block_on(async {"]
+#[doc = "             assert!(true);"]
+#[doc = "         })"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Note that `start_paused` requires the `test-util` feature to be enabled."]
+#[doc = ""]
+#[doc = " ### Rename package"]
+#[doc = ""]
+#[doc = " ```rust"]
+#[doc = " use tokio as tokio1;"]
+#[doc = ""]
+#[doc = " #[tokio1::test(crate = \"tokio1\")]"]
+#[doc = " async fn my_test() {"]
+#[doc = "     println!(\"Hello world\");"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn test(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(test), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Marks async function to be executed by runtime, suitable to test environment"]
+#[doc = ""]
+#[doc = " ## Usage"]
+#[doc = ""]
+#[doc = " ```no_run"]
+#[doc = " #[tokio::test]"]
+#[doc = " async fn my_test() {"]
+#[doc = "     assert!(true);"]
+#[doc = " }"]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn test_rt(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(test_rt), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Always fails with the error message below."]
+#[doc = " ```text"]
+#[doc = " The #[tokio::main] macro requires rt or rt-multi-thread."]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn main_fail(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(main_fail), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Always fails with the error message below."]
+#[doc = " ```text"]
+#[doc = " The #[tokio::test] macro requires rt or rt-multi-thread."]
+#[doc = " ```"]
+#[proc_macro_attribute]
+pub fn test_fail(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(stringify!(test_fail), args, input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Implementation detail of the `select!` macro. This macro is **not** intended"]
+#[doc = " to be used as part of the public API and is permitted to change."]
+#[proc_macro]
+#[doc(hidden)]
+pub fn select_priv_declare_output_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro(stringify!(select_priv_declare_output_enum), input)
+}
+#[cfg(not(feature = "native"))]
+#[doc = " Implementation detail of the `select!` macro. This macro is **not** intended"]
+#[doc = " to be used as part of the public API and is permitted to change."]
+#[proc_macro]
+#[doc(hidden)]
+pub fn select_priv_clean_pattern(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro(stringify!(select_priv_clean_pattern), input)
+}
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,51 @@
+//! Checks the crate in `tests/invalid`, in which every use of the macros is
+//! wrong in a different way, and compares the errors with upstream's
+//! messages and spans.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+const ERRORS: &[&str] = &[
+    "src/lib.rs:1:66: error: The `worker_threads` option requires the `multi_thread` runtime flavor. Use `#[tokio::main(flavor = \"multi_thread\")]`",
+    "src/lib.rs:4:62: error: The `start_paused` option requires the `current_thread` runtime flavor. Use `#[tokio::test(flavor = \"current_thread\")]`",
+    "src/lib.rs:7:31: error: The single threaded runtime flavor is called `current_thread`.",
+    "src/lib.rs:10:39: error: `worker_threads` may not be 0.",
+    "src/lib.rs:13:39: error: Failed to parse value of `worker_threads` as integer.",
+    "src/lib.rs:16:37: error: Failed to parse value of `start_paused` as bool.",
+    "src/lib.rs:19:30: error: Failed to parse value of `crate` as ident.",
+    "src/lib.rs:22:58: error: `flavor` set multiple times.",
+    "src/lib.rs:25:22: error: Unknown attribute threads is specified; expected one of: `flavor`, `worker_threads`, `start_paused`, `crate`",
+    "src/lib.rs:28:22: error: The `flavor` attribute requires an argument.",
+    "src/lib.rs:32:1: error: the `async` keyword is missing from the function declaration",
+    "src/lib.rs:35:10: error: the main function cannot accept arguments",
+    "src/lib.rs:38:1: error: second test attribute is supplied",
+    "src/lib.rs:41:1: error: The default runtime flavor is `multi_thread`, but the `rt-multi-thread` feature is disabled.",
+];
+
+#[test]
+fn invalid_options() {
+    let invalid = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("invalid");
+    let target_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("invalid");
+    let mut cargo = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo
+        .args(&["check", "--quiet", "--message-format", "short"])
+        .current_dir(&invalid)
+        .arg("--target-dir")
+        .arg(&target_dir);
+    if cfg!(feature = "native") {
+        cargo.args(&["--features", "native"]);
+    }
+    let output = cargo.output().unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let errors: Vec<&str> = stderr
+        .lines()
+        .filter(|line| line.starts_with("src/lib.rs:") && line.contains(": error: "))
+        .collect();
+    assert_eq!(errors, ERRORS, "{}", stderr);
+}
@@ -0,0 +1,42 @@
+#[tokio_macros::main(flavor = "current_thread", worker_threads = 4)]
+async fn worker_threads_on_current_thread() {}
+
+#[tokio_macros::test(flavor = "multi_thread", start_paused = true)]
+async fn start_paused_on_multi_thread() {}
+
+#[tokio_macros::main(flavor = "single_thread")]
+async fn unknown_flavor() {}
+
+#[tokio_macros::main(worker_threads = 0)]
+async fn zero_worker_threads() {}
+
+#[tokio_macros::main(worker_threads = "four")]
+async fn worker_threads_not_an_integer() {}
+
+#[tokio_macros::test(start_paused = 1)]
+async fn start_paused_not_a_bool() {}
+
+#[tokio_macros::main(crate = 1)]
+async fn crate_not_an_ident() {}
+
+#[tokio_macros::main(flavor = "current_thread", flavor = "current_thread")]
+async fn flavor_twice() {}
+
+#[tokio_macros::main(threads = 2)]
+async fn unknown_option() {}
+
+#[tokio_macros::main(flavor)]
+async fn option_without_value() {}
+
+#[tokio_macros::main]
+fn not_async() {}
+
+#[tokio_macros::main]
+async fn main(_: u32) {}
+
+#[tokio_macros::test]
+#[test]
+async fn second_test_attribute() {}
+
+#[tokio_macros::main_rt]
+async fn multi_thread_without_feature() {}
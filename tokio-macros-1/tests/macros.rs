@@ -0,0 +1,103 @@
+use std::cell::Cell;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+#[tokio_macros::test]
+async fn test_defaults_to_current_thread() {
+    assert_eq!(
+        Handle::current().runtime_flavor(),
+        RuntimeFlavor::CurrentThread
+    );
+}
+
+#[tokio_macros::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_multi_thread() {
+    let handle = Handle::current();
+    assert_eq!(handle.runtime_flavor(), RuntimeFlavor::MultiThread);
+    assert_eq!(handle.metrics().num_workers(), 2);
+    assert_eq!(tokio::spawn(async { 3 }).await.unwrap(), 3);
+}
+
+#[tokio_macros::test(flavor = "current_thread")]
+async fn test_returns_result() -> Result<(), String> {
+    tokio::task::yield_now().await;
+    Ok(())
+}
+
+#[tokio_macros::main]
+async fn main_flavor() -> RuntimeFlavor {
+    Handle::current().runtime_flavor()
+}
+
+#[tokio_macros::main(flavor = "current_thread")]
+async fn main_current_thread(a: u32, b: u32) -> (RuntimeFlavor, u32) {
+    (Handle::current().runtime_flavor(), a + b)
+}
+
+#[tokio_macros::main(worker_threads = 3)]
+async fn main_worker_threads() -> usize {
+    Handle::current().metrics().num_workers()
+}
+
+#[tokio_macros::main_rt(flavor = "current_thread")]
+async fn main_rt() -> RuntimeFlavor {
+    Handle::current().runtime_flavor()
+}
+
+#[test]
+fn main_defaults_to_multi_thread() {
+    assert_eq!(main_flavor(), RuntimeFlavor::MultiThread);
+    assert_eq!(main_current_thread(1, 2), (RuntimeFlavor::CurrentThread, 3));
+    assert_eq!(main_worker_threads(), 3);
+    assert_eq!(main_rt(), RuntimeFlavor::CurrentThread);
+}
+
+thread_local! {
+    static START_PAUSED: Cell<Option<bool>> = Cell::new(None);
+}
+
+/// Stands in for tokio through `crate = "recording"`, to see what the
+/// expansion asks of the runtime builder.
+mod recording {
+    pub use tokio::pin;
+
+    pub mod runtime {
+        use std::io;
+
+        pub struct Builder(tokio::runtime::Builder);
+
+        impl Builder {
+            pub fn new_current_thread() -> Self {
+                Builder(tokio::runtime::Builder::new_current_thread())
+            }
+
+            pub fn start_paused(self, start_paused: bool) -> Self {
+                super::super::START_PAUSED.with(|cell| cell.set(Some(start_paused)));
+                self
+            }
+
+            pub fn enable_all(mut self) -> Self {
+                self.0.enable_all();
+                self
+            }
+
+            pub fn build(mut self) -> io::Result<tokio::runtime::Runtime> {
+                self.0.build()
+            }
+        }
+    }
+}
+
+#[tokio_macros::test(crate = "recording", start_paused = true)]
+async fn test_start_paused() {
+    assert_eq!(START_PAUSED.with(Cell::get), Some(true));
+}
+
+#[tokio_macros::main(crate = "recording", flavor = "current_thread", start_paused = false)]
+async fn main_start_paused() -> Option<bool> {
+    START_PAUSED.with(Cell::get)
+}
+
+#[test]
+fn main_with_crate() {
+    assert_eq!(main_start_paused(), Some(false));
+}
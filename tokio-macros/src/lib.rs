@@ -1,5 +1,9 @@
-static WASM: &[u8] = include_bytes!("tokio-macros.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("tokio-macros.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[doc = " Marks async function to be executed by selected runtime."]
 #[doc = ""]
 #[doc = " ## Options:"]
@@ -39,6 +43,7 @@ pub fn main_threaded(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(main_threaded), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Marks async function to be executed by selected runtime."]
 #[doc = ""]
 #[doc = " ## Options:"]
@@ -77,6 +82,7 @@ pub fn main(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(main), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Marks async function to be executed by selected runtime."]
 #[doc = ""]
 #[doc = " ## Options:"]
@@ -105,6 +111,7 @@ pub fn main_basic(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(main_basic), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Marks async function to be executed by runtime, suitable to test environment"]
 #[doc = ""]
 #[doc = " ## Options:"]
@@ -138,6 +145,7 @@ pub fn test_threaded(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(test_threaded), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Marks async function to be executed by runtime, suitable to test environment"]
 #[doc = ""]
 #[doc = " ## Options:"]
@@ -171,6 +179,7 @@ pub fn test(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(test), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Marks async function to be executed by runtime, suitable to test environment"]
 #[doc = ""]
 #[doc = " ## Options:"]
@@ -192,6 +201,7 @@ pub fn test_basic(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(test_basic), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[doc = " Implementation detail of the `select!` macro. This macro is **not** intended"]
 #[doc = " to be used as part of the public API and is permitted to change."]
 #[proc_macro]
@@ -199,3 +209,5 @@ pub fn test_basic(
 pub fn select_priv_declare_output_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     MACRO.proc_macro(stringify!(select_priv_declare_output_enum), input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
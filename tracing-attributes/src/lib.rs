@@ -1,29 +1,235 @@
-static WASM: &[u8] = include_bytes!("tracing-attributes.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("tracing-attributes.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[doc = " Instruments a function to create and enter a `tracing` [span] every time"]
 #[doc = " the function is called."]
 #[doc = ""]
-#[doc = " The generated span's name will be the name of the function. Any arguments"]
-#[doc = " to that function will be recorded as fields using [`fmt::Debug`]. To skip"]
-#[doc = " recording a function's or method's argument, pass the argument's name"]
-#[doc = " to the `skip` argument on the `#[instrument]` macro. For example,"]
-#[doc = " `skip` can be used when an argument to an instrumented function does"]
-#[doc = " not implement [`fmt::Debug`], or to exclude an argument with a verbose"]
-#[doc = " or costly Debug implementation. Note that:"]
+#[doc = " Unless overridden, a span with the [`INFO`] [level] will be generated."]
+#[doc = " The generated span's name will be the name of the function."]
+#[doc = " By default, all arguments to the function are included as fields on the"]
+#[doc = " span. Arguments that are `tracing` [primitive types] implementing the"]
+#[doc = " [`Value` trait] will be recorded as fields of that type. Types which do"]
+#[doc = " not implement `Value` will be recorded using [`fmt::Debug`]."]
+#[doc = ""]
+#[doc = " [primitive types]: https://docs.rs/tracing/latest/tracing/field/trait.Value.html#foreign-impls"]
+#[doc = " [`Value` trait]: https://docs.rs/tracing/latest/tracing/field/trait.Value.html"]
+#[doc = ""]
+#[doc = " # Overriding Span Attributes"]
+#[doc = ""]
+#[doc = " To change the [name] of the generated span, add a `name` argument to the"]
+#[doc = " `#[instrument]` macro, followed by an equals sign and a string literal. For"]
+#[doc = " example:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = ""]
+#[doc = " // The generated span's name will be \"my_span\" rather than \"my_function\"."]
+#[doc = " #[instrument(name = \"my_span\")]"]
+#[doc = " pub fn my_function() {"]
+#[doc = "     // ... do something incredibly interesting and important ..."]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " To override the [target] of the generated span, add a `target` argument to"]
+#[doc = " the `#[instrument]` macro, followed by an equals sign and a string literal"]
+#[doc = " for the new target. The [module path] is still recorded separately. For"]
+#[doc = " example:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " pub mod my_module {"]
+#[doc = "     # use tracing_attributes::instrument;"]
+#[doc = "     // The generated span's target will be \"my_crate::some_special_target\","]
+#[doc = "     // rather than \"my_crate::my_module\"."]
+#[doc = "     #[instrument(target = \"my_crate::some_special_target\")]"]
+#[doc = "     pub fn my_function() {"]
+#[doc = "         // ... all kinds of neat code in here ..."]
+#[doc = "     }"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Finally, to override the [level] of the generated span, add a `level`"]
+#[doc = " argument, followed by an equals sign and a string literal with the name of"]
+#[doc = " the desired level. Level names are not case sensitive. For example:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " // The span's level will be TRACE rather than INFO."]
+#[doc = " #[instrument(level = \"trace\")]"]
+#[doc = " pub fn my_function() {"]
+#[doc = "     // ... I have written a truly marvelous implementation of this function,"]
+#[doc = "     // which this example is too narrow to contain ..."]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Skipping Fields"]
+#[doc = ""]
+#[doc = " To skip recording one or more arguments to a function or method, pass"]
+#[doc = " the argument's name inside the `skip()` argument on the `#[instrument]`"]
+#[doc = " macro. This can be used when an argument to an instrumented function does"]
+#[doc = " not implement [`fmt::Debug`], or to exclude an argument with a verbose or"]
+#[doc = " costly `Debug` implementation. Note that:"]
+#[doc = ""]
 #[doc = " - multiple argument names can be passed to `skip`."]
 #[doc = " - arguments passed to `skip` do _not_ need to implement `fmt::Debug`."]
 #[doc = ""]
-#[doc = " You can also pass additional fields (key-value pairs with arbitrary data)"]
-#[doc = " to the generated span. This is achieved using the `fields` argument on the"]
-#[doc = " `#[instrument]` macro. You can use a string, integer or boolean literal as"]
-#[doc = " a value for each field. The name of the field must be a single valid Rust"]
-#[doc = " identifier, nested (dotted) field names are not supported."]
+#[doc = " You can also use `skip_all` to skip all arguments."]
 #[doc = ""]
-#[doc = " Note that overlap between the names of fields and (non-skipped) arguments"]
-#[doc = " will result in a compile error."]
+#[doc = " ## Examples"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " # use std::collections::HashMap;"]
+#[doc = " // This type doesn't implement `fmt::Debug`!"]
+#[doc = " struct NonDebug;"]
+#[doc = ""]
+#[doc = " // `arg` will be recorded, while `non_debug` will not."]
+#[doc = " #[instrument(skip(non_debug))]"]
+#[doc = " fn my_function(arg: usize, non_debug: NonDebug) {"]
+#[doc = "     // ..."]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " // These arguments are huge"]
+#[doc = " #[instrument(skip_all)]"]
+#[doc = " fn my_big_data_function(large: Vec<u8>, also_large: HashMap<String, String>) {"]
+#[doc = "     // ..."]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Skipping the `self` parameter:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[derive(Debug)]"]
+#[doc = " struct MyType {"]
+#[doc = "    data: Vec<u8>, // Suppose this buffer is often quite long..."]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " impl MyType {"]
+#[doc = "     // Suppose we don't want to print an entire kilobyte of `data`"]
+#[doc = "     // every time this is called..."]
+#[doc = "     #[instrument(skip(self))]"]
+#[doc = "     pub fn my_method(&mut self, an_interesting_argument: usize) {"]
+#[doc = "          // ... do something (hopefully, using all that `data`!)"]
+#[doc = "     }"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " # Adding Fields"]
+#[doc = ""]
+#[doc = " Additional fields (key-value pairs with arbitrary data) can be passed"]
+#[doc = " to the generated span through the `fields` argument on the"]
+#[doc = " `#[instrument]` macro. Arbitrary expressions are accepted as value"]
+#[doc = " for each field. The name of the field must be a single valid Rust"]
+#[doc = " identifier, or a constant expression that evaluates to one, enclosed in curly"]
+#[doc = " braces. Note that nested (dotted) field names are also supported. Any"]
+#[doc = " Rust expression can be used as a field value in this manner. These"]
+#[doc = " expressions will be evaluated at the beginning of the function's body, so"]
+#[doc = " arguments to the function may be used in these expressions. Field names may"]
+#[doc = " also be specified *without* values. Doing so will result in an [empty field]"]
+#[doc = " whose value may be recorded later within the function body."]
+#[doc = ""]
+#[doc = " Note that defining a field with the same name as a (non-skipped)"]
+#[doc = " argument will implicitly skip the argument, unless the field is provided"]
+#[doc = " via a constant expression (e.g. {EXPR} or {const_fn()}) as deduplicating"]
+#[doc = " would incur a runtime cost. In this case, the"]
+#[doc = " field must be explicitly skipped."]
+#[doc = ""]
+#[doc = " ## Examples"]
+#[doc = ""]
+#[doc = " Adding a new field based on the value of an argument:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = ""]
+#[doc = " // This will record a field named \"i\" with the value of `i` *and* a field"]
+#[doc = " // named \"next\" with the value of `i` + 1."]
+#[doc = " #[instrument(fields(next = i + 1))]"]
+#[doc = " pub fn my_function(i: usize) {"]
+#[doc = "     // ..."]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Recording specific properties of a struct as their own fields:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # mod http {"]
+#[doc = " #   pub struct Error;"]
+#[doc = " #   pub struct Response<B> { pub(super) _b: std::marker::PhantomData<B> }"]
+#[doc = " #   pub struct Request<B> { _b: B }"]
+#[doc = " #   impl<B> std::fmt::Debug for Request<B> {"]
+#[doc = " #       fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {"]
+#[doc = " #           f.pad(\"request\")"]
+#[doc = " #       }"]
+#[doc = " #   }"]
+#[doc = " #   impl<B> Request<B> {"]
+#[doc = " #       pub fn uri(&self) -> &str { \"fake\" }"]
+#[doc = " #       pub fn method(&self) -> &str { \"GET\" }"]
+#[doc = " #   }"]
+#[doc = " # }"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = ""]
+#[doc = " // This will record the request's URI and HTTP method as their own separate"]
+#[doc = " // fields."]
+#[doc = " #[instrument(fields(http.uri = req.uri(), http.method = req.method()))]"]
+#[doc = " pub fn handle_request<B>(req: http::Request<B>) -> http::Response<B> {"]
+#[doc = "     // ... handle the request ..."]
+#[doc = "     # http::Response { _b: std::marker::PhantomData }"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " This can be used in conjunction with `skip` or `skip_all` to record only"]
+#[doc = " some fields of a struct:"]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " // Remember the struct with the very large `data` field from the earlier"]
+#[doc = " // example? Now it also has a `name`, which we might want to include in"]
+#[doc = " // our span."]
+#[doc = " #[derive(Debug)]"]
+#[doc = " struct MyType {"]
+#[doc = "    name: &'static str,"]
+#[doc = "    data: Vec<u8>,"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " impl MyType {"]
+#[doc = "     // This will skip the `data` field, but will include `self.name`,"]
+#[doc = "     // formatted using `fmt::Display`."]
+#[doc = "     #[instrument(skip(self), fields(self.name = %self.name))]"]
+#[doc = "     pub fn my_method(&mut self, an_interesting_argument: usize) {"]
+#[doc = "          // ... do something (hopefully, using all that `data`!)"]
+#[doc = "     }"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " Adding an empty field to be recorded later:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = ""]
+#[doc = " // This function does a very interesting and important mathematical calculation."]
+#[doc = " // Suppose we want to record both the inputs to the calculation *and* its result..."]
+#[doc = " #[instrument(fields(result))]"]
+#[doc = " pub fn do_calculation(input_1: usize, input_2: usize) -> usize {"]
+#[doc = "     // Rerform the calculation."]
+#[doc = "     let result = input_1 + input_2;"]
+#[doc = ""]
+#[doc = "     // Record the result as part of the current span."]
+#[doc = "     tracing::Span::current().record(\"result\", &result);"]
+#[doc = ""]
+#[doc = "     // Now, the result will also be included on this event!"]
+#[doc = "     tracing::info!(\"calculation complete!\");"]
+#[doc = ""]
+#[doc = "     // ... etc ..."]
+#[doc = "     # 0"]
+#[doc = " }"]
+#[doc = " ```"]
 #[doc = ""]
 #[doc = " # Examples"]
+#[doc = ""]
 #[doc = " Instrumenting a function:"]
+#[doc = ""]
 #[doc = " ```"]
 #[doc = " # use tracing_attributes::instrument;"]
 #[doc = " #[instrument]"]
@@ -37,11 +243,15 @@ static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
 #[doc = " Setting the level for the generated span:"]
 #[doc = " ```"]
 #[doc = " # use tracing_attributes::instrument;"]
-#[doc = " #[instrument(level = \"debug\")]"]
+#[doc = " # use tracing::Level;"]
+#[doc = " #[instrument(level = Level::DEBUG)]"]
 #[doc = " pub fn my_function() {"]
 #[doc = "     // ..."]
 #[doc = " }"]
 #[doc = " ```"]
+#[doc = " Levels can be specified either with [`Level`] constants, literal strings"]
+#[doc = " (e.g., `\"debug\"`, `\"info\"`) or numerically (1—5, corresponding to [`Level::TRACE`]—[`Level::ERROR`])."]
+#[doc = ""]
 #[doc = " Overriding the generated span's name:"]
 #[doc = " ```"]
 #[doc = " # use tracing_attributes::instrument;"]
@@ -58,6 +268,47 @@ static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
 #[doc = "     // ..."]
 #[doc = " }"]
 #[doc = " ```"]
+#[doc = " Overriding the generated span's parent:"]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[instrument(parent = None)]"]
+#[doc = " pub fn my_function() {"]
+#[doc = "     // ..."]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " // A struct which owns a span handle."]
+#[doc = " struct MyStruct"]
+#[doc = " {"]
+#[doc = "     span: tracing::Span"]
+#[doc = " }"]
+#[doc = ""]
+#[doc = " impl MyStruct"]
+#[doc = " {"]
+#[doc = "     // Use the struct's `span` field as the parent span"]
+#[doc = "     #[instrument(parent = &self.span, skip(self))]"]
+#[doc = "     fn my_method(&self) {}"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = " Specifying [`follows_from`] relationships:"]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[instrument(follows_from = causes)]"]
+#[doc = " pub fn my_function(causes: &[tracing::Id]) {"]
+#[doc = "     // ..."]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = " Any expression of type `impl IntoIterator<Item = impl Into<Option<Id>>>`"]
+#[doc = " may be provided to `follows_from`; e.g.:"]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[instrument(follows_from = [cause])]"]
+#[doc = " pub fn my_function(cause: &tracing::span::EnteredSpan) {"]
+#[doc = "     // ..."]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
 #[doc = ""]
 #[doc = " To skip recording an argument, pass the argument's name to the `skip`:"]
 #[doc = ""]
@@ -71,18 +322,66 @@ static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
 #[doc = " }"]
 #[doc = " ```"]
 #[doc = ""]
-#[doc = " To add an additional context to the span, you can pass key-value pairs to `fields`:"]
+#[doc = " To add additional context to the span, pass key-value pairs to `fields`:"]
 #[doc = ""]
 #[doc = " ```"]
 #[doc = " # use tracing_attributes::instrument;"]
-#[doc = " #[instrument(fields(foo=\"bar\", id=1, show=true))]"]
-#[doc = " fn my_function(arg: usize) {"]
+#[doc = " #[derive(Debug)]"]
+#[doc = " struct Argument;"]
+#[doc = " impl Argument {"]
+#[doc = "     fn bar(&self) -> &'static str {"]
+#[doc = "         \"bar\""]
+#[doc = "     }"]
+#[doc = " }"]
+#[doc = " const FOOBAR: &'static str = \"foo.bar\";"]
+#[doc = " #[instrument(fields(foo=\"bar\", id=1, show=true, {FOOBAR}=%arg.bar()))]"]
+#[doc = " fn my_function(arg: Argument) {"]
 #[doc = "     // ..."]
 #[doc = " }"]
 #[doc = " ```"]
 #[doc = ""]
-#[doc = " If the function returns a `Result<T, E>` and `E` implements `std::fmt::Display`, you can add"]
-#[doc = " `err` to emit error events when the function returns `Err`:"]
+#[doc = " Adding the `ret` argument to `#[instrument]` will emit an event with the function's"]
+#[doc = " return value when the function returns:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[instrument(ret)]"]
+#[doc = " fn my_function() -> i32 {"]
+#[doc = "     42"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = " The return value event will have the same level as the span generated by `#[instrument]`."]
+#[doc = " By default, this will be [`INFO`], but if the level is overridden, the event will be at the same"]
+#[doc = " level."]
+#[doc = ""]
+#[doc = " It's also possible to override the level for the `ret` event independently:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " # use tracing::Level;"]
+#[doc = " #[instrument(ret(level = Level::WARN))]"]
+#[doc = " fn my_function() -> i32 {"]
+#[doc = "     42"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " **Note**:  if the function returns a `Result<T, E>`, `ret` will record returned values if and"]
+#[doc = " only if the function returns [`Result::Ok`]."]
+#[doc = ""]
+#[doc = " By default, returned values will be recorded using their [`std::fmt::Debug`] implementations."]
+#[doc = " If a returned value implements [`std::fmt::Display`], it can be recorded using its `Display`"]
+#[doc = " implementation instead, by writing `ret(Display)`:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[instrument(ret(Display))]"]
+#[doc = " fn my_function() -> i32 {"]
+#[doc = "     42"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " If the function returns a `Result<T, E>` and `E` implements `std::fmt::Display`, adding"]
+#[doc = " `err` or `err(Display)` will emit error events when the function returns `Err`:"]
 #[doc = ""]
 #[doc = " ```"]
 #[doc = " # use tracing_attributes::instrument;"]
@@ -92,7 +391,45 @@ static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
 #[doc = " }"]
 #[doc = " ```"]
 #[doc = ""]
-#[doc = " If `tracing_futures` is specified as a dependency in `Cargo.toml`,"]
+#[doc = " The level of the error value event defaults to `ERROR`."]
+#[doc = ""]
+#[doc = " Similarly, overriding the level of the `err` event :"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " # use tracing::Level;"]
+#[doc = " #[instrument(err(level = Level::INFO))]"]
+#[doc = " fn my_function(arg: usize) -> Result<(), std::io::Error> {"]
+#[doc = "     Ok(())"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " By default, error values will be recorded using their `std::fmt::Display` implementations."]
+#[doc = " If an error implements `std::fmt::Debug`, it can be recorded using its `Debug` implementation"]
+#[doc = " instead by writing `err(Debug)`:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[instrument(err(Debug))]"]
+#[doc = " fn my_function(arg: usize) -> Result<(), std::io::Error> {"]
+#[doc = "     Ok(())"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
+#[doc = " If a `target` is specified, both the `ret` and `err` arguments will emit outputs to"]
+#[doc = " the declared target (or the default channel if `target` is not specified)."]
+#[doc = ""]
+#[doc = " The `ret` and `err` arguments can be combined in order to record an event if a"]
+#[doc = " function returns [`Result::Ok`] or [`Result::Err`]:"]
+#[doc = ""]
+#[doc = " ```"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[instrument(err, ret)]"]
+#[doc = " fn my_function(arg: usize) -> Result<(), std::io::Error> {"]
+#[doc = "     Ok(())"]
+#[doc = " }"]
+#[doc = " ```"]
+#[doc = ""]
 #[doc = " `async fn`s may also be instrumented:"]
 #[doc = ""]
 #[doc = " ```"]
@@ -128,34 +465,28 @@ static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
 #[doc = " }"]
 #[doc = " ```"]
 #[doc = ""]
-#[doc = " An interesting note on this subject is that references to the `Self`"]
-#[doc = " type inside the `fields` argument are only allowed when the instrumented"]
-#[doc = " function is a method aka. the function receives `self` as an argument."]
-#[doc = " For example, this *will not work* because it doesn't receive `self`:"]
-#[doc = " ```compile_fail"]
-#[doc = " # use tracing::instrument;"]
-#[doc = " use async_trait::async_trait;"]
+#[doc = " `const fn` cannot be instrumented, and will result in a compilation failure:"]
 #[doc = ""]
-#[doc = " #[async_trait]"]
-#[doc = " pub trait Bar {"]
-#[doc = "     async fn bar();"]
-#[doc = " }"]
-#[doc = ""]
-#[doc = " #[derive(Debug)]"]
-#[doc = " struct BarImpl(usize);"]
-#[doc = ""]
-#[doc = " #[async_trait]"]
-#[doc = " impl Bar for BarImpl {"]
-#[doc = "     #[instrument(fields(tmp = std::any::type_name::<Self>()))]"]
-#[doc = "     async fn bar() {}"]
-#[doc = " }"]
+#[doc = " ```compile_fail"]
+#[doc = " # use tracing_attributes::instrument;"]
+#[doc = " #[instrument]"]
+#[doc = " const fn my_const_function() {}"]
 #[doc = " ```"]
-#[doc = " Instead, you should manually rewrite any `Self` types as the type for"]
-#[doc = " which you implement the trait: `#[instrument(fields(tmp = std::any::type_name::<Bar>()))]`."]
 #[doc = ""]
 #[doc = " [span]: https://docs.rs/tracing/latest/tracing/span/index.html"]
+#[doc = " [name]: https://docs.rs/tracing/latest/tracing/struct.Metadata.html#method.name"]
+#[doc = " [target]: https://docs.rs/tracing/latest/tracing/struct.Metadata.html#method.target"]
+#[doc = " [level]: https://docs.rs/tracing/latest/tracing/struct.Level.html"]
+#[doc = " [module path]: https://docs.rs/tracing/latest/tracing/struct.Metadata.html#method.module_path"]
+#[doc = " [`INFO`]: https://docs.rs/tracing/latest/tracing/struct.Level.html#associatedconstant.INFO"]
+#[doc = " [empty field]: https://docs.rs/tracing/latest/tracing/field/struct.Empty.html"]
+#[doc = " [field syntax]: https://docs.rs/tracing/latest/tracing/#recording-fields"]
+#[doc = " [`follows_from`]: https://docs.rs/tracing/latest/tracing/struct.Span.html#method.follows_from"]
 #[doc = " [`tracing`]: https://github.com/tokio-rs/tracing"]
-#[doc = " [`fmt::Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html"]
+#[doc = " [`fmt::Debug`]: std::fmt::Debug"]
+#[doc = " [`Level`]: https://docs.rs/tracing/latest/tracing/struct.Level.html"]
+#[doc = " [`Level::TRACE`]: https://docs.rs/tracing/latest/tracing/struct.Level.html#associatedconstant.TRACE"]
+#[doc = " [`Level::ERROR`]: https://docs.rs/tracing/latest/tracing/struct.Level.html#associatedconstant.ERROR"]
 #[proc_macro_attribute]
 pub fn instrument(
     args: proc_macro::TokenStream,
@@ -163,3 +494,5 @@ pub fn instrument(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(instrument), args, input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,72 @@
+use tracing::subscriber::with_default;
+use tracing::Level;
+use tracing_attributes::instrument;
+use tracing_mock::*;
+use tracing_test::{block_on_future, PollN};
+
+use std::convert::TryFrom;
+use std::num::TryFromIntError;
+
+#[instrument(err(Display))]
+fn err_display() -> Result<u8, TryFromIntError> {
+    u8::try_from(1234)
+}
+
+#[instrument(err(Display, level = "warn"))]
+async fn err_display_async(polls: usize) -> Result<u8, TryFromIntError> {
+    let future = PollN::new_ok(polls);
+    tracing::trace!(awaiting = true);
+    future.await.ok();
+    u8::try_from(1234)
+}
+
+#[test]
+fn test_err_display() {
+    let span = expect::span().named("err_display");
+    let (subscriber, handle) = subscriber::mock()
+        .new_span(span.clone())
+        .enter(span.clone())
+        .event(
+            expect::event().at_level(Level::ERROR).with_fields(
+                expect::field("error")
+                    .with_value(&tracing::field::display(u8::try_from(1234).unwrap_err())),
+            ),
+        )
+        .exit(span.clone())
+        .drop_span(span)
+        .only()
+        .run_with_handle();
+    with_default(subscriber, || err_display().ok());
+    handle.assert_finished();
+}
+
+#[test]
+fn test_err_display_async() {
+    let span = expect::span().named("err_display_async");
+    let (subscriber, handle) = subscriber::mock()
+        .new_span(span.clone())
+        .enter(span.clone())
+        .event(
+            expect::event()
+                .with_fields(expect::field("awaiting").with_value(&true))
+                .at_level(Level::TRACE),
+        )
+        .exit(span.clone())
+        .enter(span.clone())
+        .event(
+            expect::event().at_level(Level::WARN).with_fields(
+                expect::field("error")
+                    .with_value(&tracing::field::display(u8::try_from(1234).unwrap_err())),
+            ),
+        )
+        .exit(span.clone())
+        .enter(span.clone())
+        .exit(span.clone())
+        .drop_span(span)
+        .only()
+        .run_with_handle();
+    with_default(subscriber, || {
+        block_on_future(async { err_display_async(2).await }).ok();
+    });
+    handle.assert_finished();
+}
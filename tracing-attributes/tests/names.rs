@@ -1,8 +1,6 @@
-mod support;
-use support::*;
-
 use tracing::subscriber::with_default;
 use tracing_attributes::instrument;
+use tracing_mock::*;
 
 #[instrument]
 fn default_name() {}
@@ -19,10 +17,10 @@ fn custom_name_no_equals() {}
 #[test]
 fn default_name_test() {
     let (subscriber, handle) = subscriber::mock()
-        .new_span(span::mock().named("default_name"))
-        .enter(span::mock().named("default_name"))
-        .exit(span::mock().named("default_name"))
-        .done()
+        .new_span(expect::span().named("default_name"))
+        .enter(expect::span().named("default_name"))
+        .exit(expect::span().named("default_name"))
+        .only()
         .run_with_handle();
 
     with_default(subscriber, || {
@@ -35,10 +33,10 @@ fn default_name_test() {
 #[test]
 fn custom_name_test() {
     let (subscriber, handle) = subscriber::mock()
-        .new_span(span::mock().named("my_name"))
-        .enter(span::mock().named("my_name"))
-        .exit(span::mock().named("my_name"))
-        .done()
+        .new_span(expect::span().named("my_name"))
+        .enter(expect::span().named("my_name"))
+        .exit(expect::span().named("my_name"))
+        .only()
         .run_with_handle();
 
     with_default(subscriber, || {
@@ -51,10 +49,10 @@ fn custom_name_test() {
 #[test]
 fn custom_name_no_equals_test() {
     let (subscriber, handle) = subscriber::mock()
-        .new_span(span::mock().named("my_other_name"))
-        .enter(span::mock().named("my_other_name"))
-        .exit(span::mock().named("my_other_name"))
-        .done()
+        .new_span(expect::span().named("my_other_name"))
+        .enter(expect::span().named("my_other_name"))
+        .exit(expect::span().named("my_other_name"))
+        .only()
         .run_with_handle();
 
     with_default(subscriber, || {
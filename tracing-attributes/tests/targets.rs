@@ -1,8 +1,6 @@
-mod support;
-use support::*;
-
 use tracing::subscriber::with_default;
 use tracing_attributes::instrument;
+use tracing_mock::*;
 
 #[instrument]
 fn default_target() {}
@@ -26,36 +24,36 @@ mod my_mod {
 fn default_targets() {
     let (subscriber, handle) = subscriber::mock()
         .new_span(
-            span::mock()
+            expect::span()
                 .named("default_target")
                 .with_target(module_path!()),
         )
         .enter(
-            span::mock()
+            expect::span()
                 .named("default_target")
                 .with_target(module_path!()),
         )
         .exit(
-            span::mock()
+            expect::span()
                 .named("default_target")
                 .with_target(module_path!()),
         )
         .new_span(
-            span::mock()
+            expect::span()
                 .named("default_target")
                 .with_target(my_mod::MODULE_PATH),
         )
         .enter(
-            span::mock()
+            expect::span()
                 .named("default_target")
                 .with_target(my_mod::MODULE_PATH),
         )
         .exit(
-            span::mock()
+            expect::span()
                 .named("default_target")
                 .with_target(my_mod::MODULE_PATH),
         )
-        .done()
+        .only()
         .run_with_handle();
 
     with_default(subscriber, || {
@@ -69,25 +67,37 @@ fn default_targets() {
 #[test]
 fn custom_targets() {
     let (subscriber, handle) = subscriber::mock()
-        .new_span(span::mock().named("custom_target").with_target("my_target"))
-        .enter(span::mock().named("custom_target").with_target("my_target"))
-        .exit(span::mock().named("custom_target").with_target("my_target"))
         .new_span(
-            span::mock()
+            expect::span()
+                .named("custom_target")
+                .with_target("my_target"),
+        )
+        .enter(
+            expect::span()
+                .named("custom_target")
+                .with_target("my_target"),
+        )
+        .exit(
+            expect::span()
+                .named("custom_target")
+                .with_target("my_target"),
+        )
+        .new_span(
+            expect::span()
                 .named("custom_target")
                 .with_target("my_other_target"),
         )
         .enter(
-            span::mock()
+            expect::span()
                 .named("custom_target")
                 .with_target("my_other_target"),
         )
         .exit(
-            span::mock()
+            expect::span()
                 .named("custom_target")
                 .with_target("my_other_target"),
         )
-        .done()
+        .only()
         .run_with_handle();
 
     with_default(subscriber, || {
@@ -0,0 +1,55 @@
+//! The part of `tracing-test`, a crate of the tracing repository that isn't
+//! published, that the tests of tracing-attributes use.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A future that is pending `finish_at - 1` times before it resolves.
+pub struct PollN<T, E> {
+    and_return: Option<Result<T, E>>,
+    finish_at: usize,
+    polls: usize,
+}
+
+impl PollN<(), ()> {
+    pub fn new_ok(finish_at: usize) -> Self {
+        PollN {
+            and_return: Some(Ok(())),
+            finish_at,
+            polls: 0,
+        }
+    }
+
+    pub fn new_err(finish_at: usize) -> Self {
+        PollN {
+            and_return: Some(Err(())),
+            finish_at,
+            polls: 0,
+        }
+    }
+}
+
+impl<T: Unpin, E: Unpin> Future for PollN<T, E> {
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.polls += 1;
+        if this.polls == this.finish_at {
+            Poll::Ready(this.and_return.take().expect("polled after ready"))
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+pub fn block_on_future<F: Future>(future: F) -> F::Output {
+    let mut task = tokio_test::task::spawn(future);
+    loop {
+        if let Poll::Ready(output) = task.poll() {
+            break output;
+        }
+    }
+}
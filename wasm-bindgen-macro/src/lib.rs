@@ -1,5 +1,9 @@
-static WASM: &[u8] = include_bytes!("wasm-bindgen-macro.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("wasm-bindgen-macro.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn wasm_bindgen(
     args: proc_macro::TokenStream,
@@ -7,6 +11,7 @@ pub fn wasm_bindgen(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(wasm_bindgen), args, input)
 }
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn __wasm_bindgen_class_marker(
     args: proc_macro::TokenStream,
@@ -14,3 +19,5 @@ pub fn __wasm_bindgen_class_marker(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(__wasm_bindgen_class_marker), args, input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -1,5 +1,9 @@
-static WASM: &[u8] = include_bytes!("wasm-bindgen-test-macro.wasm");
-static MACRO: watt::WasmMacro = watt::WasmMacro::new(WASM);
+#[cfg(not(feature = "native"))]
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("wasm-bindgen-test-macro.wasm");
+#[cfg(not(feature = "native"))]
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[cfg(not(feature = "native"))]
 #[proc_macro_attribute]
 pub fn wasm_bindgen_test(
     args: proc_macro::TokenStream,
@@ -7,3 +11,5 @@ pub fn wasm_bindgen_test(
 ) -> proc_macro::TokenStream {
     MACRO.proc_macro_attribute(stringify!(wasm_bindgen_test), args, input)
 }
+#[cfg(feature = "native")]
+include!("../native/lib.rs");
@@ -0,0 +1,311 @@
+//! Helpers for the build scripts of the crates in this repository.
+//!
+//! Every crate calls [`check_entry_points`] and [`write_snapshot`] first
+//! thing in the `main` of its build script:
+//!
+//! ```no_run
+//! watt_contrib_build::check_entry_points();
+//! watt_contrib_build::write_snapshot();
+//! ```
+
+mod wasm;
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// How `src/lib.rs` invokes an entry point, which determines how many token
+/// streams the exported wasm function has to take.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Derive,
+    Attribute,
+    Function,
+}
+
+impl Kind {
+    const ALL: [Kind; 3] = [Kind::Derive, Kind::Attribute, Kind::Function];
+
+    fn method(self) -> &'static str {
+        match self {
+            Kind::Derive => "proc_macro_derive",
+            Kind::Attribute => "proc_macro_attribute",
+            Kind::Function => "proc_macro",
+        }
+    }
+
+    fn params(self) -> usize {
+        match self {
+            Kind::Derive | Kind::Function => 1,
+            Kind::Attribute => 2,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Call {
+    name: String,
+    kind: Kind,
+}
+
+/// Checks that every `MACRO.proc_macro*(stringify!(name), ..)` call in the
+/// crate's `src/lib.rs` names a function exported by the wasm module it
+/// loads, and that the function takes as many token streams as the call
+/// passes. Exits the build script with an error listing every mismatch.
+///
+/// Does nothing with the `native` feature enabled, since the crate then
+/// compiles the vendored source and doesn't load the module.
+pub fn check_entry_points() {
+    if env::var_os("CARGO_FEATURE_NATIVE").is_some() {
+        return;
+    }
+    let shim = Shim::read();
+    let exports = match wasm::exported_functions(&shim.module) {
+        Ok(exports) => exports,
+        Err(err) => fail(&format!(
+            "failed to parse {}: {}",
+            shim.wasm_path.display(),
+            err
+        )),
+    };
+
+    let errors = mismatches(&calls(&shim.lib), &exports);
+    if !errors.is_empty() {
+        let mut message = format!(
+            "{} does not match the entry points used in {}:\n",
+            shim.wasm_path.display(),
+            shim.lib_path.display(),
+        );
+        for error in errors {
+            let _ = writeln!(message, "  - {}", error);
+        }
+        fail(&message);
+    }
+}
+
+/// Decodes and validates the module that the crate's `src/lib.rs` loads, and
+/// writes its [snapshot](watt_contrib_runtime::snapshot) to `OUT_DIR`, where
+/// `watt_contrib_runtime::include_snapshot!` includes it from. Exits the
+/// build script with an error if the module doesn't decode or validate.
+///
+/// Does nothing with the `native` feature enabled.
+pub fn write_snapshot() {
+    if env::var_os("CARGO_FEATURE_NATIVE").is_some() {
+        return;
+    }
+    let shim = Shim::read();
+    let snapshot = match watt_contrib_runtime::snapshot::write(&shim.module) {
+        Ok(snapshot) => snapshot,
+        Err(err) => fail(&format!("{}: {}", shim.wasm_path.display(), err)),
+    };
+    let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR not set");
+    let path = Path::new(&out_dir).join(format!("{}.snapshot", shim.wasm_name));
+    if let Err(err) = fs::write(&path, snapshot) {
+        fail(&format!("failed to write {}: {}", path.display(), err));
+    }
+}
+
+/// A crate's `src/lib.rs` and the module it loads.
+struct Shim {
+    lib_path: PathBuf,
+    lib: String,
+    wasm_name: String,
+    wasm_path: PathBuf,
+    module: Vec<u8>,
+}
+
+impl Shim {
+    fn read() -> Self {
+        let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+        let src = Path::new(&manifest_dir).join("src");
+        let lib_path = src.join("lib.rs");
+        println!("cargo:rerun-if-changed={}", lib_path.display());
+
+        let lib = read_to_string(&lib_path);
+        let wasm_name = match wasm_file(&lib) {
+            Some(wasm_name) => wasm_name.to_owned(),
+            None => fail(&format!(
+                "no `include_snapshot!(\"*.wasm\")` in {}",
+                lib_path.display(),
+            )),
+        };
+        let wasm_path = src.join(&wasm_name);
+        println!("cargo:rerun-if-changed={}", wasm_path.display());
+
+        let module = match fs::read(&wasm_path) {
+            Ok(module) => module,
+            Err(err) => fail(&format!("failed to read {}: {}", wasm_path.display(), err)),
+        };
+        Shim {
+            lib_path,
+            lib,
+            wasm_name,
+            wasm_path,
+            module,
+        }
+    }
+}
+
+/// The entry points that a crate's `src/lib.rs` calls, each with the number
+/// of token streams the call passes. `cargo xtask audit` checks that the
+/// module exports exactly these.
+pub fn entry_points(lib: &str) -> Vec<(String, usize)> {
+    calls(lib)
+        .into_iter()
+        .map(|call| (call.name, call.kind.params()))
+        .collect()
+}
+
+fn read_to_string(path: &Path) -> String {
+    match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => fail(&format!("failed to read {}: {}", path.display(), err)),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message.trim_end());
+    process::exit(1);
+}
+
+/// The file name passed to `include_snapshot!` in the shim, or to
+/// `include_bytes!` in one that loads the raw module.
+fn wasm_file(lib: &str) -> Option<&str> {
+    let start = ["include_snapshot!(\"", "include_bytes!(\""]
+        .iter()
+        .find_map(|pattern| Some(lib.find(pattern)? + pattern.len()))?;
+    let len = lib[start..].find('"')?;
+    Some(&lib[start..start + len])
+}
+
+fn calls(lib: &str) -> Vec<Call> {
+    // rustfmt may break long calls over several lines.
+    let lib: String = lib.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut calls = Vec::new();
+    for kind in &Kind::ALL {
+        let pattern = format!("MACRO.{}(stringify!(", kind.method());
+        let mut rest = lib.as_str();
+        while let Some(start) = rest.find(&pattern) {
+            rest = &rest[start + pattern.len()..];
+            let end = rest.find(')').unwrap_or(rest.len());
+            calls.push(Call {
+                name: rest[..end].to_owned(),
+                kind: *kind,
+            });
+        }
+    }
+    calls
+}
+
+fn mismatches(calls: &[Call], exports: &[wasm::ExportedFunction]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for call in calls {
+        match exports.iter().find(|export| export.name == call.name) {
+            None => errors.push(format!(
+                "`{}` is called with `MACRO.{}` but not exported",
+                call.name,
+                call.kind.method(),
+            )),
+            Some(export) if export.params != call.kind.params() => errors.push(format!(
+                "`{}` is called with `MACRO.{}`, which passes {} token stream(s), \
+                 but the export takes {}",
+                call.name,
+                call.kind.method(),
+                call.kind.params(),
+                export.params,
+            )),
+            Some(_) => {}
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wasm::ExportedFunction;
+
+    const LIB: &str = r#"
+static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("tokio-macros.wasm");
+static MACRO: watt_contrib_runtime::WasmMacro =
+    watt_contrib_runtime::WasmMacro::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"), WASM);
+#[proc_macro_derive(Pread)]
+pub fn derive_pread(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro_derive(stringify!(derive_pread), input)
+}
+#[proc_macro_attribute]
+pub fn main(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    MACRO.proc_macro_attribute(
+        stringify!(main),
+        args,
+        input,
+    )
+}
+#[proc_macro]
+pub fn select(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    MACRO.proc_macro(stringify!(select), input)
+}
+"#;
+
+    fn export(name: &str, params: usize) -> ExportedFunction {
+        ExportedFunction {
+            name: name.to_owned(),
+            params,
+        }
+    }
+
+    #[test]
+    fn parse_lib() {
+        assert_eq!(wasm_file(LIB), Some("tokio-macros.wasm"));
+        assert_eq!(
+            wasm_file(r#"static WASM: &[u8] = include_bytes!("ctor.wasm");"#),
+            Some("ctor.wasm"),
+        );
+        assert_eq!(
+            calls(LIB),
+            [
+                Call {
+                    name: "derive_pread".to_owned(),
+                    kind: Kind::Derive,
+                },
+                Call {
+                    name: "main".to_owned(),
+                    kind: Kind::Attribute,
+                },
+                Call {
+                    name: "select".to_owned(),
+                    kind: Kind::Function,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn matching_exports() {
+        let exports = [
+            export("derive_pread", 1),
+            export("main", 2),
+            export("select", 1),
+            export("unrelated", 3),
+        ];
+        assert!(mismatches(&calls(LIB), &exports).is_empty());
+    }
+
+    #[test]
+    fn missing_and_wrong_arity() {
+        let exports = [export("derive_pread", 2), export("select", 1)];
+        assert_eq!(
+            mismatches(&calls(LIB), &exports),
+            [
+                "`derive_pread` is called with `MACRO.proc_macro_derive`, which passes 1 \
+                 token stream(s), but the export takes 2",
+                "`main` is called with `MACRO.proc_macro_attribute` but not exported",
+            ]
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! Just enough of the wasm binary format to list a module's exported
+//! functions and their parameter counts.
+
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    NotWasm,
+    UnexpectedEnd,
+    Malformed(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotWasm => f.write_str("not a wasm module"),
+            Error::UnexpectedEnd => f.write_str("unexpected end of module"),
+            Error::Malformed(what) => write!(f, "malformed {}", what),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExportedFunction {
+    pub name: String,
+    pub params: usize,
+}
+
+const SECTION_TYPE: u8 = 1;
+const SECTION_IMPORT: u8 = 2;
+const SECTION_FUNCTION: u8 = 3;
+const SECTION_EXPORT: u8 = 7;
+
+const KIND_FUNCTION: u8 = 0;
+const KIND_TABLE: u8 = 1;
+const KIND_MEMORY: u8 = 2;
+const KIND_GLOBAL: u8 = 3;
+
+/// Returns every exported function of `module`, in export order.
+pub fn exported_functions(module: &[u8]) -> Result<Vec<ExportedFunction>, Error> {
+    let mut reader = Reader::new(module);
+    if reader.bytes(4)? != b"\0asm" || reader.bytes(4)? != [1, 0, 0, 0] {
+        return Err(Error::NotWasm);
+    }
+
+    // Parameter counts of the entries in the type section.
+    let mut types = Vec::new();
+    // Type index of every function, imports first.
+    let mut functions = Vec::new();
+    let mut exports = Vec::new();
+
+    while !reader.is_empty() {
+        let id = reader.byte()?;
+        let size = reader.u32()? as usize;
+        let mut section = Reader::new(reader.bytes(size)?);
+        match id {
+            SECTION_TYPE => {
+                for _ in 0..section.u32()? {
+                    if section.byte()? != 0x60 {
+                        return Err(Error::Malformed("function type"));
+                    }
+                    let params = section.u32()? as usize;
+                    section.bytes(params)?;
+                    let results = section.u32()? as usize;
+                    section.bytes(results)?;
+                    types.push(params);
+                }
+            }
+            SECTION_IMPORT => {
+                for _ in 0..section.u32()? {
+                    section.name()?;
+                    section.name()?;
+                    match section.byte()? {
+                        KIND_FUNCTION => functions.push(section.u32()?),
+                        KIND_TABLE => {
+                            section.byte()?;
+                            section.limits()?;
+                        }
+                        KIND_MEMORY => section.limits()?,
+                        KIND_GLOBAL => {
+                            section.byte()?;
+                            section.byte()?;
+                        }
+                        _ => return Err(Error::Malformed("import")),
+                    }
+                }
+            }
+            SECTION_FUNCTION => {
+                for _ in 0..section.u32()? {
+                    functions.push(section.u32()?);
+                }
+            }
+            SECTION_EXPORT => {
+                for _ in 0..section.u32()? {
+                    let name = section.name()?;
+                    let kind = section.byte()?;
+                    let index = section.u32()?;
+                    if kind == KIND_FUNCTION {
+                        exports.push((name, index));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    exports
+        .into_iter()
+        .map(|(name, index)| {
+            let params = functions
+                .get(index as usize)
+                .and_then(|ty| types.get(*ty as usize))
+                .ok_or(Error::Malformed("export"))?;
+            Ok(ExportedFunction {
+                name,
+                params: *params,
+            })
+        })
+        .collect()
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(Error::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    /// An unsigned LEB128 encoded integer.
+    fn u32(&mut self) -> Result<u32, Error> {
+        let mut result = 0u32;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift > 28 {
+                return Err(Error::Malformed("integer"));
+            }
+            result |= u32::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    fn name(&mut self) -> Result<String, Error> {
+        let len = self.u32()? as usize;
+        let bytes = self.bytes(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error::Malformed("name"))
+    }
+
+    fn limits(&mut self) -> Result<(), Error> {
+        let max = self.byte()? & 1 == 1;
+        self.u32()?;
+        if max {
+            self.u32()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module importing one function and exporting a one- and a
+    /// two-parameter function plus its memory.
+    #[rustfmt::skip]
+    const MODULE: &[u8] = &[
+        0, b'a', b's', b'm', 1, 0, 0, 0,
+        // types: (i32) -> i32, (i32, i32) -> i32
+        SECTION_TYPE, 12, 2,
+        0x60, 1, 0x7f, 1, 0x7f,
+        0x60, 2, 0x7f, 0x7f, 1, 0x7f,
+        // import "watt-0.4" "drop" as type 0
+        SECTION_IMPORT, 17, 1,
+        8, b'w', b'a', b't', b't', b'-', b'0', b'.', b'4',
+        4, b'd', b'r', b'o', b'p', KIND_FUNCTION, 0,
+        // two functions of type 0 and 1
+        SECTION_FUNCTION, 3, 2, 0, 1,
+        SECTION_EXPORT, 23, 3,
+        6, b'm', b'e', b'm', b'o', b'r', b'y', KIND_MEMORY, 0,
+        3, b'd', b'e', b'r', KIND_FUNCTION, 1,
+        4, b'a', b't', b't', b'r', KIND_FUNCTION, 2,
+    ];
+
+    #[test]
+    fn exports() {
+        assert_eq!(
+            exported_functions(MODULE).unwrap(),
+            [
+                ExportedFunction { name: "der".to_owned(), params: 1 },
+                ExportedFunction { name: "attr".to_owned(), params: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            exported_functions(&MODULE[..MODULE.len() - 1]),
+            Err(Error::UnexpectedEnd)
+        );
+        assert_eq!(exported_functions(b"\x7fELF"), Err(Error::NotWasm));
+    }
+}
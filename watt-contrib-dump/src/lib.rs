@@ -0,0 +1,355 @@
+//! The format of the expansion dumps that `watt-contrib-runtime` writes for
+//! debugging generated code, kept free of dependencies so that tools like
+//! `watt-expand` can read dumps without depending on watt.
+//!
+//! With `WATT_CONTRIB_DUMP=<dir>` set in the environment of rustc, every
+//! invocation of a shim writes a file
+//!
+//! ```text
+//! <dir>/<crate>/<entry point>/<calling crate>-<file>_<line>_<column>.<n>.rs
+//! ```
+//!
+//! holding its arguments and input, followed by its output once the wasm
+//! module returns, all three exactly as the tokens print. When the output
+//! parses as a file, a copy formatted with rustfmt follows for reading, since
+//! rustfmt changes tokens. Source locations need Rust 1.88, older compilers
+//! write `unknown`.
+//!
+//! The file is plain Rust with `//` headers, and [`Dump::parse`] reads it
+//! back:
+//!
+//! ```text
+//! // watt-contrib expansion dump
+//! // crate: serde_derive 1.0.116
+//! // entry point: derive_serialize
+//! // kind: derive
+//! // caller: my_crate
+//! // location: src/main.rs:3:10
+//!
+//! // input
+//! struct S;
+//! // output
+//! ...
+//! // formatted output
+//! ...
+//! ```
+
+use std::env;
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Name of the environment variable holding the dump directory.
+pub const ENV: &str = "WATT_CONTRIB_DUMP";
+
+const MAGIC: &str = "// watt-contrib expansion dump";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Derive,
+    Attribute,
+    Function,
+}
+
+impl Kind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Kind::Derive => "derive",
+            Kind::Attribute => "attribute",
+            Kind::Function => "function",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "derive" => Some(Kind::Derive),
+            "attribute" => Some(Kind::Attribute),
+            "function" => Some(Kind::Function),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded invocation.
+#[derive(Debug, PartialEq)]
+pub struct Dump {
+    pub krate: String,
+    pub version: String,
+    pub entry_point: String,
+    pub kind: Kind,
+    /// `CARGO_CRATE_NAME` of the crate being compiled.
+    pub caller: Option<String>,
+    /// `file:line:column` of the invocation.
+    pub location: Option<String>,
+    /// Only present for attributes.
+    pub args: Option<String>,
+    pub input: String,
+    /// Missing if the expansion panicked.
+    pub output: Option<String>,
+    /// `output` formatted with rustfmt, if it parses as a file.
+    pub formatted_output: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ParseError(String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Dump {
+    pub fn parse(content: &str) -> Result<Self, ParseError> {
+        let mut lines = content.lines();
+        if lines.next() != Some(MAGIC) {
+            return Err(ParseError(format!("missing `{}` header", MAGIC)));
+        }
+
+        let mut krate = None;
+        let mut entry_point = None;
+        let mut kind = None;
+        let mut caller = None;
+        let mut location = None;
+        for line in &mut lines {
+            if line.is_empty() {
+                break;
+            }
+            let (key, value) = line
+                .strip_prefix("// ")
+                .and_then(|header| split_once(header, ": "))
+                .ok_or_else(|| ParseError(format!("malformed header `{}`", line)))?;
+            let value = value.to_owned();
+            match key {
+                "crate" => krate = Some(value),
+                "entry point" => entry_point = Some(value),
+                "kind" => {
+                    kind = Some(
+                        Kind::parse(&value)
+                            .ok_or_else(|| ParseError(format!("unknown kind `{}`", value)))?,
+                    )
+                }
+                "caller" => caller = Some(value),
+                "location" => location = Some(value),
+                _ => {}
+            }
+        }
+
+        let mut sections: Vec<(&str, String)> = Vec::new();
+        for line in lines {
+            match line {
+                "// args" | "// input" | "// output" | "// formatted output" => {
+                    sections.push((&line[3..], String::new()))
+                }
+                _ => match sections.last_mut() {
+                    Some((_, body)) => {
+                        body.push_str(line);
+                        body.push('\n');
+                    }
+                    None if line.is_empty() => {}
+                    None => return Err(ParseError(format!("unexpected `{}`", line))),
+                },
+            }
+        }
+        let mut take = |name: &str| {
+            sections
+                .iter()
+                .position(|(section, _)| *section == name)
+                .map(|i| sections.remove(i).1.trim_end().to_owned())
+        };
+
+        let missing = |what: &str| ParseError(format!("missing {}", what));
+        let krate = krate.ok_or_else(|| missing("crate"))?;
+        let (krate, version) = split_once(&krate, " ").ok_or_else(|| missing("crate version"))?;
+        Ok(Dump {
+            krate: krate.to_owned(),
+            version: version.to_owned(),
+            entry_point: entry_point.ok_or_else(|| missing("entry point"))?,
+            kind: kind.ok_or_else(|| missing("kind"))?,
+            caller,
+            location,
+            args: take("args"),
+            input: take("input").ok_or_else(|| missing("input"))?,
+            output: take("output"),
+            formatted_output: take("formatted output"),
+        })
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "{}\n// crate: {} {}\n// entry point: {}\n// kind: {}\n",
+            MAGIC,
+            self.krate,
+            self.version,
+            self.entry_point,
+            self.kind.as_str(),
+        );
+        if let Some(caller) = &self.caller {
+            out.push_str(&format!("// caller: {}\n", caller));
+        }
+        if let Some(location) = &self.location {
+            out.push_str(&format!("// location: {}\n", location));
+        }
+        out.push('\n');
+        if let Some(args) = &self.args {
+            out.push_str(&section("args", args));
+        }
+        out.push_str(&section("input", &self.input));
+        if let Some(output) = &self.output {
+            out.push_str(&section("output", output));
+        }
+        if let Some(formatted) = &self.formatted_output {
+            out.push_str(&section("formatted output", formatted));
+        }
+        out
+    }
+
+    /// File name of the dump, without the counter and extension.
+    pub fn key(&self) -> String {
+        let caller = self.caller.as_deref().unwrap_or("unknown");
+        let location = self.location.as_deref().unwrap_or("unknown");
+        format!("{}-{}", caller, location)
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' => c,
+                _ => '_',
+            })
+            .collect()
+    }
+}
+
+/// A `// <name>` section of a dump.
+pub fn section(name: &str, body: &str) -> String {
+    format!("// {}\n{}\n", name, body.trim_end())
+}
+
+// str::split_once is only stable since Rust 1.52.
+fn split_once<'a>(s: &'a str, delimiter: &str) -> Option<(&'a str, &'a str)> {
+    let i = s.find(delimiter)?;
+    Some((&s[..i], &s[i + delimiter.len()..]))
+}
+
+/// Formats `source` as a file, or returns `None` if it doesn't parse as one or
+/// rustfmt isn't available.
+///
+/// Tries the 2015 edition after 2018, since some macros, like serde_derive
+/// 1.0.116, define a `macro_rules! try` that doesn't parse in newer ones.
+pub fn rustfmt(source: &str) -> Option<String> {
+    ["2018", "2015"]
+        .iter()
+        .find_map(|edition| rustfmt_edition(source, edition))
+}
+
+fn rustfmt_edition(source: &str, edition: &str) -> Option<String> {
+    let rustfmt = env::var_os("RUSTFMT").unwrap_or_else(|| "rustfmt".into());
+    let mut child = Command::new(rustfmt)
+        .arg("--edition")
+        .arg(edition)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(source.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dump() -> Dump {
+        Dump {
+            krate: "tracing-attributes".to_owned(),
+            version: "0.1.11".to_owned(),
+            entry_point: "instrument".to_owned(),
+            kind: Kind::Attribute,
+            caller: Some("my_crate".to_owned()),
+            location: Some("src/main.rs:3:1".to_owned()),
+            args: Some("level = \"debug\"".to_owned()),
+            input: "fn f() {}".to_owned(),
+            output: Some("fn f () { span ! () ; }".to_owned()),
+            formatted_output: Some("fn f() {\n    span!();\n}".to_owned()),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let dump = dump();
+        let rendered = dump.render();
+        assert_eq!(
+            rendered,
+            "// watt-contrib expansion dump\n\
+             // crate: tracing-attributes 0.1.11\n\
+             // entry point: instrument\n\
+             // kind: attribute\n\
+             // caller: my_crate\n\
+             // location: src/main.rs:3:1\n\
+             \n\
+             // args\n\
+             level = \"debug\"\n\
+             // input\n\
+             fn f() {}\n\
+             // output\n\
+             fn f () { span ! () ; }\n\
+             // formatted output\n\
+             fn f() {\n    span!();\n}\n"
+        );
+        assert_eq!(Dump::parse(&rendered).unwrap(), dump);
+    }
+
+    #[test]
+    fn without_output() {
+        let dump = Dump {
+            kind: Kind::Derive,
+            caller: None,
+            location: None,
+            args: None,
+            output: None,
+            formatted_output: None,
+            ..dump()
+        };
+        assert_eq!(Dump::parse(&dump.render()).unwrap(), dump);
+        assert_eq!(dump.key(), "unknown-unknown");
+    }
+
+    #[test]
+    fn key() {
+        assert_eq!(dump().key(), "my_crate-src_main.rs_3_1");
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(Dump::parse("fn main() {}").is_err());
+        let missing_input = "// watt-contrib expansion dump\n\
+                             // crate: serde_derive 1.0.116\n\
+                             // entry point: derive_serialize\n\
+                             // kind: derive\n";
+        assert_eq!(
+            Dump::parse(missing_input).unwrap_err().to_string(),
+            "missing input"
+        );
+    }
+
+    #[test]
+    fn formats_older_editions() {
+        if rustfmt("").is_none() {
+            return;
+        }
+        assert_eq!(
+            rustfmt("macro_rules! try { ($e:expr) => { $e } }").as_deref(),
+            Some("macro_rules! try {\n    ($e:expr) => {\n        $e\n    };\n}\n"),
+        );
+        assert_eq!(
+            rustfmt("async fn f() { g().await }").as_deref(),
+            Some("async fn f() {\n    g().await\n}\n"),
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! The byte format in which watt 0.4 passes token streams, the mirror image
+//! of watt's `encode.rs` and `decode.rs`.
+//!
+//! A stream is its tokens followed by `0`. Each token starts with a tag:
+//!
+//! ```text
+//! 1 group:   delimiter: u8, span: u32, stream
+//! 2 ident:   span: u32, len: u32, utf-8 text (`r#` prefix for raw idents)
+//! 3 punct:   char: u32, spacing: u8, span: u32
+//! 4 literal: span: u32, then 0, len: u32, utf-8 text
+//!                       or   1, handle: u32 of a literal watt holds
+//! ```
+//!
+//! Spans are indices into watt's table of the spans it has handed out, or
+//! `u32::MAX` for the call site. Numbers are little-endian.
+
+use crate::ffi;
+use crate::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+pub fn decode(mut bytes: &[u8]) -> TokenStream {
+    let stream = stream(&mut bytes);
+    assert!(bytes.is_empty());
+    stream
+}
+
+fn stream(bytes: &mut &[u8]) -> TokenStream {
+    let mut tokens = Vec::new();
+    loop {
+        let token = match byte(bytes) {
+            0 => break,
+            1 => {
+                let delimiter = match byte(bytes) {
+                    0 => Delimiter::Parenthesis,
+                    1 => Delimiter::Brace,
+                    2 => Delimiter::Bracket,
+                    _ => Delimiter::None,
+                };
+                let span = span(bytes);
+                let mut group = Group::new(delimiter, stream(bytes));
+                group.set_span(span);
+                TokenTree::Group(group)
+            }
+            2 => {
+                let span = span(bytes);
+                let text = str(bytes);
+                TokenTree::Ident(match text.strip_prefix("r#") {
+                    Some(text) => Ident::new_raw(text, span),
+                    None => Ident::new(text, span),
+                })
+            }
+            3 => {
+                let ch = std::char::from_u32(u32(bytes)).unwrap();
+                let spacing = match byte(bytes) {
+                    0 => Spacing::Alone,
+                    _ => Spacing::Joint,
+                };
+                let mut punct = Punct::new(ch, spacing);
+                punct.set_span(span(bytes));
+                TokenTree::Punct(punct)
+            }
+            _ => {
+                let span = span(bytes);
+                let repr = match byte(bytes) {
+                    0 => str(bytes).to_owned(),
+                    _ => ffi::literal_to_string(u32(bytes)),
+                };
+                let mut literal = Literal::from_repr(repr);
+                literal.set_span(span);
+                TokenTree::Literal(literal)
+            }
+        };
+        tokens.push(token);
+    }
+    tokens.into_iter().collect()
+}
+
+fn byte(bytes: &mut &[u8]) -> u8 {
+    let byte = bytes[0];
+    *bytes = &bytes[1..];
+    byte
+}
+
+fn u32(bytes: &mut &[u8]) -> u32 {
+    let (head, rest) = bytes.split_at(4);
+    *bytes = rest;
+    u32::from_le_bytes([head[0], head[1], head[2], head[3]])
+}
+
+fn span(bytes: &mut &[u8]) -> Span {
+    Span::from_handle(u32(bytes))
+}
+
+fn str<'a>(bytes: &mut &'a [u8]) -> &'a str {
+    let len = u32(bytes) as usize;
+    let (text, rest) = bytes.split_at(len);
+    *bytes = rest;
+    std::str::from_utf8(text).unwrap()
+}
+
+pub fn encode(stream: &TokenStream) -> Vec<u8> {
+    let mut dst = Vec::new();
+    encode_stream(stream, &mut dst);
+    dst
+}
+
+fn encode_stream(stream: &TokenStream, dst: &mut Vec<u8>) {
+    for token in stream.inner.iter() {
+        match token {
+            TokenTree::Group(group) => {
+                dst.push(1);
+                dst.push(match group.delimiter() {
+                    Delimiter::Parenthesis => 0,
+                    Delimiter::Brace => 1,
+                    Delimiter::Bracket => 2,
+                    Delimiter::None => 3,
+                });
+                put_u32(group.span().handle(), dst);
+                encode_stream(&group.stream, dst);
+            }
+            TokenTree::Ident(ident) => {
+                dst.push(2);
+                put_u32(ident.span().handle(), dst);
+                put_str(&ident.to_string(), dst);
+            }
+            TokenTree::Punct(punct) => {
+                encode_punct(punct.as_char(), punct.spacing(), punct.span(), dst)
+            }
+            TokenTree::Literal(literal) => {
+                // rustc lexes a negative number as a `-` followed by the
+                // number, and watt expects a single literal token.
+                let repr = literal.to_string();
+                let repr = match repr.strip_prefix('-') {
+                    Some(rest) => {
+                        encode_punct('-', Spacing::Alone, literal.span(), dst);
+                        rest.to_owned()
+                    }
+                    None => repr,
+                };
+                dst.push(4);
+                put_u32(literal.span().handle(), dst);
+                dst.push(0);
+                put_str(&repr, dst);
+            }
+        }
+    }
+    dst.push(0);
+}
+
+fn encode_punct(ch: char, spacing: Spacing, span: Span, dst: &mut Vec<u8>) {
+    dst.push(3);
+    put_u32(ch as u32, dst);
+    dst.push(match spacing {
+        Spacing::Alone => 0,
+        Spacing::Joint => 1,
+    });
+    put_u32(span.handle(), dst);
+}
+
+fn put_u32(value: u32, dst: &mut Vec<u8>) {
+    dst.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_str(text: &str, dst: &mut Vec<u8>) {
+    put_u32(text.len() as u32, dst);
+    dst.extend_from_slice(text.as_bytes());
+}
@@ -0,0 +1,28 @@
+//! Items that proc-macro2 has but `proc_macro` doesn't.
+
+use crate::Span;
+
+/// The span of the delimiters of a [`Group`](crate::Group). Watt passes a
+/// single span for both.
+#[derive(Copy, Clone, Debug)]
+pub struct DelimSpan {
+    span: Span,
+}
+
+impl DelimSpan {
+    pub(crate) fn new(span: Span) -> Self {
+        DelimSpan { span }
+    }
+
+    pub fn join(&self) -> Span {
+        self.span
+    }
+
+    pub fn open(&self) -> Span {
+        self.span
+    }
+
+    pub fn close(&self) -> Span {
+        self.span
+    }
+}
@@ -0,0 +1,108 @@
+//! The host functions of watt 0.4 and the two functions watt calls around
+//! every entry point.
+//!
+//! Handles of host objects (token streams, strings, byte buffers and
+//! literals) are indices into tables that watt clears after each expansion,
+//! so none of them is kept past the call that received it.
+
+use crate::codec;
+use crate::TokenStream;
+use std::panic;
+use std::sync::Once;
+
+macro_rules! host_functions {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+        #[cfg(target_arch = "wasm32")]
+        mod host {
+            #[link(wasm_import_module = "watt-0.4")]
+            extern "C" {
+                $(pub fn $name($($arg: $ty),*) $(-> $ret)?;)*
+            }
+        }
+
+        /// Outside of wasm, so that the crate can be unit tested natively.
+        #[cfg(not(target_arch = "wasm32"))]
+        mod host {
+            $(
+                #[allow(unused_variables)]
+                pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                    unreachable!(concat!(stringify!($name), " only exists inside watt"))
+                }
+            )*
+        }
+    };
+}
+
+host_functions! {
+    fn token_stream_serialize(stream: u32) -> u32;
+    fn token_stream_deserialize(ptr: *const u8, len: usize) -> u32;
+    fn token_stream_parse(ptr: *const u8, len: usize) -> u32;
+    fn literal_to_string(literal: u32) -> u32;
+
+    fn string_new(ptr: *const u8, len: usize) -> u32;
+    fn string_len(string: u32) -> usize;
+    fn string_read(string: u32, ptr: *mut u8);
+    fn bytes_len(bytes: u32) -> usize;
+    fn bytes_read(bytes: u32, ptr: *mut u8);
+    fn print_panic(message: u32);
+}
+
+/// What `token_stream_parse` returns for input that doesn't lex.
+const PARSE_ERROR: u32 = u32::MAX;
+
+/// Called by watt with the handle of each input of an entry point.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn raw_to_token_stream(raw: u32) -> TokenStream {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| panic::set_hook(Box::new(|info| print_panic(&info.to_string()))));
+    decode(raw)
+}
+
+/// Called by watt with the output of an entry point.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn token_stream_into_raw(stream: TokenStream) -> u32 {
+    let bytes = codec::encode(&stream);
+    unsafe { host::token_stream_deserialize(bytes.as_ptr(), bytes.len()) }
+}
+
+/// Lexes `source` with rustc, or returns `None` if it isn't valid tokens.
+pub fn parse(source: &str) -> Option<TokenStream> {
+    match unsafe { host::token_stream_parse(source.as_ptr(), source.len()) } {
+        PARSE_ERROR => None,
+        raw => Some(decode(raw)),
+    }
+}
+
+/// The source text of the host literal `literal`.
+pub fn literal_to_string(literal: u32) -> String {
+    let string = unsafe { host::literal_to_string(literal) };
+    let len = unsafe { host::string_len(string) };
+    let mut buf = Vec::with_capacity(len);
+    unsafe {
+        host::string_read(string, buf.as_mut_ptr());
+        buf.set_len(len);
+    }
+    String::from_utf8(buf).expect("non-utf8 literal")
+}
+
+fn decode(raw: u32) -> TokenStream {
+    let bytes = unsafe { host::token_stream_serialize(raw) };
+    let len = unsafe { host::bytes_len(bytes) };
+    let mut buf = Vec::with_capacity(len);
+    unsafe {
+        host::bytes_read(bytes, buf.as_mut_ptr());
+        buf.set_len(len);
+    }
+    codec::decode(&buf)
+}
+
+/// Makes rustc panic with `message`, which a panic inside the module would
+/// otherwise only report as an `unreachable` trap.
+fn print_panic(message: &str) {
+    unsafe {
+        let string = host::string_new(message.as_ptr(), message.len());
+        host::print_panic(string);
+    }
+}
@@ -0,0 +1,878 @@
+//! The `proc-macro2` that `cargo xtask regenerate` builds the `.wasm`
+//! modules against, in place of the one on crates.io.
+//!
+//! Inside watt there is no `proc_macro` crate: token streams live in the
+//! module's own memory, and only lexing source text and handing the input
+//! and output of an entry point over go through watt 0.4's host functions
+//! (see [`ffi`]). The API is the subset of proc-macro2's that syn and quote
+//! use, and prints tokens the way proc-macro2's fallback implementation
+//! does.
+//!
+//! Spans can only be passed along: watt hands out opaque handles for the
+//! spans of the input, and everything created in the module is at the call
+//! site. [`Span::join`] and [`Literal::subspan`] return `None`, and
+//! [`Span::mixed_site`] is the call site.
+
+mod codec;
+mod ffi;
+
+pub mod extra;
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::RangeBounds;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// A sequence of token trees.
+///
+/// Passed to and returned from the entry points by value, so it has to stay
+/// a single pointer: watt calls them with one `i32` per token stream.
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct TokenStream {
+    inner: Rc<Vec<TokenTree>>,
+}
+
+impl TokenStream {
+    pub fn new() -> Self {
+        TokenStream {
+            inner: Rc::new(Vec::new()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    fn tokens_mut(&mut self) -> &mut Vec<TokenTree> {
+        Rc::make_mut(&mut self.inner)
+    }
+}
+
+impl Default for TokenStream {
+    fn default() -> Self {
+        TokenStream::new()
+    }
+}
+
+impl FromStr for TokenStream {
+    type Err = LexError;
+
+    fn from_str(src: &str) -> Result<TokenStream, LexError> {
+        ffi::parse(src).ok_or(LexError {
+            span: Span::call_site(),
+        })
+    }
+}
+
+impl From<TokenTree> for TokenStream {
+    fn from(token: TokenTree) -> Self {
+        TokenStream {
+            inner: Rc::new(vec![token]),
+        }
+    }
+}
+
+impl FromIterator<TokenTree> for TokenStream {
+    fn from_iter<I: IntoIterator<Item = TokenTree>>(tokens: I) -> Self {
+        TokenStream {
+            inner: Rc::new(tokens.into_iter().collect()),
+        }
+    }
+}
+
+impl FromIterator<TokenStream> for TokenStream {
+    fn from_iter<I: IntoIterator<Item = TokenStream>>(streams: I) -> Self {
+        let mut stream = TokenStream::new();
+        stream.extend(streams);
+        stream
+    }
+}
+
+impl Extend<TokenTree> for TokenStream {
+    fn extend<I: IntoIterator<Item = TokenTree>>(&mut self, tokens: I) {
+        self.tokens_mut().extend(tokens);
+    }
+}
+
+impl Extend<TokenStream> for TokenStream {
+    fn extend<I: IntoIterator<Item = TokenStream>>(&mut self, streams: I) {
+        let tokens = self.tokens_mut();
+        for stream in streams {
+            match Rc::try_unwrap(stream.inner) {
+                Ok(vec) => tokens.extend(vec),
+                Err(rc) => tokens.extend(rc.iter().cloned()),
+            }
+        }
+    }
+}
+
+impl IntoIterator for TokenStream {
+    type Item = TokenTree;
+    type IntoIter = token_stream::IntoIter;
+
+    fn into_iter(self) -> token_stream::IntoIter {
+        let tokens = match Rc::try_unwrap(self.inner) {
+            Ok(tokens) => tokens,
+            Err(rc) => (*rc).clone(),
+        };
+        token_stream::IntoIter {
+            inner: tokens.into_iter(),
+        }
+    }
+}
+
+impl Display for TokenStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut joint = false;
+        for (i, token) in self.inner.iter().enumerate() {
+            if i != 0 && !joint {
+                f.write_str(" ")?;
+            }
+            joint = false;
+            match token {
+                TokenTree::Group(group) => Display::fmt(group, f),
+                TokenTree::Ident(ident) => Display::fmt(ident, f),
+                TokenTree::Punct(punct) => {
+                    joint = punct.spacing() == Spacing::Joint;
+                    Display::fmt(punct, f)
+                }
+                TokenTree::Literal(literal) => Display::fmt(literal, f),
+            }?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for TokenStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("TokenStream ")?;
+        f.debug_list().entries(self.inner.iter()).finish()
+    }
+}
+
+pub mod token_stream {
+    //! Iteration over the tokens of a [`TokenStream`](crate::TokenStream).
+
+    use crate::TokenTree;
+    use std::fmt::{self, Debug};
+    use std::vec;
+
+    #[derive(Clone)]
+    pub struct IntoIter {
+        pub(crate) inner: vec::IntoIter<TokenTree>,
+    }
+
+    impl Iterator for IntoIter {
+        type Item = TokenTree;
+
+        fn next(&mut self) -> Option<TokenTree> {
+            self.inner.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.inner.size_hint()
+        }
+    }
+
+    impl Debug for IntoIter {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("TokenStream ")?;
+            f.debug_list().entries(self.inner.as_slice()).finish()
+        }
+    }
+}
+
+/// Returned by [`TokenStream::from_str`] and [`Literal::from_str`] for
+/// source text that rustc doesn't accept.
+pub struct LexError {
+    span: Span,
+}
+
+impl LexError {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("cannot parse string into token stream")
+    }
+}
+
+impl Debug for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LexError").finish()
+    }
+}
+
+impl Error for LexError {}
+
+/// A region of source code, as a handle into watt's table of the spans of
+/// the current expansion.
+#[derive(Copy, Clone)]
+pub struct Span {
+    handle: u32,
+}
+
+impl Span {
+    /// How watt encodes the call site.
+    const CALL_SITE: u32 = u32::MAX;
+
+    pub(crate) fn from_handle(handle: u32) -> Self {
+        Span { handle }
+    }
+
+    pub(crate) fn handle(self) -> u32 {
+        self.handle
+    }
+
+    pub fn call_site() -> Self {
+        Span::from_handle(Span::CALL_SITE)
+    }
+
+    /// Watt has no mixed-site spans, this is the call site.
+    pub fn mixed_site() -> Self {
+        Span::call_site()
+    }
+
+    /// Watt can't combine the location of one span with the hygiene of
+    /// another, so this keeps `self`.
+    pub fn resolved_at(&self, _other: Span) -> Span {
+        *self
+    }
+
+    /// Watt can't combine the location of one span with the hygiene of
+    /// another, so this is `other`.
+    pub fn located_at(&self, other: Span) -> Span {
+        other
+    }
+
+    pub fn join(&self, _other: Span) -> Option<Span> {
+        None
+    }
+
+    pub fn source_text(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.handle == Span::CALL_SITE {
+            f.write_str("Span")
+        } else {
+            write!(f, "Span({})", self.handle)
+        }
+    }
+}
+
+/// A single token or a delimited sequence of token trees.
+#[derive(Clone)]
+pub enum TokenTree {
+    Group(Group),
+    Ident(Ident),
+    Punct(Punct),
+    Literal(Literal),
+}
+
+impl TokenTree {
+    pub fn span(&self) -> Span {
+        match self {
+            TokenTree::Group(group) => group.span(),
+            TokenTree::Ident(ident) => ident.span(),
+            TokenTree::Punct(punct) => punct.span(),
+            TokenTree::Literal(literal) => literal.span(),
+        }
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        match self {
+            TokenTree::Group(group) => group.set_span(span),
+            TokenTree::Ident(ident) => ident.set_span(span),
+            TokenTree::Punct(punct) => punct.set_span(span),
+            TokenTree::Literal(literal) => literal.set_span(span),
+        }
+    }
+}
+
+impl From<Group> for TokenTree {
+    fn from(group: Group) -> Self {
+        TokenTree::Group(group)
+    }
+}
+
+impl From<Ident> for TokenTree {
+    fn from(ident: Ident) -> Self {
+        TokenTree::Ident(ident)
+    }
+}
+
+impl From<Punct> for TokenTree {
+    fn from(punct: Punct) -> Self {
+        TokenTree::Punct(punct)
+    }
+}
+
+impl From<Literal> for TokenTree {
+    fn from(literal: Literal) -> Self {
+        TokenTree::Literal(literal)
+    }
+}
+
+impl Display for TokenTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenTree::Group(group) => Display::fmt(group, f),
+            TokenTree::Ident(ident) => Display::fmt(ident, f),
+            TokenTree::Punct(punct) => Display::fmt(punct, f),
+            TokenTree::Literal(literal) => Display::fmt(literal, f),
+        }
+    }
+}
+
+impl Debug for TokenTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenTree::Group(group) => Debug::fmt(group, f),
+            TokenTree::Ident(ident) => Debug::fmt(ident, f),
+            TokenTree::Punct(punct) => Debug::fmt(punct, f),
+            TokenTree::Literal(literal) => Debug::fmt(literal, f),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Delimiter {
+    Parenthesis,
+    Brace,
+    Bracket,
+    None,
+}
+
+/// A delimited token stream. Watt passes one span per group, which is also
+/// the span of either delimiter.
+#[derive(Clone)]
+pub struct Group {
+    delimiter: Delimiter,
+    stream: TokenStream,
+    span: Span,
+}
+
+impl Group {
+    pub fn new(delimiter: Delimiter, stream: TokenStream) -> Self {
+        Group {
+            delimiter,
+            stream,
+            span: Span::call_site(),
+        }
+    }
+
+    pub fn delimiter(&self) -> Delimiter {
+        self.delimiter
+    }
+
+    pub fn stream(&self) -> TokenStream {
+        self.stream.clone()
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn span_open(&self) -> Span {
+        self.span
+    }
+
+    pub fn span_close(&self) -> Span {
+        self.span
+    }
+
+    pub fn delim_span(&self) -> extra::DelimSpan {
+        extra::DelimSpan::new(self.span)
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (open, close) = match self.delimiter {
+            Delimiter::Parenthesis => ("(", ")"),
+            Delimiter::Brace => ("{ ", "}"),
+            Delimiter::Bracket => ("[", "]"),
+            Delimiter::None => ("", ""),
+        };
+        f.write_str(open)?;
+        Display::fmt(&self.stream, f)?;
+        if self.delimiter == Delimiter::Brace && !self.stream.is_empty() {
+            f.write_str(" ")?;
+        }
+        f.write_str(close)
+    }
+}
+
+impl Debug for Group {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Group")
+            .field("delimiter", &self.delimiter)
+            .field("stream", &self.stream)
+            .finish()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Spacing {
+    Alone,
+    Joint,
+}
+
+#[derive(Clone)]
+pub struct Punct {
+    ch: char,
+    spacing: Spacing,
+    span: Span,
+}
+
+impl Punct {
+    pub fn new(ch: char, spacing: Spacing) -> Self {
+        const LEGAL: &str = "~!@#$%^&*-=+|;:,<.>/?'";
+        if !LEGAL.contains(ch) {
+            panic!("unsupported proc macro punctuation character {:?}", ch);
+        }
+        Punct {
+            ch,
+            spacing,
+            span: Span::call_site(),
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        self.ch
+    }
+
+    pub fn spacing(&self) -> Spacing {
+        self.spacing
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+}
+
+impl Display for Punct {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.ch, f)
+    }
+}
+
+impl Debug for Punct {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Punct")
+            .field("char", &self.ch)
+            .field("spacing", &self.spacing)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+pub struct Ident {
+    sym: Box<str>,
+    raw: bool,
+    span: Span,
+}
+
+impl Ident {
+    pub fn new(string: &str, span: Span) -> Self {
+        Ident::_new(string, false, span)
+    }
+
+    pub fn new_raw(string: &str, span: Span) -> Self {
+        Ident::_new(string, true, span)
+    }
+
+    fn _new(string: &str, raw: bool, span: Span) -> Self {
+        if string.is_empty() {
+            panic!("Ident is not allowed to be empty; use Option<Ident>");
+        }
+        Ident {
+            sym: Box::from(string),
+            raw,
+            span,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+}
+
+impl PartialEq for Ident {
+    fn eq(&self, other: &Ident) -> bool {
+        self.sym == other.sym && self.raw == other.raw
+    }
+}
+
+impl<T: ?Sized + AsRef<str>> PartialEq<T> for Ident {
+    fn eq(&self, other: &T) -> bool {
+        let other = other.as_ref();
+        if self.raw {
+            other.starts_with("r#") && *self.sym == other[2..]
+        } else {
+            *self.sym == *other
+        }
+    }
+}
+
+impl Eq for Ident {}
+
+impl PartialOrd for Ident {
+    fn partial_cmp(&self, other: &Ident) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ident {
+    fn cmp(&self, other: &Ident) -> Ordering {
+        self.to_string().cmp(&other.to_string())
+    }
+}
+
+impl Hash for Ident {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.to_string().hash(hasher);
+    }
+}
+
+impl Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.raw {
+            f.write_str("r#")?;
+        }
+        Display::fmt(&self.sym, f)
+    }
+}
+
+impl Debug for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Ident")
+            .field("sym", &format_args!("{}", self))
+            .finish()
+    }
+}
+
+/// A literal, kept as its source text.
+#[derive(Clone)]
+pub struct Literal {
+    repr: String,
+    span: Span,
+}
+
+macro_rules! suffixed_numbers {
+    ($($name:ident => $kind:ident,)*) => ($(
+        pub fn $name(n: $kind) -> Literal {
+            Literal::from_repr(format!(concat!("{}", stringify!($kind)), n))
+        }
+    )*)
+}
+
+macro_rules! unsuffixed_numbers {
+    ($($name:ident => $kind:ident,)*) => ($(
+        pub fn $name(n: $kind) -> Literal {
+            Literal::from_repr(n.to_string())
+        }
+    )*)
+}
+
+impl Literal {
+    pub(crate) fn from_repr(repr: String) -> Self {
+        Literal {
+            repr,
+            span: Span::call_site(),
+        }
+    }
+
+    suffixed_numbers! {
+        u8_suffixed => u8,
+        u16_suffixed => u16,
+        u32_suffixed => u32,
+        u64_suffixed => u64,
+        u128_suffixed => u128,
+        usize_suffixed => usize,
+        i8_suffixed => i8,
+        i16_suffixed => i16,
+        i32_suffixed => i32,
+        i64_suffixed => i64,
+        i128_suffixed => i128,
+        isize_suffixed => isize,
+        f32_suffixed => f32,
+        f64_suffixed => f64,
+    }
+
+    unsuffixed_numbers! {
+        u8_unsuffixed => u8,
+        u16_unsuffixed => u16,
+        u32_unsuffixed => u32,
+        u64_unsuffixed => u64,
+        u128_unsuffixed => u128,
+        usize_unsuffixed => usize,
+        i8_unsuffixed => i8,
+        i16_unsuffixed => i16,
+        i32_unsuffixed => i32,
+        i64_unsuffixed => i64,
+        i128_unsuffixed => i128,
+        isize_unsuffixed => isize,
+    }
+
+    pub fn f32_unsuffixed(f: f32) -> Literal {
+        assert!(f.is_finite(), "invalid float literal {}", f);
+        Literal::from_repr(float_repr(f.to_string()))
+    }
+
+    pub fn f64_unsuffixed(f: f64) -> Literal {
+        assert!(f.is_finite(), "invalid float literal {}", f);
+        Literal::from_repr(float_repr(f.to_string()))
+    }
+
+    pub fn string(string: &str) -> Literal {
+        let mut repr = String::with_capacity(string.len() + 2);
+        repr.push('"');
+        escape_utf8(string, &mut repr);
+        repr.push('"');
+        Literal::from_repr(repr)
+    }
+
+    pub fn character(ch: char) -> Literal {
+        let mut repr = String::from("'");
+        if ch == '"' {
+            // escape_debug turns this into '\"' which is unnecessary.
+            repr.push(ch);
+        } else {
+            repr.extend(ch.escape_debug());
+        }
+        repr.push('\'');
+        Literal::from_repr(repr)
+    }
+
+    pub fn byte_character(byte: u8) -> Literal {
+        let mut repr = String::from("b'");
+        if byte == b'"' {
+            repr.push('"');
+        } else {
+            escape_byte(byte, &mut repr);
+        }
+        repr.push('\'');
+        Literal::from_repr(repr)
+    }
+
+    pub fn byte_string(bytes: &[u8]) -> Literal {
+        let mut repr = String::from("b\"");
+        escape_bytes(bytes, &mut repr);
+        repr.push('"');
+        Literal::from_repr(repr)
+    }
+
+    pub fn c_string(string: &CStr) -> Literal {
+        let mut repr = String::from("c\"");
+        escape_bytes(string.to_bytes(), &mut repr);
+        repr.push('"');
+        Literal::from_repr(repr)
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    pub fn subspan<R: RangeBounds<usize>>(&self, _range: R) -> Option<Span> {
+        None
+    }
+}
+
+impl FromStr for Literal {
+    type Err = LexError;
+
+    fn from_str(repr: &str) -> Result<Self, LexError> {
+        let error = || LexError {
+            span: Span::call_site(),
+        };
+        let mut tokens = ffi::parse(repr).ok_or_else(error)?.into_iter();
+        match (tokens.next(), tokens.next(), tokens.next()) {
+            (Some(TokenTree::Punct(minus)), Some(TokenTree::Literal(literal)), None)
+                if minus.as_char() == '-' =>
+            {
+                Ok(Literal::from_repr(format!("-{}", literal.repr)))
+            }
+            (Some(TokenTree::Literal(literal)), None, None) => Ok(literal),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.repr, f)
+    }
+}
+
+impl Debug for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Literal")
+            .field("lit", &format_args!("{}", self.repr))
+            .finish()
+    }
+}
+
+/// Makes `1` a float literal, `1.0`, unless it already is one like `1e10`.
+fn float_repr(mut repr: String) -> String {
+    if !repr.contains(['.', 'e', 'E']) {
+        repr.push_str(".0");
+    }
+    repr
+}
+
+fn escape_utf8(string: &str, repr: &mut String) {
+    let mut chars = string.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\0' {
+            // `\0` followed by a digit would read as an octal escape.
+            repr.push_str(
+                if chars
+                    .as_str()
+                    .starts_with(|next| ('0'..='7').contains(&next))
+                {
+                    "\\x00"
+                } else {
+                    "\\0"
+                },
+            );
+        } else if ch == '\'' {
+            // escape_debug turns this into "\'" which is unnecessary.
+            repr.push(ch);
+        } else {
+            repr.extend(ch.escape_debug());
+        }
+    }
+}
+
+fn escape_bytes(bytes: &[u8], repr: &mut String) {
+    let mut bytes = bytes.iter();
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            b'\0' => repr.push_str(match bytes.as_slice().first() {
+                Some(b'0'..=b'7') => "\\x00",
+                _ => "\\0",
+            }),
+            b'\'' => repr.push('\''),
+            b'"' => repr.push_str("\\\""),
+            _ => escape_byte(byte, repr),
+        }
+    }
+}
+
+fn escape_byte(byte: u8, repr: &mut String) {
+    match byte {
+        b'\0' => repr.push_str("\\0"),
+        b'\t' => repr.push_str("\\t"),
+        b'\n' => repr.push_str("\\n"),
+        b'\r' => repr.push_str("\\r"),
+        b'\'' => repr.push_str("\\'"),
+        b'\\' => repr.push_str("\\\\"),
+        b'\x20'..=b'\x7E' => repr.push(byte as char),
+        _ => repr.push_str(&format!("\\x{:02X}", byte)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals() {
+        assert_eq!(Literal::u8_suffixed(1).to_string(), "1u8");
+        assert_eq!(Literal::i32_unsuffixed(-1).to_string(), "-1");
+        assert_eq!(Literal::f64_unsuffixed(1.0).to_string(), "1.0");
+        assert_eq!(
+            Literal::f32_unsuffixed(1e20).to_string(),
+            "100000000000000000000.0"
+        );
+        assert_eq!(Literal::f64_suffixed(0.5).to_string(), "0.5f64");
+        assert_eq!(
+            Literal::string("a\"'\0\n\u{1F600}").to_string(),
+            r#""a\"'\0\n😀""#
+        );
+        assert_eq!(Literal::string("\x001").to_string(), r#""\x001""#);
+        assert_eq!(Literal::character('"').to_string(), "'\"'");
+        assert_eq!(Literal::character('\'').to_string(), r"'\''");
+        assert_eq!(Literal::byte_character(b'"').to_string(), "b'\"'");
+        assert_eq!(Literal::byte_character(0xff).to_string(), r"b'\xFF'");
+        assert_eq!(
+            Literal::byte_string(b"\"'\t\x7f").to_string(),
+            r#"b"\"'\t\x7F""#
+        );
+        let c = CStr::from_bytes_with_nul(b"a\n\0").unwrap();
+        assert_eq!(Literal::c_string(c).to_string(), r#"c"a\n""#);
+    }
+
+    #[test]
+    fn printing() {
+        let path: TokenStream = vec![
+            TokenTree::from(Ident::new_raw("type", Span::call_site())),
+            TokenTree::from(Punct::new(':', Spacing::Joint)),
+            TokenTree::from(Punct::new(':', Spacing::Alone)),
+            TokenTree::from(Ident::new("x", Span::call_site())),
+        ]
+        .into_iter()
+        .collect();
+        let tokens: TokenStream = vec![
+            TokenTree::from(Group::new(Delimiter::Brace, path)),
+            TokenTree::from(Group::new(Delimiter::Parenthesis, TokenStream::new())),
+            TokenTree::from(Group::new(Delimiter::Brace, TokenStream::new())),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(tokens.to_string(), "{ r#type :: x } () { }");
+        assert_eq!(Ident::new_raw("type", Span::call_site()), "r#type");
+        assert_ne!(Ident::new("type", Span::call_site()), "r#type");
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut literal = Literal::i8_suffixed(-5);
+        literal.set_span(Span::from_handle(7));
+        let stream: TokenStream = vec![
+            TokenTree::from(Ident::new_raw("fn", Span::from_handle(3))),
+            TokenTree::from(literal),
+        ]
+        .into_iter()
+        .collect();
+        let encoded = codec::encode(&stream);
+        // The negative literal comes out as a `-` and the number.
+        let decoded = codec::decode(&encoded);
+        assert_eq!(decoded.to_string(), "r#fn - 5i8");
+        let spans: Vec<u32> = decoded
+            .into_iter()
+            .map(|token| token.span().handle())
+            .collect();
+        assert_eq!(spans, [3, 7, 7]);
+    }
+}
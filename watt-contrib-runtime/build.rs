@@ -0,0 +1,30 @@
+use std::env;
+use std::process::Command;
+use std::str;
+
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(span_locations)");
+    // Set by hand to print the imports and exports of every module watt loads.
+    println!("cargo:rustc-check-cfg=cfg(watt_debug)");
+
+    let minor = match rustc_minor_version() {
+        Some(minor) => minor,
+        None => return,
+    };
+
+    // Span::file, Span::line and Span::column stabilized in Rust 1.88.
+    if minor >= 88 {
+        println!("cargo:rustc-cfg=span_locations");
+    }
+}
+
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = str::from_utf8(&output.stdout).ok()?;
+    let mut pieces = version.split('.');
+    if pieces.next() != Some("rustc 1") {
+        return None;
+    }
+    pieces.next()?.parse().ok()
+}
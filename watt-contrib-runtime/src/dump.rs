@@ -0,0 +1,130 @@
+//! Expansion dumps for debugging generated code, in the format of
+//! [`watt_contrib_dump`].
+//!
+//! With `WATT_CONTRIB_DUMP=<dir>` set in the environment of rustc, every
+//! invocation of a shim writes a file
+//!
+//! ```text
+//! <dir>/<crate>/<entry point>/<calling crate>-<file>_<line>_<column>.<n>.rs
+//! ```
+//!
+//! holding its arguments and input, followed by its output once the wasm
+//! module returns, and a copy of the output formatted with rustfmt.
+
+use proc_macro::TokenStream;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use watt_contrib_dump::{rustfmt, section};
+
+pub use watt_contrib_dump::{Dump, Kind, ParseError, ENV};
+
+/// An invocation whose arguments and input have been written, waiting for its
+/// output.
+pub(crate) struct Recorder {
+    path: PathBuf,
+    file: File,
+}
+
+impl Recorder {
+    /// Writes the arguments and input if dumps are enabled. Failing to write
+    /// the dump is reported but doesn't fail the expansion.
+    pub(crate) fn start(
+        krate: &str,
+        version: &str,
+        entry_point: &str,
+        kind: Kind,
+        args: Option<&TokenStream>,
+        input: &TokenStream,
+    ) -> Option<Self> {
+        let dir = env::var_os(ENV).filter(|dir| !dir.is_empty())?;
+        let dump = Dump {
+            krate: krate.to_owned(),
+            version: version.to_owned(),
+            entry_point: entry_point.to_owned(),
+            kind,
+            caller: env::var("CARGO_CRATE_NAME").ok(),
+            location: location(),
+            args: args.map(ToString::to_string),
+            input: input.to_string(),
+            output: None,
+            formatted_output: None,
+        };
+        let dir = Path::new(&dir).join(krate).join(entry_point);
+        match create(&dir, &dump.key()) {
+            Ok((path, mut file)) => match file.write_all(dump.render().as_bytes()) {
+                Ok(()) => Some(Recorder { path, file }),
+                Err(err) => {
+                    warn(&path, &err);
+                    None
+                }
+            },
+            Err(err) => {
+                warn(&dir, &err);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn finish(mut self, output: &TokenStream) {
+        let output = output.to_string();
+        let mut sections = section("output", &output);
+        if let Some(formatted) = rustfmt(&output) {
+            sections.push_str(&section("formatted output", &formatted));
+        }
+        if let Err(err) = self.file.write_all(sections.as_bytes()) {
+            warn(&self.path, &err);
+        }
+    }
+}
+
+/// Creates `<key>.<n>.rs` with the first `n` that isn't taken, also by other
+/// rustc processes writing to the same directory.
+fn create(dir: &Path, key: &str) -> io::Result<(PathBuf, File)> {
+    fs::create_dir_all(dir)?;
+    let mut n = 0;
+    loop {
+        let path = dir.join(format!("{}.{}.rs", key, n));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn warn(path: &Path, err: &io::Error) {
+    eprintln!(
+        "warning: {}: failed to write {}: {}",
+        ENV,
+        path.display(),
+        err
+    );
+}
+
+#[cfg(span_locations)]
+fn location() -> Option<String> {
+    let span = proc_macro::Span::call_site();
+    Some(format!("{}:{}:{}", span.file(), span.line(), span.column()))
+}
+
+#[cfg(not(span_locations))]
+fn location() -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unique_files() {
+        let dir = env::temp_dir().join(format!("watt-contrib-dump-{}", std::process::id()));
+        let (first, _) = create(&dir, "key").unwrap();
+        let (second, _) = create(&dir, "key").unwrap();
+        assert_eq!(first.file_name().unwrap(), "key.0.rs");
+        assert_eq!(second.file_name().unwrap(), "key.1.rs");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+//! Runtime shared by the crates in this repository: the `WasmMacro` that
+//! every shim's `MACRO` static goes through.
+//!
+//! It runs the modules the way watt 0.4.6 does, on watt's own code, vendored
+//! in `watt/` of this crate: `watt/src` holds watt's host functions and
+//! `watt/runtime` its interpreter, under the ISC license in
+//! `watt/runtime/LICENSE_ISC`, which watt 0.4.6 leaves out of its package and
+//! is taken from watt 0.4.2. The interpreter can instantiate a module without
+//! validating it again, which lets shims load a [`snapshot`] of their module
+//! that their build script decoded and validated.
+//!
+//! Besides that it implements the debugging aids described in the README,
+//! like [dumping every expansion](dump).
+
+extern crate proc_macro;
+
+pub mod dump;
+pub mod snapshot;
+
+#[allow(warnings, clippy::all)]
+#[path = "../watt/runtime/src/lib.rs"]
+mod runtime;
+
+#[path = "../watt/src/interpret.rs"]
+mod interpret;
+
+#[path = "../watt/src/data.rs"]
+mod data;
+#[allow(clippy::legacy_numeric_constants)]
+#[path = "../watt/src/decode.rs"]
+mod decode;
+#[path = "../watt/src/encode.rs"]
+mod encode;
+#[path = "../watt/src/import.rs"]
+mod import;
+#[allow(clippy::legacy_numeric_constants)]
+#[path = "../watt/src/sym.rs"]
+mod sym;
+
+use dump::Kind;
+use proc_macro::TokenStream;
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+/// Includes the [`snapshot`] of the module `src/<file>` that
+/// `watt_contrib_build::write_snapshot` wrote in `OUT_DIR`.
+///
+/// ```
+/// # const IGNORE: &str = stringify! {
+/// static WASM: &[u8] = watt_contrib_runtime::include_snapshot!("tokio-macros.wasm");
+/// # };
+/// ```
+#[macro_export]
+macro_rules! include_snapshot {
+    ($file:literal) => {
+        ::core::include_bytes!(::core::concat!(
+            ::core::env!("OUT_DIR"),
+            "/",
+            $file,
+            ".snapshot",
+        ))
+    };
+}
+
+pub struct WasmMacro {
+    krate: &'static str,
+    version: &'static str,
+    wasm: &'static [u8],
+    id: AtomicUsize,
+}
+
+impl WasmMacro {
+    /// `krate` and `version` name the shim in dumps, pass
+    /// `env!("CARGO_PKG_NAME")` and `env!("CARGO_PKG_VERSION")`. `wasm` is
+    /// either the module or a [`snapshot`] of it.
+    pub const fn new(krate: &'static str, version: &'static str, wasm: &'static [u8]) -> Self {
+        WasmMacro {
+            krate,
+            version,
+            wasm,
+            id: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn proc_macro(&self, fun: &str, input: TokenStream) -> TokenStream {
+        let recorder = self.record(fun, Kind::Function, None, &input);
+        let output = interpret::proc_macro(fun, vec![input], self);
+        finish(recorder, &output);
+        output
+    }
+
+    pub fn proc_macro_derive(&self, fun: &str, input: TokenStream) -> TokenStream {
+        let recorder = self.record(fun, Kind::Derive, None, &input);
+        let output = interpret::proc_macro(fun, vec![input], self);
+        finish(recorder, &output);
+        output
+    }
+
+    pub fn proc_macro_attribute(
+        &self,
+        fun: &str,
+        args: TokenStream,
+        input: TokenStream,
+    ) -> TokenStream {
+        let recorder = self.record(fun, Kind::Attribute, Some(&args), &input);
+        let output = interpret::proc_macro(fun, vec![args, input], self);
+        finish(recorder, &output);
+        output
+    }
+
+    fn record(
+        &self,
+        fun: &str,
+        kind: Kind,
+        args: Option<&TokenStream>,
+        input: &TokenStream,
+    ) -> Option<dump::Recorder> {
+        dump::Recorder::start(self.krate, self.version, fun, kind, args, input)
+    }
+
+    fn id(&self) -> usize {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+        match self.id.load(SeqCst) {
+            0 => {}
+            n => return n,
+        }
+        let id = NEXT_ID.fetch_add(1, SeqCst);
+        self.id
+            .compare_exchange(0, id, SeqCst, SeqCst)
+            .unwrap_or_else(|id| id)
+    }
+}
+
+fn finish(recorder: Option<dump::Recorder>, output: &TokenStream) {
+    if let Some(recorder) = recorder {
+        recorder.finish(output);
+    }
+}
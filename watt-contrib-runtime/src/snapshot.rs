@@ -0,0 +1,902 @@
+//! Snapshots of wasm modules, decoded and validated at build time.
+//!
+//! Decoding and validating a module takes a rustc process most of the time
+//! it spends loading it, which it does for every crate that uses one of its
+//! macros. The build script of every crate hence writes the module to
+//! `OUT_DIR` as a snapshot with `watt_contrib_build::write_snapshot`, which
+//! encodes the interpreter's syntax tree of the module in a form that is quick
+//! to read back, and [`include_snapshot!`] includes that in place of the raw
+//! module.
+//!
+//! A snapshot starts with a header that keeps its layout across format
+//! versions:
+//!
+//! - [`MAGIC`], which a wasm module never starts with;
+//! - the format version, a little-endian `u32`;
+//! - the raw module, preceded by its length as a little-endian `u32`.
+//!
+//! The encoded module follows. When the version is not [`VERSION`] the raw
+//! module is loaded instead, the same as when the bytes are a module rather
+//! than a snapshot.
+//!
+//! [`include_snapshot!`]: crate::include_snapshot
+
+use crate::runtime::ast::*;
+use crate::runtime::types;
+use crate::runtime::values::Value;
+use crate::runtime::{decode_module, validate_module};
+use std::io::Cursor;
+
+/// The first bytes of a snapshot.
+pub const MAGIC: [u8; 4] = *b"\0snp";
+
+/// The format version written by [`write`] and read by [`read`]. It changes
+/// whenever the encoding, or the interpreter's syntax tree, does.
+pub const VERSION: u32 = 1;
+
+/// Decodes and validates `wasm` and returns its snapshot.
+pub fn write(wasm: &[u8]) -> Result<Vec<u8>, String> {
+    let module = decode_module(Cursor::new(wasm))
+        .map_err(|err| format!("failed to decode the module: {:?}", err))?;
+    if let Some(err) = validate_module(&module) {
+        return Err(format!("the module is invalid: {:?}", err));
+    }
+
+    let mut out = Encoder(Vec::with_capacity(2 * wasm.len()));
+    out.0.extend_from_slice(&MAGIC);
+    out.0.extend_from_slice(&VERSION.to_le_bytes());
+    out.0.extend_from_slice(&(wasm.len() as u32).to_le_bytes());
+    out.0.extend_from_slice(wasm);
+    out.module(&module);
+    Ok(out.0)
+}
+
+/// Reads the module from `bytes`. Returns the raw module instead if `bytes`
+/// are one, or a snapshot of a different format version.
+pub(crate) fn read(bytes: &[u8]) -> Result<Module, &[u8]> {
+    let mut header = Decoder { bytes, pos: 0 };
+    let raw = (|| {
+        if header.take(MAGIC.len())? != MAGIC {
+            return None;
+        }
+        let version = header.fixed_u32()?;
+        let len = header.fixed_u32()? as usize;
+        Some((version, header.take(len)?))
+    })();
+    match raw {
+        Some((VERSION, raw)) => {
+            let mut decoder = Decoder {
+                bytes,
+                pos: header.pos,
+            };
+            match decoder.module() {
+                Some(module) if decoder.pos == bytes.len() => Ok(module),
+                _ => Err(raw),
+            }
+        }
+        Some((_, raw)) => Err(raw),
+        None => Err(bytes),
+    }
+}
+
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn byte(&mut self, byte: u8) {
+        self.0.push(byte);
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.u64(n.into());
+    }
+
+    fn u64(&mut self, mut n: u64) {
+        while n >= 0x80 {
+            self.byte(n as u8 | 0x80);
+            n >>= 7;
+        }
+        self.byte(n as u8);
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(len as u32);
+    }
+
+    fn bool(&mut self, b: bool) {
+        self.byte(b as u8);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn string(&mut self, string: &str) {
+        self.bytes(string.as_bytes());
+    }
+
+    fn option_u32(&mut self, n: Option<u32>) {
+        match n {
+            None => self.byte(0),
+            Some(n) => {
+                self.byte(1);
+                self.u32(n);
+            }
+        }
+    }
+
+    fn indices(&mut self, indices: &[Index]) {
+        self.len(indices.len());
+        for &index in indices {
+            self.u32(index);
+        }
+    }
+
+    fn value_type(&mut self, type_: types::Value) {
+        self.byte(match type_ {
+            types::Value::Int(types::Int::I32) => 0,
+            types::Value::Int(types::Int::I64) => 1,
+            types::Value::Float(types::Float::F32) => 2,
+            types::Value::Float(types::Float::F64) => 3,
+        });
+    }
+
+    fn value_types(&mut self, types: &[types::Value]) {
+        self.len(types.len());
+        for &type_ in types {
+            self.value_type(type_);
+        }
+    }
+
+    fn limits(&mut self, limits: &types::Limits) {
+        self.u32(limits.min);
+        self.option_u32(limits.max);
+    }
+
+    fn table(&mut self, table: &types::Table) {
+        let types::Elem::AnyFunc = table.elem;
+        self.limits(&table.limits);
+    }
+
+    fn global(&mut self, global: &types::Global) {
+        self.value_type(global.value);
+        self.bool(global.mutable);
+    }
+
+    fn module(&mut self, module: &Module) {
+        self.len(module.types.len());
+        for func in &module.types {
+            self.value_types(&func.args);
+            self.value_types(&func.result);
+        }
+
+        self.len(module.funcs.len());
+        for func in &module.funcs {
+            self.u32(func.type_index);
+            self.value_types(&func.locals);
+            self.expr(&func.body);
+        }
+
+        self.len(module.tables.len());
+        for table in &module.tables {
+            self.table(&table.type_);
+        }
+
+        self.len(module.memories.len());
+        for memory in &module.memories {
+            self.limits(&memory.type_.limits);
+        }
+
+        self.len(module.globals.len());
+        for global in &module.globals {
+            self.global(&global.type_);
+            self.expr(&global.value);
+        }
+
+        self.len(module.elems.len());
+        for elem in &module.elems {
+            self.u32(elem.index);
+            self.expr(&elem.offset);
+            self.indices(&elem.init);
+        }
+
+        self.len(module.data.len());
+        for data in &module.data {
+            self.u32(data.index);
+            self.expr(&data.offset);
+            self.bytes(&data.init);
+        }
+
+        self.option_u32(module.start);
+
+        self.len(module.imports.len());
+        for import in &module.imports {
+            self.string(&import.module);
+            self.string(&import.name);
+            match &import.desc {
+                ImportDesc::Func(index) => {
+                    self.byte(0);
+                    self.u32(*index);
+                }
+                ImportDesc::Table(table) => {
+                    self.byte(1);
+                    self.table(table);
+                }
+                ImportDesc::Memory(memory) => {
+                    self.byte(2);
+                    self.limits(&memory.limits);
+                }
+                ImportDesc::Global(global) => {
+                    self.byte(3);
+                    self.global(global);
+                }
+            }
+        }
+
+        self.len(module.exports.len());
+        for export in &module.exports {
+            self.string(&export.name);
+            let (kind, index) = match export.desc {
+                ExportDesc::Func(index) => (0, index),
+                ExportDesc::Table(index) => (1, index),
+                ExportDesc::Memory(index) => (2, index),
+                ExportDesc::Global(index) => (3, index),
+            };
+            self.byte(kind);
+            self.u32(index);
+        }
+    }
+
+    fn expr(&mut self, expr: &[Instr]) {
+        self.len(expr.len());
+        for instr in expr {
+            self.instr(instr);
+        }
+    }
+
+    fn mem_op<T>(&mut self, op: &MemOp<T>) {
+        self.u32(op.align);
+        self.u32(op.offset);
+        self.value_type(op.type_);
+    }
+
+    fn instr(&mut self, instr: &Instr) {
+        use self::Instr::*;
+
+        match instr {
+            Unreachable => self.byte(0),
+            Nop => self.byte(1),
+            Block(result, body) => {
+                self.byte(2);
+                self.value_types(result);
+                self.expr(body);
+            }
+            Loop(result, body) => {
+                self.byte(3);
+                self.value_types(result);
+                self.expr(body);
+            }
+            If(result, then, else_) => {
+                self.byte(4);
+                self.value_types(result);
+                self.expr(then);
+                self.expr(else_);
+            }
+            Br(index) => {
+                self.byte(5);
+                self.u32(*index);
+            }
+            BrIf(index) => {
+                self.byte(6);
+                self.u32(*index);
+            }
+            BrTable(indices, default) => {
+                self.byte(7);
+                self.indices(indices);
+                self.u32(*default);
+            }
+            Return => self.byte(8),
+            Call(index) => {
+                self.byte(9);
+                self.u32(*index);
+            }
+            CallIndirect(index) => {
+                self.byte(10);
+                self.u32(*index);
+            }
+            Drop_ => self.byte(11),
+            Select => self.byte(12),
+            GetLocal(index) => {
+                self.byte(13);
+                self.u32(*index);
+            }
+            SetLocal(index) => {
+                self.byte(14);
+                self.u32(*index);
+            }
+            TeeLocal(index) => {
+                self.byte(15);
+                self.u32(*index);
+            }
+            GetGlobal(index) => {
+                self.byte(16);
+                self.u32(*index);
+            }
+            SetGlobal(index) => {
+                self.byte(17);
+                self.u32(*index);
+            }
+            Load(op) => {
+                self.byte(18);
+                self.mem_op(op);
+                match op.opt {
+                    None => self.byte(0),
+                    Some((size, signed)) => {
+                        self.byte(1);
+                        self.u32(size);
+                        self.bool(signed);
+                    }
+                }
+            }
+            Store(op) => {
+                self.byte(19);
+                self.mem_op(op);
+                self.option_u32(op.opt);
+            }
+            CurrentMemory => self.byte(20),
+            GrowMemory => self.byte(21),
+            Const(value) => match *value {
+                Value::I32(n) => {
+                    self.byte(22);
+                    self.u32(n);
+                }
+                Value::I64(n) => {
+                    self.byte(23);
+                    self.u64(n);
+                }
+                Value::F32(n) => {
+                    self.byte(24);
+                    self.0.extend_from_slice(&n.to_bits().to_le_bytes());
+                }
+                Value::F64(n) => {
+                    self.byte(25);
+                    self.0.extend_from_slice(&n.to_bits().to_le_bytes());
+                }
+            },
+            IUnary(type_, op) => {
+                self.byte(26);
+                self.byte(int(*type_) | iunop(op) << 1);
+            }
+            FUnary(type_, op) => {
+                self.byte(27);
+                self.byte(float(*type_) | funop(op) << 1);
+            }
+            IBin(type_, op) => {
+                self.byte(28);
+                self.byte(int(*type_) | ibinop(op) << 1);
+            }
+            FBin(type_, op) => {
+                self.byte(29);
+                self.byte(float(*type_) | fbinop(op) << 1);
+            }
+            ITest(type_, ITestOp::Eqz) => {
+                self.byte(30);
+                self.byte(int(*type_));
+            }
+            IRel(type_, op) => {
+                self.byte(31);
+                self.byte(int(*type_) | irelop(op) << 1);
+            }
+            FRel(type_, op) => {
+                self.byte(32);
+                self.byte(float(*type_) | frelop(op) << 1);
+            }
+            Convert(op) => {
+                self.byte(33);
+                self.convert_op(op);
+            }
+        }
+    }
+
+    fn convert_op(&mut self, op: &ConvertOp) {
+        match *op {
+            ConvertOp::I32WrapI64 => self.byte(0),
+            ConvertOp::I64ExtendUI32 => self.byte(1),
+            ConvertOp::I64ExtendSI32 => self.byte(2),
+            ConvertOp::Trunc { from, to, signed } => {
+                self.byte(3);
+                self.byte(float(from) | int(to) << 1 | (signed as u8) << 2);
+            }
+            ConvertOp::Convert { from, to, signed } => {
+                self.byte(4);
+                self.byte(int(from) | float(to) << 1 | (signed as u8) << 2);
+            }
+            ConvertOp::Reinterpret { from, to } => {
+                self.byte(5);
+                self.value_type(from);
+                self.value_type(to);
+            }
+            ConvertOp::F32DemoteF64 => self.byte(6),
+            ConvertOp::F64PromoteF32 => self.byte(7),
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        let byte = *self.bytes.get(self.pos)?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn fixed_u32(&mut self) -> Option<u32> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Some(u32::from_le_bytes(bytes))
+    }
+
+    fn fixed_u64(&mut self) -> Option<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let n = self.u64()?;
+        if n > u32::MAX.into() {
+            return None;
+        }
+        Some(n as u32)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let mut n = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift > 63 {
+                return None;
+            }
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(n);
+            }
+            shift += 7;
+        }
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        let len = self.u32()? as usize;
+        // Every element takes at least one byte, so this bounds allocations.
+        if len > self.bytes.len() - self.pos {
+            return None;
+        }
+        Some(len)
+    }
+
+    fn vec<T>(&mut self, mut element: impl FnMut(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        let len = self.len()?;
+        let mut vec = Vec::with_capacity(len);
+        for _ in 0..len {
+            vec.push(element(self)?);
+        }
+        Some(vec)
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.byte()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn bytes(&mut self) -> Option<Vec<u8>> {
+        let len = self.len()?;
+        Some(self.take(len)?.to_vec())
+    }
+
+    fn string(&mut self) -> Option<String> {
+        String::from_utf8(self.bytes()?).ok()
+    }
+
+    fn option_u32(&mut self) -> Option<Option<u32>> {
+        match self.byte()? {
+            0 => Some(None),
+            1 => Some(Some(self.u32()?)),
+            _ => None,
+        }
+    }
+
+    fn value_type(&mut self) -> Option<types::Value> {
+        Some(match self.byte()? {
+            0 => types::I32,
+            1 => types::I64,
+            2 => types::F32,
+            3 => types::F64,
+            _ => return None,
+        })
+    }
+
+    fn value_types(&mut self) -> Option<Vec<types::Value>> {
+        self.vec(Self::value_type)
+    }
+
+    fn limits(&mut self) -> Option<types::Limits> {
+        Some(types::Limits {
+            min: self.u32()?,
+            max: self.option_u32()?,
+        })
+    }
+
+    fn table(&mut self) -> Option<types::Table> {
+        Some(types::Table {
+            limits: self.limits()?,
+            elem: types::Elem::AnyFunc,
+        })
+    }
+
+    fn memory(&mut self) -> Option<types::Memory> {
+        Some(types::Memory {
+            limits: self.limits()?,
+        })
+    }
+
+    fn global(&mut self) -> Option<types::Global> {
+        Some(types::Global {
+            value: self.value_type()?,
+            mutable: self.bool()?,
+        })
+    }
+
+    fn module(&mut self) -> Option<Module> {
+        let types = self.vec(|d| {
+            Some(types::Func {
+                args: d.value_types()?,
+                result: d.value_types()?,
+            })
+        })?;
+        let funcs = self.vec(|d| {
+            Some(Func {
+                type_index: d.u32()?,
+                locals: d.value_types()?,
+                body: d.expr()?,
+            })
+        })?;
+        let tables = self.vec(|d| Some(Table { type_: d.table()? }))?;
+        let memories = self.vec(|d| Some(Memory { type_: d.memory()? }))?;
+        let globals = self.vec(|d| {
+            Some(Global {
+                type_: d.global()?,
+                value: d.expr()?,
+            })
+        })?;
+        let elems = self.vec(|d| {
+            Some(Segment {
+                index: d.u32()?,
+                offset: d.expr()?,
+                init: d.vec(Self::u32)?,
+            })
+        })?;
+        let data = self.vec(|d| {
+            Some(Segment {
+                index: d.u32()?,
+                offset: d.expr()?,
+                init: d.bytes()?,
+            })
+        })?;
+        let start = self.option_u32()?;
+        let imports = self.vec(|d| {
+            Some(Import {
+                module: d.string()?,
+                name: d.string()?,
+                desc: match d.byte()? {
+                    0 => ImportDesc::Func(d.u32()?),
+                    1 => ImportDesc::Table(d.table()?),
+                    2 => ImportDesc::Memory(d.memory()?),
+                    3 => ImportDesc::Global(d.global()?),
+                    _ => return None,
+                },
+            })
+        })?;
+        let exports = self.vec(|d| {
+            Some(Export {
+                name: d.string()?,
+                desc: match d.byte()? {
+                    0 => ExportDesc::Func(d.u32()?),
+                    1 => ExportDesc::Table(d.u32()?),
+                    2 => ExportDesc::Memory(d.u32()?),
+                    3 => ExportDesc::Global(d.u32()?),
+                    _ => return None,
+                },
+            })
+        })?;
+        Some(Module {
+            types,
+            funcs,
+            tables,
+            memories,
+            globals,
+            elems,
+            data,
+            start,
+            imports,
+            exports,
+        })
+    }
+
+    fn expr(&mut self) -> Option<Expr> {
+        self.vec(Self::instr)
+    }
+
+    fn mem_op<T>(&mut self, opt: impl FnOnce(&mut Self) -> Option<Option<T>>) -> Option<MemOp<T>> {
+        Some(MemOp {
+            align: self.u32()?,
+            offset: self.u32()?,
+            type_: self.value_type()?,
+            opt: opt(self)?,
+        })
+    }
+
+    fn instr(&mut self) -> Option<Instr> {
+        use self::Instr::*;
+
+        Some(match self.byte()? {
+            0 => Unreachable,
+            1 => Nop,
+            2 => Block(self.value_types()?, self.expr()?),
+            3 => Loop(self.value_types()?, self.expr()?),
+            4 => If(self.value_types()?, self.expr()?, self.expr()?),
+            5 => Br(self.u32()?),
+            6 => BrIf(self.u32()?),
+            7 => BrTable(self.vec(Self::u32)?, self.u32()?),
+            8 => Return,
+            9 => Call(self.u32()?),
+            10 => CallIndirect(self.u32()?),
+            11 => Drop_,
+            12 => Select,
+            13 => GetLocal(self.u32()?),
+            14 => SetLocal(self.u32()?),
+            15 => TeeLocal(self.u32()?),
+            16 => GetGlobal(self.u32()?),
+            17 => SetGlobal(self.u32()?),
+            18 => Load(self.mem_op(|d| match d.byte()? {
+                0 => Some(None),
+                1 => Some(Some((d.u32()?, d.bool()?))),
+                _ => None,
+            })?),
+            19 => Store(self.mem_op(Self::option_u32)?),
+            20 => CurrentMemory,
+            21 => GrowMemory,
+            22 => Const(Value::I32(self.u32()?)),
+            23 => Const(Value::I64(self.u64()?)),
+            24 => Const(Value::F32(f32::from_bits(self.fixed_u32()?))),
+            25 => Const(Value::F64(f64::from_bits(self.fixed_u64()?))),
+            26 => {
+                let byte = self.byte()?;
+                IUnary(to_int(byte), to_iunop(byte >> 1)?)
+            }
+            27 => {
+                let byte = self.byte()?;
+                FUnary(to_float(byte), to_funop(byte >> 1)?)
+            }
+            28 => {
+                let byte = self.byte()?;
+                IBin(to_int(byte), to_ibinop(byte >> 1)?)
+            }
+            29 => {
+                let byte = self.byte()?;
+                FBin(to_float(byte), to_fbinop(byte >> 1)?)
+            }
+            30 => ITest(to_int(self.byte()?), ITestOp::Eqz),
+            31 => {
+                let byte = self.byte()?;
+                IRel(to_int(byte), to_irelop(byte >> 1)?)
+            }
+            32 => {
+                let byte = self.byte()?;
+                FRel(to_float(byte), to_frelop(byte >> 1)?)
+            }
+            33 => Convert(self.convert_op()?),
+            _ => return None,
+        })
+    }
+
+    fn convert_op(&mut self) -> Option<ConvertOp> {
+        Some(match self.byte()? {
+            0 => ConvertOp::I32WrapI64,
+            1 => ConvertOp::I64ExtendUI32,
+            2 => ConvertOp::I64ExtendSI32,
+            3 => {
+                let byte = self.byte()?;
+                ConvertOp::Trunc {
+                    from: to_float(byte),
+                    to: to_int(byte >> 1),
+                    signed: byte & 4 != 0,
+                }
+            }
+            4 => {
+                let byte = self.byte()?;
+                ConvertOp::Convert {
+                    from: to_int(byte),
+                    to: to_float(byte >> 1),
+                    signed: byte & 4 != 0,
+                }
+            }
+            5 => ConvertOp::Reinterpret {
+                from: self.value_type()?,
+                to: self.value_type()?,
+            },
+            6 => ConvertOp::F32DemoteF64,
+            7 => ConvertOp::F64PromoteF32,
+            _ => return None,
+        })
+    }
+}
+
+fn int(type_: types::Int) -> u8 {
+    match type_ {
+        types::Int::I32 => 0,
+        types::Int::I64 => 1,
+    }
+}
+
+fn to_int(bits: u8) -> types::Int {
+    if bits & 1 == 0 {
+        types::Int::I32
+    } else {
+        types::Int::I64
+    }
+}
+
+fn float(type_: types::Float) -> u8 {
+    match type_ {
+        types::Float::F32 => 0,
+        types::Float::F64 => 1,
+    }
+}
+
+fn to_float(bits: u8) -> types::Float {
+    if bits & 1 == 0 {
+        types::Float::F32
+    } else {
+        types::Float::F64
+    }
+}
+
+/// Numbers the variants of the operator enums in the syntax tree, both ways.
+macro_rules! operators {
+    ($($op:ident: $code:ident $from_code:ident { $($variant:ident = $n:literal,)* })*) => {
+        $(
+            fn $code(op: &$op) -> u8 {
+                match op {
+                    $($op::$variant => $n,)*
+                }
+            }
+
+            fn $from_code(code: u8) -> Option<$op> {
+                match code {
+                    $($n => Some($op::$variant),)*
+                    _ => None,
+                }
+            }
+        )*
+    };
+}
+
+operators! {
+    IUnOp: iunop to_iunop {
+        Clz = 0,
+        Ctz = 1,
+        Popcnt = 2,
+    }
+    FUnOp: funop to_funop {
+        Neg = 0,
+        Abs = 1,
+        Ceil = 2,
+        Floor = 3,
+        Trunc = 4,
+        Nearest = 5,
+        Sqrt = 6,
+    }
+    IBinOp: ibinop to_ibinop {
+        Add = 0,
+        Sub = 1,
+        Mul = 2,
+        DivS = 3,
+        DivU = 4,
+        RemS = 5,
+        RemU = 6,
+        And = 7,
+        Or = 8,
+        Xor = 9,
+        Shl = 10,
+        ShrS = 11,
+        ShrU = 12,
+        Rotl = 13,
+        Rotr = 14,
+    }
+    FBinOp: fbinop to_fbinop {
+        Add = 0,
+        Sub = 1,
+        Mul = 2,
+        Div = 3,
+        Min = 4,
+        Max = 5,
+        CopySign = 6,
+    }
+    IRelOp: irelop to_irelop {
+        Eq_ = 0,
+        Ne = 1,
+        LtS = 2,
+        LtU = 3,
+        GtS = 4,
+        GtU = 5,
+        LeS = 6,
+        LeU = 7,
+        GeS = 8,
+        GeU = 9,
+    }
+    FRelOp: frelop to_frelop {
+        Eq_ = 0,
+        Ne = 1,
+        Lt = 2,
+        Gt = 3,
+        Le = 4,
+        Ge = 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    fn ctor_wasm() -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../ctor/src/ctor.wasm");
+        fs::read(path).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let wasm = ctor_wasm();
+        let snapshot = write(&wasm).unwrap();
+        assert_eq!(snapshot[..MAGIC.len()], MAGIC);
+
+        let decoded = decode_module(Cursor::new(&wasm)).unwrap();
+        let loaded = read(&snapshot).unwrap();
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", decoded));
+    }
+
+    #[test]
+    fn fall_back_to_raw_module() {
+        let wasm = ctor_wasm();
+        assert_eq!(read(&wasm).unwrap_err(), wasm);
+
+        let mut snapshot = write(&wasm).unwrap();
+        snapshot.pop();
+        assert_eq!(read(&snapshot).unwrap_err(), wasm);
+
+        let mut snapshot = write(&wasm).unwrap();
+        snapshot[MAGIC.len()..][..4].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        assert_eq!(read(&snapshot).unwrap_err(), wasm);
+    }
+
+    #[test]
+    fn invalid_module() {
+        let err = write(b"\0asm\x02\0\0\0").unwrap_err();
+        assert_eq!(err, "failed to decode the module: DecodeModuleFailed");
+    }
+}
@@ -0,0 +1,74 @@
+//! Expands a synthetic crate of 300 structs deriving `Serialize` and
+//! `Deserialize`, once loading `serde_derive.wasm` for every invocation and
+//! once keeping it loaded for the whole run.
+//!
+//!     cargo bench --bench many_derives
+
+use proc_macro2::TokenStream;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use watt_expand::host;
+
+const ITEMS: usize = 300;
+
+fn items() -> Vec<TokenStream> {
+    (0..ITEMS)
+        .map(|i| {
+            let fields: String = (0..i % 8 + 1)
+                .map(|j| {
+                    format!(
+                        "#[serde(default)] field_{}: Option<Vec<u{}>>, ",
+                        j,
+                        8 << (j % 4)
+                    )
+                })
+                .collect();
+            format!(
+                "#[serde(rename_all = \"camelCase\")] struct Item{} {{ {} }}",
+                i, fields
+            )
+            .parse()
+            .unwrap()
+        })
+        .collect()
+}
+
+fn run(
+    name: &str,
+    items: &[TokenStream],
+    mut expand: impl FnMut(&str, TokenStream) -> TokenStream,
+) -> Duration {
+    let start = Instant::now();
+    for item in items {
+        for entry_point in &["derive_serialize", "derive_deserialize"] {
+            let output = expand(entry_point, item.clone());
+            assert!(!output.is_empty());
+        }
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:>8}: {:>6} ms for {} expansions",
+        name,
+        elapsed.as_millis(),
+        items.len() * 2,
+    );
+    elapsed
+}
+
+fn main() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../serde_derive/src/serde_derive.wasm");
+    let wasm: &'static [u8] = Box::leak(fs::read(path).unwrap().into_boxed_slice());
+    let items = items();
+
+    let fresh = run("fresh", &items, |entry_point, input| {
+        host::expand(wasm, entry_point, None, input).unwrap()
+    });
+    let pooled = run("pooled", &items, |entry_point, input| {
+        host::expand_pooled(wasm, entry_point, None, input).unwrap()
+    });
+    println!(
+        "speedup: {:.1}x",
+        fresh.as_secs_f64() / pooled.as_secs_f64()
+    );
+}
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use watt_expand_fuzz::DeriveCase;
+
+fuzz_target!(|case: DeriveCase| watt_expand_fuzz::run(&case.dump()));
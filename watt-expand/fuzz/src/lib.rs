@@ -0,0 +1,1103 @@
+//! Structurally valid Rust items for the fuzz targets, and the entry points
+//! they are fed to.
+//!
+//! The items are built from small vocabularies of names, types and helper
+//! attributes, so most inputs get past parsing and exercise the code that
+//! generates the expansion.
+//!
+//! The entry points are listed by the directory of their crate in this
+//! repository, since a crate can be here in several versions.
+//!
+//! Every input runs in wasmi, which stops expansions that don't terminate,
+//! and then on watt's own interpreter, which is what runs the modules inside
+//! rustc. The two have to agree. Watt's `WasmMacro` itself only works inside
+//! a proc macro, as it hands the module `proc_macro` tokens.
+
+use arbitrary::Arbitrary;
+use proc_macro2::TokenStream;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::Path;
+use watt_contrib_dump::{Dump, Kind};
+use watt_expand::host::{self, Expander, Panic};
+use watt_expand::watt;
+
+/// Derive entry points, which get a struct or an enum.
+pub const DERIVES: &[(&str, &str)] = &[
+    ("actix_derive", "message_derive_rtype"),
+    ("actix_derive", "message_response_derive_rtype"),
+    ("derivative", "derivative"),
+    ("derive-new", "derive"),
+    ("derive-new-0.7", "derive"),
+    ("enum-as-inner", "enum_as_inner"),
+    ("enum-as-inner-0.6", "enum_as_inner"),
+    ("num-derive", "float"),
+    ("num-derive", "from_primitive"),
+    ("num-derive", "num"),
+    ("num-derive", "num_cast"),
+    ("num-derive", "num_ops"),
+    ("num-derive", "one"),
+    ("num-derive", "to_primitive"),
+    ("num-derive", "zero"),
+    ("pin-project-internal", "__pin_project_internal_derive"),
+    ("pin-project-internal-1", "__pin_project_internal_derive"),
+    ("proc-macro-hack", "enum_hack"),
+    ("scroll_derive", "derive_ioread"),
+    ("scroll_derive", "derive_iowrite"),
+    ("scroll_derive", "derive_pread"),
+    ("scroll_derive", "derive_pwrite"),
+    ("scroll_derive", "derive_sizewith"),
+    ("scroll_derive-0.13", "derive_ioread"),
+    ("scroll_derive-0.13", "derive_iowrite"),
+    ("scroll_derive-0.13", "derive_pread"),
+    ("scroll_derive-0.13", "derive_pwrite"),
+    ("scroll_derive-0.13", "derive_sizewith"),
+    ("serde_derive", "derive_deserialize"),
+    ("serde_derive", "derive_serialize"),
+    ("serde_repr", "derive_deserialize"),
+    ("serde_repr", "derive_serialize"),
+    ("strum_macros", "as_ref_str"),
+    ("strum_macros", "as_static_str"),
+    ("strum_macros", "display"),
+    ("strum_macros", "enum_count"),
+    ("strum_macros", "enum_discriminants"),
+    ("strum_macros", "enum_iter"),
+    ("strum_macros", "enum_messages"),
+    ("strum_macros", "enum_properties"),
+    ("strum_macros", "from_string"),
+    ("strum_macros", "into_static_str"),
+    ("strum_macros", "to_string"),
+    ("strum_macros", "variant_names"),
+    ("strum_macros-0.27", "as_ref_str"),
+    ("strum_macros-0.27", "as_static_str"),
+    ("strum_macros-0.27", "display"),
+    ("strum_macros-0.27", "enum_count"),
+    ("strum_macros-0.27", "enum_discriminants"),
+    ("strum_macros-0.27", "enum_is"),
+    ("strum_macros-0.27", "enum_iter"),
+    ("strum_macros-0.27", "enum_messages"),
+    ("strum_macros-0.27", "enum_properties"),
+    ("strum_macros-0.27", "enum_table"),
+    ("strum_macros-0.27", "enum_try_as"),
+    ("strum_macros-0.27", "from_repr"),
+    ("strum_macros-0.27", "from_string"),
+    ("strum_macros-0.27", "into_static_str"),
+    ("strum_macros-0.27", "static_variants_array"),
+    ("strum_macros-0.27", "to_string"),
+    ("strum_macros-0.27", "variant_names"),
+    ("strum_macros-0.27", "variant_names_deprecated"),
+    ("thiserror-impl", "derive_error"),
+];
+
+/// Attribute entry points, which get any item.
+pub const ATTRIBUTES: &[(&str, &str)] = &[
+    ("actix-web-codegen", "connect"),
+    ("actix-web-codegen", "delete"),
+    ("actix-web-codegen", "get"),
+    ("actix-web-codegen", "head"),
+    ("actix-web-codegen", "main"),
+    ("actix-web-codegen", "options"),
+    ("actix-web-codegen", "patch"),
+    ("actix-web-codegen", "post"),
+    ("actix-web-codegen", "put"),
+    ("actix-web-codegen", "trace"),
+    ("actix-web-codegen-4", "connect"),
+    ("actix-web-codegen-4", "delete"),
+    ("actix-web-codegen-4", "get"),
+    ("actix-web-codegen-4", "head"),
+    ("actix-web-codegen-4", "main"),
+    ("actix-web-codegen-4", "options"),
+    ("actix-web-codegen-4", "patch"),
+    ("actix-web-codegen-4", "post"),
+    ("actix-web-codegen-4", "put"),
+    ("actix-web-codegen-4", "route"),
+    ("actix-web-codegen-4", "routes"),
+    ("actix-web-codegen-4", "scope"),
+    ("actix-web-codegen-4", "test"),
+    ("actix-web-codegen-4", "trace"),
+    ("async-trait", "async_trait"),
+    ("ctor", "ctor"),
+    ("ctor", "dtor"),
+    ("futures-macro", "test_internal"),
+    ("pin-project-internal", "pin_project"),
+    ("pin-project-internal", "pinned_drop"),
+    ("pin-project-internal", "project"),
+    ("pin-project-internal", "project_ref"),
+    ("pin-project-internal", "project_replace"),
+    ("pin-project-internal-1", "pin_project"),
+    ("pin-project-internal-1", "pinned_drop"),
+    ("proc-macro-error-attr", "proc_macro_error"),
+    ("proc-macro-hack", "fake_call_site"),
+    ("proc-macro-hack", "proc_macro_hack"),
+    ("rustversion", "all"),
+    ("rustversion", "any"),
+    ("rustversion", "attr"),
+    ("rustversion", "before"),
+    ("rustversion", "beta"),
+    ("rustversion", "nightly"),
+    ("rustversion", "not"),
+    ("rustversion", "since"),
+    ("rustversion", "stable"),
+    ("tokio-macros", "main"),
+    ("tokio-macros", "main_basic"),
+    ("tokio-macros", "main_threaded"),
+    ("tokio-macros", "test"),
+    ("tokio-macros", "test_basic"),
+    ("tokio-macros", "test_threaded"),
+    ("tokio-macros-1", "main"),
+    ("tokio-macros-1", "main_fail"),
+    ("tokio-macros-1", "main_rt"),
+    ("tokio-macros-1", "test"),
+    ("tokio-macros-1", "test_fail"),
+    ("tokio-macros-1", "test_rt"),
+    ("tracing-attributes", "instrument"),
+    ("wasm-bindgen-macro", "__wasm_bindgen_class_marker"),
+    ("wasm-bindgen-macro", "wasm_bindgen"),
+    ("wasm-bindgen-test-macro", "wasm_bindgen_test"),
+];
+
+/// Function-like entry points, which get a list of [`Arg`]s.
+pub const FUNCTIONS: &[(&str, &str)] = &[
+    ("futures-macro", "join_internal"),
+    ("futures-macro", "select_biased_internal"),
+    ("futures-macro", "select_internal"),
+    ("futures-macro", "stream_select_internal"),
+    ("futures-macro", "try_join_internal"),
+    ("tokio-macros", "select_priv_declare_output_enum"),
+    ("tokio-macros-1", "select_priv_clean_pattern"),
+    ("tokio-macros-1", "select_priv_declare_output_enum"),
+];
+
+const NAMES: &[&str] = &[
+    "A", "B", "Foo", "Bar", "x", "y", "value", "new", "run", "main", "r#type", "r#async", "Self_",
+];
+
+const WORDS: &[&str] = &[
+    "rename",
+    "rename_all",
+    "default",
+    "skip",
+    "skip_serializing_if",
+    "flatten",
+    "tag",
+    "content",
+    "untagged",
+    "transparent",
+    "with",
+    "bound",
+    "borrow",
+    "alias",
+    "from",
+    "into",
+    "try_from",
+    "deny_unknown_fields",
+    "source",
+    "backtrace",
+    "message",
+    "serialize",
+    "deserialize",
+    "to_string",
+    "serialize_all",
+    "ascii_case_insensitive",
+    "Debug",
+    "Clone",
+    "PartialEq",
+    "Default",
+    "skip_all",
+    "fields",
+    "level",
+    "name",
+    "target",
+    "err",
+    "ret",
+    "project",
+    "project_ref",
+    "PinnedDrop",
+    "UnsafeUnpin",
+    "flavor",
+    "core_threads",
+    "crate",
+    "x",
+    "T",
+];
+
+const HELPERS: &[&str] = &[
+    "serde",
+    "error",
+    "strum",
+    "derivative",
+    "new",
+    "pin",
+    "source",
+    "from",
+    "backtrace",
+    "doc",
+    "repr",
+    "instrument",
+    "cfg",
+];
+
+const STRINGS: &[&str] = &[
+    "camelCase",
+    "snake_case",
+    "{0}",
+    "{x}",
+    "{}",
+    "{x:?} and {1}",
+    "T: Clone",
+    "path::to::f",
+    "",
+    "\\u{1f600}",
+    "debug",
+    "current_thread",
+];
+
+const TYPES: &[&str] = &[
+    "u8", "i64", "f32", "bool", "char", "String", "T", "Self", "str", "Vec", "Option", "Box",
+    "HashMap", "Result",
+];
+
+/// Picks a name from a vocabulary.
+#[derive(Arbitrary, Clone, Copy)]
+pub struct Word(u8);
+
+impl Word {
+    fn of(self, list: &[&'static str]) -> &'static str {
+        list[self.0 as usize % list.len()]
+    }
+}
+
+#[derive(Arbitrary)]
+pub enum Lit {
+    Str(Word),
+    Int(i16),
+    Float(u8),
+    Bool(bool),
+    Char(char),
+    ByteStr(Word),
+}
+
+#[derive(Arbitrary)]
+pub enum Meta {
+    Path(Word),
+    NameValue(Word, Lit),
+    List(Word, Vec<Meta>),
+    Lit(Lit),
+}
+
+#[derive(Arbitrary)]
+pub struct Attr {
+    name: Word,
+    args: Option<Vec<Meta>>,
+    value: Option<Lit>,
+}
+
+#[derive(Arbitrary)]
+pub enum Type {
+    Path(Word, Vec<Type>),
+    Ref(bool, bool, Box<Type>),
+    Tuple(Vec<Type>),
+    Array(Box<Type>, u8),
+    Slice(Box<Type>),
+    Fn(Vec<Type>, Box<Type>),
+    Dyn(Word),
+    Impl(Word),
+    Never,
+}
+
+#[derive(Arbitrary)]
+pub enum Param {
+    Lifetime,
+    Type(Word, Option<Word>),
+    Const(Word),
+}
+
+#[derive(Arbitrary)]
+pub struct Generics {
+    params: Vec<Param>,
+    where_clause: Option<(Word, Word)>,
+}
+
+#[derive(Arbitrary)]
+pub struct Field {
+    attrs: Vec<Attr>,
+    public: bool,
+    name: Word,
+    ty: Type,
+}
+
+#[derive(Arbitrary)]
+pub enum Fields {
+    Unit,
+    Tuple(Vec<Field>),
+    Named(Vec<Field>),
+}
+
+#[derive(Arbitrary)]
+pub struct Variant {
+    attrs: Vec<Attr>,
+    name: Word,
+    fields: Fields,
+    discriminant: Option<i8>,
+}
+
+/// The input of a derive.
+#[derive(Arbitrary)]
+pub enum Data {
+    Struct(Generics, Fields),
+    Enum(Generics, Vec<Variant>),
+    Union(Generics, Vec<Field>),
+}
+
+#[derive(Arbitrary)]
+pub enum Expr {
+    Lit(Lit),
+    Path(Word),
+    Call(Word, Vec<Expr>),
+    Method(Box<Expr>, Word, Vec<Expr>),
+    Await(Box<Expr>),
+    Try(Box<Expr>),
+    Block(Vec<Stmt>),
+    Async(Vec<Stmt>),
+    Closure(Vec<Word>, Box<Expr>),
+    Return(Option<Box<Expr>>),
+    Macro(Word, Vec<Expr>),
+}
+
+#[derive(Arbitrary)]
+pub enum Stmt {
+    Let(Word, Option<Type>, Expr),
+    Expr(Expr),
+    Semi(Expr),
+    Item(Box<Item>),
+}
+
+#[derive(Arbitrary)]
+pub struct Signature {
+    asyncness: bool,
+    unsafety: bool,
+    name: Word,
+    generics: Generics,
+    receiver: Option<u8>,
+    inputs: Vec<(Word, Type)>,
+    output: Option<Type>,
+}
+
+#[derive(Arbitrary)]
+pub struct Fn {
+    attrs: Vec<Attr>,
+    public: bool,
+    sig: Signature,
+    body: Vec<Stmt>,
+}
+
+/// The input of an attribute.
+#[derive(Arbitrary)]
+pub enum Item {
+    Data(Vec<Attr>, Word, Data),
+    Fn(Fn),
+    Trait(Word, Generics, Vec<(Signature, Option<Vec<Stmt>>)>),
+    Impl(Generics, Option<Word>, Type, Vec<Fn>),
+    Const(Word, Type, Expr),
+}
+
+/// One argument of a function-like macro: a future of `join!`, a branch of
+/// `select!`, or the `(_ _ _)` that `tokio::select!` passes on to count its
+/// branches.
+#[derive(Arbitrary)]
+pub enum Arg {
+    Expr(Expr),
+    Branch(Word, Expr, Expr),
+    Default(Expr),
+    Complete(Expr),
+    Underscores(u8),
+}
+
+/// Renders the `Arbitrary` types as source. Nesting is cut off at
+/// `MAX_DEPTH`, since input nested a few dozen levels deep makes the macros
+/// nest more calls than watt allows, which isn't what the fuzzer should
+/// spend its time on.
+struct Writer {
+    out: String,
+    depth: usize,
+}
+
+const MAX_DEPTH: usize = 8;
+
+impl Writer {
+    fn nested(&mut self, f: impl FnOnce(&mut Self)) {
+        if self.depth < MAX_DEPTH {
+            self.depth += 1;
+            f(self);
+            self.depth -= 1;
+        }
+    }
+
+    fn list<T>(&mut self, items: &[T], separator: &str, mut f: impl FnMut(&mut Self, &T)) {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(separator);
+            }
+            f(self, item);
+        }
+    }
+
+    fn lit(&mut self, lit: &Lit) {
+        let out = &mut self.out;
+        let _ = match lit {
+            Lit::Str(word) => write!(out, "{:?}", word.of(STRINGS)),
+            Lit::Int(n) => write!(out, "{}", n),
+            Lit::Float(n) => write!(out, "{}.5", n),
+            Lit::Bool(b) => write!(out, "{}", b),
+            Lit::Char(c) => write!(out, "{:?}", c),
+            Lit::ByteStr(word) => {
+                write!(out, "b{:?}", word.of(STRINGS).escape_default().to_string())
+            }
+        };
+    }
+
+    fn meta(&mut self, meta: &Meta) {
+        match meta {
+            Meta::Path(word) => self.out.push_str(word.of(WORDS)),
+            Meta::NameValue(word, lit) => {
+                self.out.push_str(word.of(WORDS));
+                self.out.push_str(" = ");
+                self.lit(lit);
+            }
+            Meta::List(word, metas) => {
+                self.out.push_str(word.of(WORDS));
+                self.out.push('(');
+                self.nested(|w| w.list(metas, ", ", Self::meta));
+                self.out.push(')');
+            }
+            Meta::Lit(lit) => self.lit(lit),
+        }
+    }
+
+    fn attrs(&mut self, attrs: &[Attr]) {
+        for attr in attrs {
+            self.out.push_str("#[");
+            self.out.push_str(attr.name.of(HELPERS));
+            if let Some(args) = &attr.args {
+                self.out.push('(');
+                self.nested(|w| w.list(args, ", ", Self::meta));
+                self.out.push(')');
+            } else if let Some(value) = &attr.value {
+                self.out.push_str(" = ");
+                self.lit(value);
+            }
+            self.out.push_str("] ");
+        }
+    }
+
+    fn ty(&mut self, ty: &Type) {
+        if self.depth >= MAX_DEPTH {
+            self.out.push_str("u8");
+            return;
+        }
+        self.depth += 1;
+        match ty {
+            Type::Path(word, args) => {
+                self.out.push_str(word.of(TYPES));
+                if !args.is_empty() {
+                    self.out.push('<');
+                    self.list(args, ", ", Self::ty);
+                    self.out.push('>');
+                }
+            }
+            Type::Ref(lifetime, mutable, ty) => {
+                self.out.push('&');
+                if *lifetime {
+                    self.out.push_str("'a ");
+                }
+                if *mutable {
+                    self.out.push_str("mut ");
+                }
+                self.ty(ty);
+            }
+            Type::Tuple(types) => {
+                self.out.push('(');
+                self.list(types, ", ", Self::ty);
+                if types.len() == 1 {
+                    self.out.push(',');
+                }
+                self.out.push(')');
+            }
+            Type::Array(ty, len) => {
+                self.out.push('[');
+                self.ty(ty);
+                let _ = write!(self.out, "; {}]", len);
+            }
+            Type::Slice(ty) => {
+                self.out.push('[');
+                self.ty(ty);
+                self.out.push(']');
+            }
+            Type::Fn(inputs, output) => {
+                self.out.push_str("fn(");
+                self.list(inputs, ", ", Self::ty);
+                self.out.push_str(") -> ");
+                self.ty(output);
+            }
+            Type::Dyn(word) => {
+                let _ = write!(self.out, "Box<dyn {} + 'static>", word.of(WORDS));
+            }
+            Type::Impl(word) => {
+                let _ = write!(self.out, "impl {}", word.of(WORDS));
+            }
+            Type::Never => self.out.push('!'),
+        }
+        self.depth -= 1;
+    }
+
+    fn generics(&mut self, generics: &Generics) {
+        if !generics.params.is_empty() {
+            self.out.push('<');
+            self.list(&generics.params, ", ", |w, param| match param {
+                Param::Lifetime => w.out.push_str("'a"),
+                Param::Type(name, bound) => {
+                    w.out.push_str(name.of(TYPES));
+                    if let Some(bound) = bound {
+                        w.out.push_str(": ");
+                        w.out.push_str(bound.of(WORDS));
+                    }
+                }
+                Param::Const(name) => {
+                    let _ = write!(w.out, "const {}: usize", name.of(NAMES));
+                }
+            });
+            self.out.push('>');
+        }
+    }
+
+    fn where_clause(&mut self, generics: &Generics) {
+        if let Some((ty, bound)) = generics.where_clause {
+            let _ = write!(self.out, " where {}: {}", ty.of(TYPES), bound.of(WORDS));
+        }
+    }
+
+    fn fields(&mut self, fields: &Fields) {
+        match fields {
+            Fields::Unit => {}
+            Fields::Tuple(fields) => {
+                self.out.push('(');
+                self.list(fields, ", ", |w, field| {
+                    w.attrs(&field.attrs);
+                    if field.public {
+                        w.out.push_str("pub ");
+                    }
+                    w.ty(&field.ty);
+                });
+                self.out.push(')');
+            }
+            Fields::Named(fields) => self.named_fields(fields),
+        }
+    }
+
+    fn named_fields(&mut self, fields: &[Field]) {
+        self.out.push_str(" {");
+        for field in fields {
+            self.attrs(&field.attrs);
+            if field.public {
+                self.out.push_str("pub ");
+            }
+            self.out.push_str(field.name.of(NAMES));
+            self.out.push_str(": ");
+            self.ty(&field.ty);
+            self.out.push_str(", ");
+        }
+        self.out.push('}');
+    }
+
+    fn data(&mut self, attrs: &[Attr], name: Word, data: &Data) {
+        self.attrs(attrs);
+        let name = name.of(NAMES);
+        match data {
+            Data::Struct(generics, fields) => {
+                let _ = write!(self.out, "struct {}", name);
+                self.generics(generics);
+                match fields {
+                    Fields::Named(fields) => {
+                        self.where_clause(generics);
+                        self.named_fields(fields);
+                    }
+                    fields => {
+                        self.fields(fields);
+                        self.where_clause(generics);
+                        self.out.push(';');
+                    }
+                }
+            }
+            Data::Enum(generics, variants) => {
+                let _ = write!(self.out, "enum {}", name);
+                self.generics(generics);
+                self.where_clause(generics);
+                self.out.push_str(" {");
+                for variant in variants {
+                    self.attrs(&variant.attrs);
+                    self.out.push_str(variant.name.of(NAMES));
+                    self.fields(&variant.fields);
+                    if let Some(discriminant) = variant.discriminant {
+                        let _ = write!(self.out, " = {}", discriminant);
+                    }
+                    self.out.push_str(", ");
+                }
+                self.out.push('}');
+            }
+            Data::Union(generics, fields) => {
+                let _ = write!(self.out, "union {}", name);
+                self.generics(generics);
+                self.where_clause(generics);
+                self.named_fields(fields);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        if self.depth >= MAX_DEPTH {
+            self.out.push_str("()");
+            return;
+        }
+        self.depth += 1;
+        match expr {
+            Expr::Lit(lit) => self.lit(lit),
+            Expr::Path(word) => self.out.push_str(word.of(NAMES)),
+            Expr::Call(word, args) => {
+                self.out.push_str(word.of(NAMES));
+                self.out.push('(');
+                self.list(args, ", ", Self::expr);
+                self.out.push(')');
+            }
+            Expr::Method(receiver, word, args) => {
+                self.expr(receiver);
+                let _ = write!(self.out, ".{}(", word.of(NAMES));
+                self.list(args, ", ", Self::expr);
+                self.out.push(')');
+            }
+            Expr::Await(expr) => {
+                self.expr(expr);
+                self.out.push_str(".await");
+            }
+            Expr::Try(expr) => {
+                self.expr(expr);
+                self.out.push('?');
+            }
+            Expr::Block(stmts) => self.block(stmts),
+            Expr::Async(stmts) => {
+                self.out.push_str("async move ");
+                self.block(stmts);
+            }
+            Expr::Closure(args, body) => {
+                self.out.push('|');
+                self.list(args, ", ", |w, arg| w.out.push_str(arg.of(NAMES)));
+                self.out.push_str("| ");
+                self.expr(body);
+            }
+            Expr::Return(expr) => {
+                self.out.push_str("return");
+                if let Some(expr) = expr {
+                    self.out.push(' ');
+                    self.expr(expr);
+                }
+            }
+            Expr::Macro(word, args) => {
+                let _ = write!(self.out, "{}!(", word.of(WORDS));
+                self.list(args, ", ", Self::expr);
+                self.out.push(')');
+            }
+        }
+        self.depth -= 1;
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) {
+        self.out.push_str("{ ");
+        self.nested(|w| {
+            for stmt in stmts {
+                match stmt {
+                    Stmt::Let(name, ty, expr) => {
+                        let _ = write!(w.out, "let {}", name.of(NAMES));
+                        if let Some(ty) = ty {
+                            w.out.push_str(": ");
+                            w.ty(ty);
+                        }
+                        w.out.push_str(" = ");
+                        w.expr(expr);
+                        w.out.push_str("; ");
+                    }
+                    Stmt::Expr(expr) => {
+                        w.expr(expr);
+                        w.out.push(' ');
+                    }
+                    Stmt::Semi(expr) => {
+                        w.expr(expr);
+                        w.out.push_str("; ");
+                    }
+                    Stmt::Item(item) => {
+                        w.item(item);
+                        w.out.push(' ');
+                    }
+                }
+            }
+        });
+        self.out.push('}');
+    }
+
+    fn signature(&mut self, sig: &Signature) {
+        if sig.asyncness {
+            self.out.push_str("async ");
+        }
+        if sig.unsafety {
+            self.out.push_str("unsafe ");
+        }
+        let _ = write!(self.out, "fn {}", sig.name.of(NAMES));
+        self.generics(&sig.generics);
+        self.out.push('(');
+        let receiver = sig.receiver.map(|receiver| match receiver % 4 {
+            0 => "self",
+            1 => "&self",
+            2 => "&mut self",
+            _ => "self: Pin<&mut Self>",
+        });
+        if let Some(receiver) = receiver {
+            self.out.push_str(receiver);
+            if !sig.inputs.is_empty() {
+                self.out.push_str(", ");
+            }
+        }
+        self.list(&sig.inputs, ", ", |w, (name, ty)| {
+            w.out.push_str(name.of(NAMES));
+            w.out.push_str(": ");
+            w.ty(ty);
+        });
+        self.out.push(')');
+        if let Some(output) = &sig.output {
+            self.out.push_str(" -> ");
+            self.ty(output);
+        }
+        self.where_clause(&sig.generics);
+    }
+
+    fn function(&mut self, function: &Fn) {
+        self.attrs(&function.attrs);
+        if function.public {
+            self.out.push_str("pub ");
+        }
+        self.signature(&function.sig);
+        self.out.push(' ');
+        self.block(&function.body);
+    }
+
+    fn item(&mut self, item: &Item) {
+        if self.depth >= MAX_DEPTH {
+            self.out.push_str("struct S;");
+            return;
+        }
+        self.depth += 1;
+        match item {
+            Item::Data(attrs, name, data) => self.data(attrs, *name, data),
+            Item::Fn(function) => self.function(function),
+            Item::Trait(name, generics, items) => {
+                let _ = write!(self.out, "trait {}", name.of(NAMES));
+                self.generics(generics);
+                self.where_clause(generics);
+                self.out.push_str(" { ");
+                for (sig, body) in items {
+                    self.signature(sig);
+                    match body {
+                        Some(body) => self.block(body),
+                        None => self.out.push(';'),
+                    }
+                    self.out.push(' ');
+                }
+                self.out.push('}');
+            }
+            Item::Impl(generics, trait_, self_ty, functions) => {
+                self.out.push_str("impl");
+                self.generics(generics);
+                self.out.push(' ');
+                if let Some(trait_) = trait_ {
+                    let _ = write!(self.out, "{} for ", trait_.of(WORDS));
+                }
+                self.ty(self_ty);
+                self.where_clause(generics);
+                self.out.push_str(" { ");
+                for function in functions {
+                    self.function(function);
+                    self.out.push(' ');
+                }
+                self.out.push('}');
+            }
+            Item::Const(name, ty, expr) => {
+                let _ = write!(self.out, "const {}: ", name.of(NAMES));
+                self.ty(ty);
+                self.out.push_str(" = ");
+                self.expr(expr);
+                self.out.push(';');
+            }
+        }
+        self.depth -= 1;
+    }
+}
+
+impl Writer {
+    fn arg(&mut self, arg: &Arg) {
+        match arg {
+            Arg::Expr(expr) => self.expr(expr),
+            Arg::Branch(name, future, body) => {
+                let _ = write!(self.out, "{} = ", name.of(NAMES));
+                self.expr(future);
+                self.out.push_str(" => ");
+                self.expr(body);
+            }
+            Arg::Default(body) => {
+                self.out.push_str("default => ");
+                self.expr(body);
+            }
+            Arg::Complete(body) => {
+                self.out.push_str("complete => ");
+                self.expr(body);
+            }
+            Arg::Underscores(n) => {
+                // Up to 64 branches are supported.
+                let underscores = vec!["_"; *n as usize % 72];
+                let _ = write!(self.out, "({})", underscores.join(" "));
+            }
+        }
+    }
+}
+
+fn render(f: impl FnOnce(&mut Writer)) -> String {
+    let mut writer = Writer {
+        out: String::new(),
+        depth: 0,
+    };
+    f(&mut writer);
+    writer.out
+}
+
+/// Input of the `derive` target.
+#[derive(Arbitrary)]
+pub struct DeriveCase {
+    entry_point: Word,
+    attrs: Vec<Attr>,
+    name: Word,
+    data: Data,
+}
+
+/// Input of the `attribute` target.
+#[derive(Arbitrary)]
+pub struct AttributeCase {
+    entry_point: Word,
+    args: Vec<Meta>,
+    item: Item,
+}
+
+/// Input of the `function` target.
+#[derive(Arbitrary)]
+pub struct FunctionCase {
+    entry_point: Word,
+    args: Vec<Arg>,
+}
+
+impl DeriveCase {
+    pub fn dump(&self) -> Dump {
+        let (dir, entry_point) = pick(DERIVES, self.entry_point);
+        let input = render(|w| w.data(&self.attrs, self.name, &self.data));
+        dump(dir, entry_point, Kind::Derive, None, input)
+    }
+}
+
+impl AttributeCase {
+    pub fn dump(&self) -> Dump {
+        let (dir, entry_point) = pick(ATTRIBUTES, self.entry_point);
+        let args = render(|w| w.list(&self.args, ", ", Writer::meta));
+        let input = render(|w| w.item(&self.item));
+        dump(dir, entry_point, Kind::Attribute, Some(args), input)
+    }
+}
+
+impl FunctionCase {
+    pub fn dump(&self) -> Dump {
+        let (dir, entry_point) = pick(FUNCTIONS, self.entry_point);
+        let input = render(|w| w.list(&self.args, ", ", Writer::arg));
+        dump(dir, entry_point, Kind::Function, None, input)
+    }
+}
+
+// `cargo fuzz fmt` prints the input in the format of `WATT_CONTRIB_DUMP`,
+// ready to be saved as a regression test.
+impl fmt::Debug for DeriveCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.dump().render())
+    }
+}
+
+impl fmt::Debug for AttributeCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.dump().render())
+    }
+}
+
+impl fmt::Debug for FunctionCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.dump().render())
+    }
+}
+
+fn pick(entry_points: &[(&'static str, &'static str)], word: Word) -> (&'static str, &'static str) {
+    entry_points[word.0 as usize % entry_points.len()]
+}
+
+fn dump(dir: &str, entry_point: &str, kind: Kind, args: Option<String>, input: String) -> Dump {
+    let (krate, version) = package(dir);
+    Dump {
+        krate,
+        version,
+        entry_point: entry_point.to_owned(),
+        kind,
+        caller: None,
+        location: None,
+        args,
+        input,
+        output: None,
+        formatted_output: None,
+    }
+}
+
+fn crate_dir(dir: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(dir)
+}
+
+/// The package name and version of the crate in `dir`.
+fn package(dir: &str) -> (String, String) {
+    let manifest = fs::read_to_string(crate_dir(dir).join("Cargo.toml")).unwrap();
+    let field = |name: &str| {
+        manifest
+            .lines()
+            .find_map(|line| {
+                line.strip_prefix(name)?
+                    .strip_prefix(" = \"")?
+                    .strip_suffix('"')
+            })
+            .unwrap()
+            .to_owned()
+    };
+    (field("name"), field("version"))
+}
+
+/// A module, and the same module loaded into wasmi.
+struct Loaded {
+    module: Vec<u8>,
+    expander: Expander,
+}
+
+/// Expands `dump` with the module of its crate and version in this
+/// repository, and panics if the module traps, runs out of fuel, returns
+/// something that isn't a token stream or gives a different result on watt's
+/// interpreter. Rejecting the input, with `compile_error!` or a panic, is
+/// fine.
+pub fn run(dump: &Dump) {
+    // Inputs the fuzzer made up that aren't even tokens aren't interesting.
+    let args = match dump
+        .args
+        .as_deref()
+        .map(str::parse::<TokenStream>)
+        .transpose()
+    {
+        Ok(args) => args,
+        Err(_) => return,
+    };
+    let input: TokenStream = match dump.input.parse() {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    thread_local! {
+        // Keyed by crate and version.
+        static MODULES: RefCell<HashMap<(String, String), Loaded>> = RefCell::new(HashMap::new());
+    }
+    MODULES.with(|modules| {
+        let mut modules = modules.borrow_mut();
+        let key = (dump.krate.clone(), dump.version.clone());
+        let Loaded { module, expander } = modules.entry(key).or_insert_with(|| {
+            let path = watt_expand::repo_module(&dump.krate, &dump.version).unwrap();
+            let module = fs::read(path).unwrap();
+            let expander = Expander::with_fuel(&module, host::FUEL).unwrap();
+            Loaded { module, expander }
+        });
+        let result = expander.expand(&dump.entry_point, args.clone(), input.clone());
+        match &result {
+            Err(err) if !err.is::<Panic>() => panic!("{:#}\n\n{}", err, dump.render()),
+            _ => {}
+        }
+        let on_watt = watt::expand(module, &dump.entry_point, args, input);
+        if let Err(err) = watt::compare(&result, &on_watt) {
+            panic!("{:#}\n\n{}", err, dump.render());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every entry point of every shim gets fuzzed.
+    #[test]
+    fn all_entry_points() {
+        let mut fuzzed: Vec<_> = DERIVES
+            .iter()
+            .chain(FUNCTIONS)
+            .map(|&(dir, entry_point)| (dir, entry_point, 1))
+            .chain(
+                ATTRIBUTES
+                    .iter()
+                    .map(|&(dir, entry_point)| (dir, entry_point, 2)),
+            )
+            .collect();
+        fuzzed.sort();
+
+        let mut shims = Vec::new();
+        for entry in fs::read_dir(crate_dir("")).unwrap() {
+            let dir = entry.unwrap().path();
+            if !dir.join("Cargo.watt.lock").is_file() {
+                continue;
+            }
+            let name = dir.file_name().unwrap().to_str().unwrap().to_owned();
+            let lib = fs::read_to_string(dir.join("src").join("lib.rs")).unwrap();
+            for (entry_point, params) in watt_contrib_build::entry_points(&lib) {
+                shims.push((name.clone(), entry_point, params));
+            }
+        }
+        shims.sort();
+
+        let shims: Vec<_> = shims
+            .iter()
+            .map(|(name, entry_point, params)| (name.as_str(), entry_point.as_str(), *params))
+            .collect();
+        assert_eq!(fuzzed, shims);
+    }
+
+    // Each entry point is looked up in the module of the directory it's listed
+    // under, not in another version of the same crate.
+    #[test]
+    fn modules() {
+        for &(dir, _) in DERIVES.iter().chain(ATTRIBUTES).chain(FUNCTIONS) {
+            let (krate, version) = package(dir);
+            let module = watt_expand::repo_module(&krate, &version).unwrap();
+            assert_eq!(
+                module
+                    .parent()
+                    .unwrap()
+                    .parent()
+                    .unwrap()
+                    .canonicalize()
+                    .unwrap(),
+                crate_dir(dir).canonicalize().unwrap(),
+            );
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! The serialization of token streams that watt's proc-macro2 runtime
+//! inside the wasm module exchanges with its host.
+//!
+//! A stream is a sequence of token trees terminated by `0`, each starting
+//! with a tag byte:
+//!
+//! ```text
+//! 1 group:   delimiter: u8, span: u32, stream
+//! 2 ident:   span: u32, len: u32, utf-8 text (`r#` prefix for raw idents)
+//! 3 punct:   char: u32, spacing: u8, span: u32
+//! 4 literal: span: u32, 0, len: u32, utf-8 text
+//!            span: u32, 1, handle: u32   (a literal owned by the host)
+//! ```
+//!
+//! Integers are little endian. Spans are opaque handles, `u32::MAX` being
+//! the call site. Outside of rustc every span is the call site, so they are
+//! written as `u32::MAX` and ignored when reading.
+
+use anyhow::{bail, Context, Result};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+const END: u8 = 0;
+const GROUP: u8 = 1;
+const IDENT: u8 = 2;
+const PUNCT: u8 = 3;
+const LITERAL: u8 = 4;
+
+const LITERAL_TEXT: u8 = 0;
+const LITERAL_HANDLE: u8 = 1;
+
+const CALL_SITE: u32 = u32::MAX;
+
+/// Encodes `stream`, moving its literals into `literals` so the module can
+/// ask for their text by handle.
+pub fn encode(stream: TokenStream, literals: &mut Vec<Literal>) -> Vec<u8> {
+    let mut dst = Vec::new();
+    encode_stream(stream, literals, &mut dst);
+    dst
+}
+
+fn encode_stream(stream: TokenStream, literals: &mut Vec<Literal>, dst: &mut Vec<u8>) {
+    for token in stream {
+        match token {
+            TokenTree::Group(group) => {
+                dst.push(GROUP);
+                dst.push(match group.delimiter() {
+                    Delimiter::Parenthesis => 0,
+                    Delimiter::Brace => 1,
+                    Delimiter::Bracket => 2,
+                    Delimiter::None => 3,
+                });
+                encode_u32(CALL_SITE, dst);
+                encode_stream(group.stream(), literals, dst);
+            }
+            TokenTree::Ident(ident) => {
+                dst.push(IDENT);
+                encode_u32(CALL_SITE, dst);
+                encode_str(&ident.to_string(), dst);
+            }
+            TokenTree::Punct(punct) => {
+                dst.push(PUNCT);
+                encode_u32(punct.as_char() as u32, dst);
+                dst.push(match punct.spacing() {
+                    Spacing::Alone => 0,
+                    Spacing::Joint => 1,
+                });
+                encode_u32(CALL_SITE, dst);
+            }
+            TokenTree::Literal(literal) => {
+                dst.push(LITERAL);
+                encode_u32(CALL_SITE, dst);
+                dst.push(LITERAL_HANDLE);
+                encode_u32(literals.len() as u32, dst);
+                literals.push(literal);
+            }
+        }
+    }
+    dst.push(END);
+}
+
+fn encode_u32(n: u32, dst: &mut Vec<u8>) {
+    dst.extend_from_slice(&n.to_le_bytes());
+}
+
+fn encode_str(s: &str, dst: &mut Vec<u8>) {
+    encode_u32(s.len() as u32, dst);
+    dst.extend_from_slice(s.as_bytes());
+}
+
+/// Decodes a stream written by the module. Literal handles refer to
+/// `literals`.
+pub fn decode(mut src: &[u8], literals: &[Literal]) -> Result<TokenStream> {
+    let stream = decode_stream(&mut src, literals)?;
+    if !src.is_empty() {
+        bail!("{} trailing bytes after token stream", src.len());
+    }
+    Ok(stream)
+}
+
+fn decode_stream(src: &mut &[u8], literals: &[Literal]) -> Result<TokenStream> {
+    let mut tokens = Vec::new();
+    loop {
+        let token = match decode_u8(src)? {
+            END => return Ok(tokens.into_iter().collect()),
+            GROUP => {
+                let delimiter = match decode_u8(src)? {
+                    0 => Delimiter::Parenthesis,
+                    1 => Delimiter::Brace,
+                    2 => Delimiter::Bracket,
+                    3 => Delimiter::None,
+                    other => bail!("invalid delimiter {}", other),
+                };
+                decode_u32(src)?;
+                TokenTree::Group(Group::new(delimiter, decode_stream(src, literals)?))
+            }
+            IDENT => {
+                decode_u32(src)?;
+                let text = decode_str(src)?;
+                TokenTree::Ident(match text.strip_prefix("r#") {
+                    Some(raw) => Ident::new_raw(raw, Span::call_site()),
+                    None => Ident::new(text, Span::call_site()),
+                })
+            }
+            PUNCT => {
+                let ch = decode_u32(src)?;
+                let ch = char::from_u32(ch).with_context(|| format!("invalid char {:#x}", ch))?;
+                let spacing = match decode_u8(src)? {
+                    0 => Spacing::Alone,
+                    1 => Spacing::Joint,
+                    other => bail!("invalid spacing {}", other),
+                };
+                decode_u32(src)?;
+                TokenTree::Punct(Punct::new(ch, spacing))
+            }
+            LITERAL => {
+                decode_u32(src)?;
+                TokenTree::Literal(match decode_u8(src)? {
+                    LITERAL_TEXT => {
+                        let text = decode_str(src)?;
+                        text.parse()
+                            .map_err(|_| anyhow::anyhow!("invalid literal `{}`", text))?
+                    }
+                    LITERAL_HANDLE => {
+                        let handle = decode_u32(src)?;
+                        literals
+                            .get(handle as usize)
+                            .with_context(|| format!("invalid literal handle {}", handle))?
+                            .clone()
+                    }
+                    other => bail!("invalid literal kind {}", other),
+                })
+            }
+            other => bail!("invalid token tag {}", other),
+        };
+        tokens.push(token);
+    }
+}
+
+fn decode_u8(src: &mut &[u8]) -> Result<u8> {
+    let (&byte, rest) = src
+        .split_first()
+        .context("unexpected end of token stream")?;
+    *src = rest;
+    Ok(byte)
+}
+
+fn decode_u32(src: &mut &[u8]) -> Result<u32> {
+    if src.len() < 4 {
+        bail!("unexpected end of token stream");
+    }
+    let (bytes, rest) = src.split_at(4);
+    *src = rest;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn decode_str<'a>(src: &mut &'a [u8]) -> Result<&'a str> {
+    let len = decode_u32(src)? as usize;
+    if src.len() < len {
+        bail!("unexpected end of token stream");
+    }
+    let (bytes, rest) = src.split_at(len);
+    *src = rest;
+    Ok(std::str::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let stream: TokenStream = "#[serde(rename = \"x\")] struct r#S<'a>(&'a [u8], i32);"
+            .parse()
+            .unwrap();
+        let mut literals = Vec::new();
+        let encoded = encode(stream.clone(), &mut literals);
+        assert_eq!(literals.len(), 1);
+        assert_eq!(
+            decode(&encoded, &literals).unwrap().to_string(),
+            stream.to_string()
+        );
+    }
+
+    #[test]
+    fn module_output() {
+        // `compile_error! { "oops" }` as written by the module.
+        let mut src = vec![IDENT];
+        encode_u32(CALL_SITE, &mut src);
+        encode_str("compile_error", &mut src);
+        src.push(PUNCT);
+        encode_u32('!' as u32, &mut src);
+        src.push(0);
+        encode_u32(CALL_SITE, &mut src);
+        src.extend_from_slice(&[GROUP, 1]);
+        encode_u32(CALL_SITE, &mut src);
+        src.push(LITERAL);
+        encode_u32(CALL_SITE, &mut src);
+        src.push(LITERAL_TEXT);
+        encode_str("\"oops\"", &mut src);
+        src.extend_from_slice(&[END, END]);
+
+        assert_eq!(
+            decode(&src, &[]).unwrap().to_string(),
+            "compile_error ! { \"oops\" }"
+        );
+        assert!(decode(&src[..src.len() - 1], &[]).is_err());
+    }
+}
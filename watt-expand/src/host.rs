@@ -0,0 +1,499 @@
+//! Runs an entry point of a watt module in wasmi, implementing the
+//! `watt-0.4` host functions on top of proc-macro2's fallback tokens.
+
+use crate::abi;
+use anyhow::{anyhow, Context, Result};
+use proc_macro2::{Literal, TokenStream};
+use std::cell::RefCell;
+use std::collections::hash_map::{Entry, HashMap};
+use std::fmt;
+use wasmi::core::TrapCode;
+use wasmi::{Caller, Config, Engine, Linker, Memory, Module, StackLimits, Store};
+
+/// The objects the module refers to by handle.
+#[derive(Default)]
+pub(crate) struct Host {
+    pub(crate) streams: Vec<TokenStream>,
+    pub(crate) literals: Vec<Literal>,
+    pub(crate) bytes: Vec<Vec<u8>>,
+    pub(crate) strings: Vec<String>,
+    pub(crate) panic: Option<String>,
+    pub(crate) error: Option<anyhow::Error>,
+}
+
+pub(crate) fn push<T>(handles: &mut Vec<T>, value: T) -> i32 {
+    handles.push(value);
+    handles.len() as i32 - 1
+}
+
+pub(crate) fn get<T>(handles: &[T], handle: i32) -> &T {
+    // The module only uses handles it was given, anything else is a bug in
+    // the runtime compiled into it.
+    &handles[handle as u32 as usize]
+}
+
+fn memory(caller: &Caller<'_, Host>) -> Memory {
+    caller
+        .get_export("memory")
+        .and_then(|export| export.into_memory())
+        .expect("module doesn't export its memory")
+}
+
+fn read(caller: &Caller<'_, Host>, ptr: i32, len: i32) -> Vec<u8> {
+    let mut buf = vec![0; len as u32 as usize];
+    memory(caller)
+        .read(caller, ptr as u32 as usize, &mut buf)
+        .expect("read out of bounds");
+    buf
+}
+
+fn write(caller: &mut Caller<'_, Host>, ptr: i32, data: &[u8]) {
+    memory(caller)
+        .write(caller, ptr as u32 as usize, data)
+        .expect("write out of bounds");
+}
+
+fn read_string(caller: &mut Caller<'_, Host>, ptr: i32, len: i32) -> Option<String> {
+    let bytes = read(caller, ptr, len);
+    match String::from_utf8(bytes) {
+        Ok(string) => Some(string),
+        Err(err) => {
+            caller.data_mut().error = Some(err.into());
+            None
+        }
+    }
+}
+
+fn linker(engine: &Engine) -> Result<Linker<Host>> {
+    const WATT: &str = "watt-0.4";
+    let mut linker = Linker::new(engine);
+    linker
+        .func_wrap(
+            WATT,
+            "string_new",
+            |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> i32 {
+                let string = read_string(&mut caller, ptr, len).unwrap_or_default();
+                push(&mut caller.data_mut().strings, string)
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "string_len",
+            |caller: Caller<'_, Host>, string: i32| -> i32 {
+                get(&caller.data().strings, string).len() as i32
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "string_read",
+            |mut caller: Caller<'_, Host>, string: i32, ptr: i32| {
+                let string = get(&caller.data().strings, string).clone();
+                write(&mut caller, ptr, string.as_bytes());
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "bytes_len",
+            |caller: Caller<'_, Host>, bytes: i32| -> i32 {
+                get(&caller.data().bytes, bytes).len() as i32
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "bytes_read",
+            |mut caller: Caller<'_, Host>, bytes: i32, ptr: i32| {
+                let bytes = get(&caller.data().bytes, bytes).clone();
+                write(&mut caller, ptr, &bytes);
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "print_panic",
+            |mut caller: Caller<'_, Host>, string: i32| {
+                let message = get(&caller.data().strings, string).clone();
+                caller.data_mut().panic = Some(message);
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "token_stream_serialize",
+            |mut caller: Caller<'_, Host>, stream: i32| -> i32 {
+                let host = caller.data_mut();
+                let stream = get(&host.streams, stream).clone();
+                let bytes = abi::encode(stream, &mut host.literals);
+                push(&mut host.bytes, bytes)
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "token_stream_deserialize",
+            |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> i32 {
+                let bytes = read(&caller, ptr, len);
+                let host = caller.data_mut();
+                let stream = match abi::decode(&bytes, &host.literals) {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        host.error = Some(err.context("module returned a malformed token stream"));
+                        TokenStream::new()
+                    }
+                };
+                push(&mut host.streams, stream)
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "token_stream_parse",
+            |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> i32 {
+                // Used for strings like `#[serde(bound = "...")]`. Like watt,
+                // a string that doesn't lex gets the invalid handle `u32::MAX`,
+                // which the module reports as a `LexError`.
+                let source = read(&caller, ptr, len);
+                match std::str::from_utf8(&source)
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                {
+                    Some(stream) => push(&mut caller.data_mut().streams, stream),
+                    None => -1,
+                }
+            },
+        )?
+        .func_wrap(
+            WATT,
+            "literal_to_string",
+            |mut caller: Caller<'_, Host>, literal: i32| -> i32 {
+                let host = caller.data_mut();
+                let string = get(&host.literals, literal).to_string();
+                push(&mut host.strings, string)
+            },
+        )?;
+    Ok(linker)
+}
+
+/// Calls `entry_point` of `module` with `args` (for attributes) and `input`,
+/// and returns the expansion.
+pub fn expand(
+    module: &[u8],
+    entry_point: &str,
+    args: Option<TokenStream>,
+    input: TokenStream,
+) -> Result<TokenStream> {
+    Expander::new(module)?.expand(entry_point, args, input)
+}
+
+/// Fuel for one expansion in the fuzz targets and regression tests, about four
+/// times the less than 150 million that deriving `Deserialize` for a struct
+/// with a hundred fields takes.
+pub const FUEL: u64 = 600_000_000;
+
+/// How many calls watt's interpreter nests before it traps, which panics the
+/// shim and fails the build.
+const MAX_RECURSION_DEPTH: usize = 300;
+
+thread_local! {
+    static POOL: RefCell<HashMap<(usize, usize), Expander>> = RefCell::new(HashMap::new());
+}
+
+/// Like [`expand`], but keeps `module` parsed and validated for later
+/// calls on the same thread, the way a shim would within one rustc process.
+/// Modules are told apart by address, so pass the same `include_bytes!`.
+pub fn expand_pooled(
+    module: &'static [u8],
+    entry_point: &str,
+    args: Option<TokenStream>,
+    input: TokenStream,
+) -> Result<TokenStream> {
+    POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        let expander = match pool.entry((module.as_ptr() as usize, module.len())) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Expander::new(module)?),
+        };
+        expander.expand(entry_point, args, input)
+    })
+}
+
+/// A parsed and validated module, ready to be instantiated.
+///
+/// Parsing and validating dominates the cost of an expansion, while
+/// instantiating only copies the data segments into a new linear memory. So
+/// every expansion gets a fresh instance, which resets the memory to its
+/// initial contents more thoroughly than restoring a snapshot in place: it
+/// also resets the stack pointer, which the modules don't export, and undoes
+/// memory growth, which the allocator does on first use.
+pub struct Expander {
+    module: Module,
+    linker: Linker<Host>,
+    fuel: Option<u64>,
+}
+
+impl Expander {
+    pub fn new(module: &[u8]) -> Result<Self> {
+        Self::build(module, None)
+    }
+
+    /// Like [`Expander::new`], but an expansion fails once it has executed
+    /// about `fuel` wasm instructions, instead of possibly running forever.
+    pub fn with_fuel(module: &[u8], fuel: u64) -> Result<Self> {
+        Self::build(module, Some(fuel))
+    }
+
+    fn build(module: &[u8], fuel: Option<u64>) -> Result<Self> {
+        let mut config = Config::default();
+        config.consume_fuel(fuel.is_some());
+        // Input nested deeper than watt can expand should fail here too.
+        config.set_stack_limits(StackLimits {
+            maximum_recursion_depth: MAX_RECURSION_DEPTH,
+            ..StackLimits::default()
+        });
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, module).context("failed to load wasm module")?;
+        let linker = linker(&engine)?;
+        Ok(Expander {
+            module,
+            linker,
+            fuel,
+        })
+    }
+
+    pub fn expand(
+        &self,
+        entry_point: &str,
+        args: Option<TokenStream>,
+        input: TokenStream,
+    ) -> Result<TokenStream> {
+        let mut store = Store::new(self.module.engine(), Host::default());
+        if let Some(fuel) = self.fuel {
+            store.set_fuel(fuel).map_err(|err| anyhow!("{}", err))?;
+        }
+        let instance = self
+            .linker
+            .instantiate(&mut store, &self.module)
+            .and_then(|instance| instance.start(&mut store))
+            .context("failed to instantiate wasm module")?;
+
+        let raw_to_token_stream =
+            instance.get_typed_func::<i32, i32>(&store, "raw_to_token_stream")?;
+        let token_stream_into_raw =
+            instance.get_typed_func::<i32, i32>(&store, "token_stream_into_raw")?;
+
+        let mut raw_args = Vec::new();
+        for stream in args.into_iter().chain(Some(input)) {
+            let handle = push(&mut store.data_mut().streams, stream);
+            raw_args.push(raw_to_token_stream.call(&mut store, handle)?);
+        }
+
+        let output = match raw_args[..] {
+            [input] => instance
+                .get_typed_func::<i32, i32>(&store, entry_point)
+                .map_err(|_| no_entry_point(entry_point, 1))?
+                .call(&mut store, input),
+            [args, input] => instance
+                .get_typed_func::<(i32, i32), i32>(&store, entry_point)
+                .map_err(|_| no_entry_point(entry_point, 2))?
+                .call(&mut store, (args, input)),
+            _ => unreachable!(),
+        };
+        let output = output.and_then(|output| token_stream_into_raw.call(&mut store, output));
+
+        let host = store.data_mut();
+        if let Some(err) = host.error.take() {
+            return Err(err);
+        }
+        match output {
+            Ok(output) => Ok(get(&host.streams, output).clone()),
+            Err(trap) => match host.panic.take() {
+                Some(message) => Err(Panic {
+                    entry_point: entry_point.to_owned(),
+                    message,
+                }
+                .into()),
+                None if trap.as_trap_code() == Some(TrapCode::StackOverflow) => {
+                    Err(StackOverflow {
+                        entry_point: entry_point.to_owned(),
+                    }
+                    .into())
+                }
+                None => Err(anyhow!(trap).context(format!("{} trapped", entry_point))),
+            },
+        }
+    }
+}
+
+/// The error of an expansion that panicked, which is how many macros report
+/// invalid input. Anything else going wrong inside the module is a bug.
+#[derive(Debug)]
+pub struct Panic {
+    pub entry_point: String,
+    /// Like `panicked at 'message', src/lib.rs:1:2`.
+    pub message: String,
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.entry_point, self.message)
+    }
+}
+
+impl std::error::Error for Panic {}
+
+/// The error of an expansion that nested more calls than watt allows. Under
+/// watt the build fails the same way.
+#[derive(Debug)]
+pub struct StackOverflow {
+    pub entry_point: String,
+}
+
+impl fmt::Display for StackOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} nested more than {} calls",
+            self.entry_point, MAX_RECURSION_DEPTH,
+        )
+    }
+}
+
+impl std::error::Error for StackOverflow {}
+
+pub(crate) fn no_entry_point(entry_point: &str, params: usize) -> anyhow::Error {
+    anyhow!(
+        "module has no entry point `{}` taking {} token stream{}",
+        entry_point,
+        params,
+        if params == 1 { "" } else { "s" },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    fn module(krate: &str) -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join(krate)
+            .join("src")
+            .join(format!("{}.wasm", krate));
+        fs::read(path).unwrap()
+    }
+
+    #[test]
+    fn derive() {
+        let input = "#[serde(rename_all = \"camelCase\")] struct S { a_b: u8 }";
+        let output = expand(
+            &module("serde_derive"),
+            "derive_serialize",
+            None,
+            input.parse().unwrap(),
+        )
+        .unwrap()
+        .to_string();
+        assert!(
+            output.contains("impl _serde :: Serialize for S"),
+            "{}",
+            output
+        );
+        assert!(output.contains("\"aB\""), "{}", output);
+    }
+
+    #[test]
+    fn attribute() {
+        let wasm = module("tokio-macros");
+        let output = expand(
+            &wasm,
+            "main",
+            Some("".parse().unwrap()),
+            "async fn main() {}".parse().unwrap(),
+        )
+        .unwrap();
+        assert!(output.to_string().contains("block_on"), "{}", output);
+
+        let error = expand(
+            &wasm,
+            "main",
+            Some("".parse().unwrap()),
+            "fn main() {}".parse().unwrap(),
+        )
+        .unwrap();
+        assert!(
+            error.to_string().starts_with("compile_error !"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn missing_entry_point() {
+        let error = expand(&module("tokio-macros"), "main", None, "".parse().unwrap()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "module has no entry point `main` taking 1 token stream"
+        );
+    }
+
+    // Expanding with a module that already ran other expansions has to give
+    // the same result as a module loaded just for this one.
+    fn assert_no_leak(expander: &Expander, wasm: &[u8], entry_point: &str, input: &str) {
+        let input: TokenStream = input.parse().unwrap();
+        let fresh = expand(wasm, entry_point, None, input.clone()).unwrap();
+        let reused = expander.expand(entry_point, None, input).unwrap();
+        assert_eq!(reused.to_string(), fresh.to_string());
+    }
+
+    #[test]
+    fn reuse() {
+        let wasm = module("serde_derive");
+        let expander = Expander::new(&wasm).unwrap();
+        let fields: String = (0..300).map(|i| format!("f{}: u8, ", i)).collect();
+        let big = format!("struct Big {{ {} }}", fields);
+        let inputs = [
+            ("derive_serialize", "struct A { x: u8 }"),
+            (
+                "derive_deserialize",
+                "#[serde(tag = \"t\")] enum B { C { y: String } }",
+            ),
+            ("derive_serialize", "union U { a: u8 }"),
+            ("derive_deserialize", &big),
+            ("derive_serialize", "struct A { x: u8 }"),
+            (
+                "derive_deserialize",
+                "struct A<'a> { #[serde(borrow)] x: &'a str }",
+            ),
+        ];
+        for (entry_point, input) in &inputs {
+            assert_no_leak(&expander, &wasm, entry_point, input);
+        }
+    }
+
+    #[test]
+    fn after_error() {
+        let wasm = module("tokio-macros");
+        let expander = Expander::new(&wasm).unwrap();
+        expander
+            .expand("main", None, "".parse().unwrap())
+            .unwrap_err();
+        let output = expander
+            .expand(
+                "main",
+                Some("".parse().unwrap()),
+                "async fn main() {}".parse().unwrap(),
+            )
+            .unwrap();
+        assert!(output.to_string().contains("block_on"), "{}", output);
+    }
+
+    #[test]
+    fn pooled() {
+        let wasm: &'static [u8] = Box::leak(module("serde_derive").into_boxed_slice());
+        for _ in 0..2 {
+            let output =
+                expand_pooled(wasm, "derive_serialize", None, "struct A;".parse().unwrap())
+                    .unwrap();
+            assert!(output.to_string().contains("Serialize for A"), "{}", output);
+        }
+        POOL.with(|pool| assert_eq!(pool.borrow().len(), 1));
+    }
+}
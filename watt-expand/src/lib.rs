@@ -0,0 +1,74 @@
+//! Runs the entry points of the wasm modules in this repository outside of
+//! rustc, on proc-macro2's fallback tokens.
+//!
+//! Used by the `watt-expand` binary, the benchmarks and the fuzz targets.
+
+pub mod abi;
+pub mod host;
+pub mod watt;
+
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The module of `krate` at `version` in this repository, which keeps
+/// further versions of a crate in directories like `tokio-macros-1`.
+pub fn repo_module(krate: &str, version: &str) -> Result<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let mut hosted = Vec::new();
+    for entry in fs::read_dir(root)? {
+        let dir = entry?.path();
+        let manifest = match fs::read_to_string(dir.join("Cargo.toml")) {
+            Ok(manifest) if dir.join("Cargo.watt.lock").is_file() => manifest,
+            _ => continue,
+        };
+        let field = |name: &str| {
+            manifest
+                .lines()
+                .find_map(|line| {
+                    line.strip_prefix(name)?
+                        .strip_prefix(" = \"")?
+                        .strip_suffix('"')
+                })
+                .map(str::to_owned)
+        };
+        if field("name").as_deref() != Some(krate) {
+            continue;
+        }
+        let current = field("version").unwrap_or_else(|| "an unknown version".to_owned());
+        if current == version {
+            return Ok(dir.join("src").join(format!("{}.wasm", krate)));
+        }
+        hosted.push(current);
+    }
+    if hosted.is_empty() {
+        bail!("{} is not in this repository", krate);
+    }
+    hosted.sort();
+    bail!(
+        "the dump was recorded with {} {}, but this repository has {}",
+        krate,
+        version,
+        hosted.join(" and "),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_version() {
+        let module = repo_module("serde_derive", "1.0.116").unwrap();
+        assert!(module.ends_with("serde_derive/src/serde_derive.wasm"));
+        assert!(module.is_file());
+
+        let error = repo_module("serde_derive", "1.0.0").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the dump was recorded with serde_derive 1.0.0, but this repository has 1.0.116",
+        );
+        let error = repo_module("serde", "1.0.116").unwrap_err();
+        assert_eq!(error.to_string(), "serde is not in this repository");
+    }
+}
@@ -0,0 +1,164 @@
+//! Replays a macro invocation against one of the `.wasm` modules in this
+//! repository without going through rustc.
+
+use anyhow::{anyhow, bail, Context, Result};
+use proc_macro2::TokenStream;
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use watt_contrib_dump::{rustfmt, Dump, Kind};
+use watt_expand::{host, repo_module};
+
+const USAGE: &str = "\
+usage: watt-expand [--args TOKENS] MODULE ENTRY_POINT [INPUT]
+       watt-expand [--module MODULE] --dump DUMP
+
+Runs ENTRY_POINT of the wasm MODULE on the Rust source in the file INPUT, or
+on stdin, and prints the expansion. Passing --args calls the entry point as an
+attribute with TOKENS as its arguments.
+
+With --dump, replays an invocation recorded with WATT_CONTRIB_DUMP. The module
+defaults to the one of the recorded crate in this repository.
+
+options:
+    --args TOKENS      attribute arguments
+    --dump DUMP        file written by WATT_CONTRIB_DUMP
+    --module MODULE    .wasm file to load
+    --raw              don't format the expansion with rustfmt";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {:#}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let mut args = None;
+    let mut dump = None;
+    let mut module = None;
+    let mut raw = false;
+    let mut positional = Vec::new();
+
+    let mut argv = env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = |name: &str| {
+            argv.next()
+                .with_context(|| format!("{} needs a value", name))
+        };
+        match arg.as_str() {
+            "--args" => args = Some(value("--args")?),
+            "--dump" => dump = Some(PathBuf::from(value("--dump")?)),
+            "--module" => module = Some(PathBuf::from(value("--module")?)),
+            "--raw" => raw = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                bail!("unknown option `{}`\n\n{}", flag, USAGE)
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let invocation = match dump {
+        Some(dump) => {
+            if args.is_some() || !positional.is_empty() {
+                bail!("--dump doesn't take other arguments\n\n{}", USAGE);
+            }
+            replay(&dump, module)?
+        }
+        None => {
+            let mut positional = positional.into_iter();
+            let (module, entry_point) = match (module, positional.next(), positional.next()) {
+                (None, Some(module), Some(entry_point)) => (PathBuf::from(module), entry_point),
+                _ => bail!("{}", USAGE),
+            };
+            let input = match positional.next() {
+                Some(path) if path != "-" => {
+                    fs::read_to_string(&path).with_context(|| format!("failed to read {}", path))?
+                }
+                _ => {
+                    let mut input = String::new();
+                    io::stdin().read_to_string(&mut input)?;
+                    input
+                }
+            };
+            if positional.next().is_some() {
+                bail!("{}", USAGE);
+            }
+            Invocation {
+                module,
+                entry_point,
+                args,
+                input,
+                recorded_output: None,
+            }
+        }
+    };
+
+    let wasm = fs::read(&invocation.module)
+        .with_context(|| format!("failed to read {}", invocation.module.display()))?;
+    let args = invocation.args.as_deref().map(parse).transpose()?;
+    let input = parse(&invocation.input)?;
+    let output = host::expand(&wasm, &invocation.entry_point, args, input)?;
+
+    if let Some(recorded) = &invocation.recorded_output {
+        if parse(recorded)?.to_string() != output.to_string() {
+            eprintln!("note: the expansion differs from the output recorded in the dump");
+        }
+    }
+    let output = output.to_string();
+    let output = if raw {
+        output
+    } else {
+        rustfmt(&output).unwrap_or(output)
+    };
+    let mut stdout = io::stdout();
+    writeln!(stdout, "{}", output.trim_end())?;
+    Ok(())
+}
+
+struct Invocation {
+    module: PathBuf,
+    entry_point: String,
+    args: Option<String>,
+    input: String,
+    recorded_output: Option<String>,
+}
+
+fn replay(path: &Path, module: Option<PathBuf>) -> Result<Invocation> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let dump =
+        Dump::parse(&content).with_context(|| format!("failed to parse {}", path.display()))?;
+    match (&dump.args, dump.kind) {
+        (None, Kind::Attribute) => {
+            bail!("{} is missing arguments for an attribute", path.display())
+        }
+        (Some(_), kind) if kind != Kind::Attribute => {
+            bail!("{} has arguments for a {}", path.display(), kind.as_str())
+        }
+        _ => {}
+    }
+    let module = match module {
+        Some(module) => module,
+        None => repo_module(&dump.krate, &dump.version)
+            .map_err(|err| anyhow!("{}, pass --module", err))?,
+    };
+    Ok(Invocation {
+        module,
+        entry_point: dump.entry_point,
+        args: dump.args,
+        input: dump.input,
+        recorded_output: dump.output,
+    })
+}
+
+fn parse(source: &str) -> Result<TokenStream> {
+    source
+        .parse()
+        .map_err(|err| anyhow!("failed to tokenize input: {}", err))
+}
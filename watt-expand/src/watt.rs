@@ -0,0 +1,267 @@
+//! Runs an entry point on watt 0.4.6's interpreter, which runs the modules
+//! inside rustc, from where `watt-contrib-runtime` vendors it in
+//! `watt/runtime`.
+//!
+//! [`host`](crate::host) runs them in wasmi instead, which is much faster and
+//! can stop an expansion that doesn't terminate, but isn't what a build runs.
+//! The fuzz targets and the regression tests run every input on both and
+//! require the same result.
+//!
+//! The host functions behave like watt's, except that where watt panics this
+//! returns an error. Like in [`host`](crate::host), every expansion gets a
+//! fresh instance, while watt keeps one per module for the whole thread.
+
+#[allow(warnings, clippy::all)]
+#[path = "../../watt-contrib-runtime/watt/runtime/src/lib.rs"]
+mod runtime;
+
+use crate::abi;
+use crate::host::{get, no_entry_point, push, Host, Panic, StackOverflow};
+use anyhow::{anyhow, bail, Context, Result};
+use proc_macro2::TokenStream;
+use runtime::func::WasmArg;
+use runtime::{Error, Extern, ExternVal, FuncAddr, HostFunc, Interpreter, Store, Value};
+use std::cell::RefCell;
+use std::io::Cursor;
+use std::rc::Rc;
+
+/// Calls `entry_point` of `module` with `args` (for attributes) and `input`,
+/// and returns the expansion.
+pub fn expand(
+    module: &[u8],
+    entry_point: &str,
+    args: Option<TokenStream>,
+    input: TokenStream,
+) -> Result<TokenStream> {
+    let module = runtime::decode_module(Cursor::new(module))
+        .map_err(|err| anyhow!("failed to load wasm module: {:?}", err))?;
+    let host = Rc::new(RefCell::new(Host::default()));
+    let mut store = runtime::init_store();
+    let mut extern_vals = Vec::new();
+    for (module, name, ty) in runtime::module_imports(&module) {
+        let func = match ty {
+            Extern::Func(func) if module == "watt-0.4" => func,
+            _ => bail!("module imports unknown {}::{}", module, name),
+        };
+        let host_func = host_func(name, &host)?;
+        extern_vals.push(ExternVal::Func(runtime::alloc_func(
+            &mut store, &func, host_func,
+        )));
+    }
+    let instance = runtime::instantiate_module(&mut store, module, &extern_vals)
+        .map_err(|err| anyhow!("failed to instantiate wasm module: {:?}", err))?;
+
+    let export = |name: &str| match runtime::get_export(&instance, name) {
+        Ok(ExternVal::Func(func)) => Some(func),
+        _ => None,
+    };
+    let raw_to_token_stream =
+        export("raw_to_token_stream").context("module doesn't export raw_to_token_stream")?;
+    let token_stream_into_raw =
+        export("token_stream_into_raw").context("module doesn't export token_stream_into_raw")?;
+
+    let params = if args.is_some() { 2 } else { 1 };
+    let main = export(entry_point).ok_or_else(|| no_entry_point(entry_point, params))?;
+
+    let output = (|| {
+        let mut raw_args = Vec::new();
+        for stream in args.into_iter().chain(Some(input)) {
+            let handle = push(&mut host.borrow_mut().streams, stream) as u32;
+            raw_args.push(call(&mut store, raw_to_token_stream, vec![handle])?);
+        }
+        let output = call(&mut store, main, raw_args)?;
+        call(&mut store, token_stream_into_raw, vec![output])
+    })();
+
+    let mut host = host.borrow_mut();
+    if let Some(err) = host.error.take() {
+        return Err(err);
+    }
+    match output {
+        Ok(output) => Ok(get(&host.streams, output as i32).clone()),
+        Err(Error::NotEnoughArgument) | Err(Error::ArgumentTypeMismatch) => {
+            Err(no_entry_point(entry_point, params))
+        }
+        Err(err) => match host.panic.take() {
+            Some(message) => Err(Panic {
+                entry_point: entry_point.to_owned(),
+                message,
+            }
+            .into()),
+            None if err == Error::StackOverflow => Err(StackOverflow {
+                entry_point: entry_point.to_owned(),
+            }
+            .into()),
+            None => Err(anyhow!("{} trapped: {:?}", entry_point, err)),
+        },
+    }
+}
+
+fn call(store: &mut Store, func: FuncAddr, args: Vec<u32>) -> Result<u32, Error> {
+    let args = args.into_iter().map(Value::I32).collect();
+    match runtime::invoke_func(store, func, args)?[..] {
+        [Value::I32(ret)] => Ok(ret),
+        _ => Err(Error::ArgumentTypeMismatch),
+    }
+}
+
+/// A host function taking `u32` arguments and the module's memory, returning
+/// a `u32` if it returns anything.
+type Func = fn(&mut Host, &mut [u8], &[u32]) -> Option<u32>;
+
+fn host_func(name: &str, host: &Rc<RefCell<Host>>) -> Result<HostFunc> {
+    let (params, func): (usize, Func) = match name {
+        "string_new" => (2, |host, memory, args| {
+            let bytes = memory[args[0] as usize..][..args[1] as usize].to_vec();
+            let string = String::from_utf8(bytes).unwrap_or_else(|err| {
+                host.error = Some(err.into());
+                String::new()
+            });
+            Some(push(&mut host.strings, string) as u32)
+        }),
+        "string_len" => (1, |host, _, args| {
+            Some(get(&host.strings, args[0] as i32).len() as u32)
+        }),
+        "string_read" => (2, |host, memory, args| {
+            let string = get(&host.strings, args[0] as i32);
+            memory[args[1] as usize..][..string.len()].copy_from_slice(string.as_bytes());
+            None
+        }),
+        "bytes_len" => (1, |host, _, args| {
+            Some(get(&host.bytes, args[0] as i32).len() as u32)
+        }),
+        "bytes_read" => (2, |host, memory, args| {
+            let bytes = get(&host.bytes, args[0] as i32);
+            memory[args[1] as usize..][..bytes.len()].copy_from_slice(bytes);
+            None
+        }),
+        "print_panic" => (1, |host, _, args| {
+            host.panic = Some(get(&host.strings, args[0] as i32).clone());
+            None
+        }),
+        "token_stream_serialize" => (1, |host, _, args| {
+            let stream = get(&host.streams, args[0] as i32).clone();
+            let bytes = abi::encode(stream, &mut host.literals);
+            Some(push(&mut host.bytes, bytes) as u32)
+        }),
+        "token_stream_deserialize" => (2, |host, memory, args| {
+            let bytes = &memory[args[0] as usize..][..args[1] as usize];
+            let stream = abi::decode(bytes, &host.literals).unwrap_or_else(|err| {
+                host.error = Some(err.context("module returned a malformed token stream"));
+                TokenStream::new()
+            });
+            Some(push(&mut host.streams, stream) as u32)
+        }),
+        "token_stream_parse" => (2, |host, memory, args| {
+            let bytes = &memory[args[0] as usize..][..args[1] as usize];
+            let stream = std::str::from_utf8(bytes).ok().and_then(|s| s.parse().ok());
+            Some(match stream {
+                Some(stream) => push(&mut host.streams, stream) as u32,
+                None => u32::MAX,
+            })
+        }),
+        "literal_to_string" => (1, |host, _, args| {
+            let string = get(&host.literals, args[0] as i32).to_string();
+            Some(push(&mut host.strings, string) as u32)
+        }),
+        _ => bail!("module imports unknown watt-0.4::{}", name),
+    };
+    let host = host.clone();
+    Ok(Box::new(move |interp: &mut Interpreter| {
+        let mut args = [0; 2];
+        for arg in args[..params].iter_mut().rev() {
+            *arg = u32::pop(interp);
+        }
+        let mut host = host.borrow_mut();
+        let ret = func(&mut host, interp.get_memory_mut(), &args[..params]);
+        // Watt panics here, which stops the expansion the same way.
+        if host.panic.is_some() || host.error.is_some() {
+            return Some("host function failed".to_owned());
+        }
+        if let Some(ret) = ret {
+            interp.push(Value::I32(ret));
+        }
+        None
+    }))
+}
+
+/// Checks that `wasmi`, the result of an expansion in [`host`](crate::host),
+/// and `watt`, the result of the same expansion here, agree: both expanded to
+/// the same tokens, panicked with the same message or overflowed the stack.
+pub fn compare(wasmi: &Result<TokenStream>, watt: &Result<TokenStream>) -> Result<()> {
+    let agree = match (wasmi, watt) {
+        (Ok(wasmi), Ok(watt)) => wasmi.to_string() == watt.to_string(),
+        (Err(wasmi), Err(watt)) => match (wasmi.downcast_ref(), watt.downcast_ref()) {
+            (Some(Panic { message: wasmi, .. }), Some(Panic { message: watt, .. })) => {
+                wasmi == watt
+            }
+            _ => wasmi.is::<StackOverflow>() && watt.is::<StackOverflow>(),
+        },
+        _ => false,
+    };
+    if !agree {
+        bail!(
+            "wasmi and watt disagree\n\nwasmi: {}\n\nwatt: {}",
+            describe(wasmi),
+            describe(watt),
+        );
+    }
+    Ok(())
+}
+
+fn describe(result: &Result<TokenStream>) -> String {
+    match result {
+        Ok(expansion) => expansion.to_string(),
+        Err(err) => format!("{:#}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host;
+    use std::fs;
+    use std::path::Path;
+
+    fn module(krate: &str) -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .unwrap()
+            .join(krate)
+            .join("src")
+            .join(format!("{}.wasm", krate));
+        fs::read(path).unwrap()
+    }
+
+    fn assert_agree(krate: &str, entry_point: &str, args: Option<&str>, input: &str) {
+        let wasm = module(krate);
+        let args: Option<TokenStream> = args.map(|args| args.parse().unwrap());
+        let input: TokenStream = input.parse().unwrap();
+        let wasmi = host::expand(&wasm, entry_point, args.clone(), input.clone());
+        let watt = expand(&wasm, entry_point, args, input);
+        compare(&wasmi, &watt).unwrap();
+    }
+
+    #[test]
+    fn agrees_with_wasmi() {
+        assert_agree(
+            "serde_derive",
+            "derive_deserialize",
+            None,
+            "#[serde(tag = \"t\")] enum B { C { y: String } }",
+        );
+        assert_agree("tokio-macros", "main", Some(""), "async fn main() {}");
+        assert_agree("futures-macro", "join_internal", None, "a, b.c()");
+        // `derive_new` panics on unions.
+        assert_agree("derive-new", "derive", None, "union U { a: u8 }");
+    }
+
+    #[test]
+    fn missing_entry_point() {
+        let error = expand(&module("tokio-macros"), "main", None, "".parse().unwrap()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "module has no entry point `main` taking 1 token stream"
+        );
+    }
+}
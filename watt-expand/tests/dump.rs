@@ -0,0 +1,132 @@
+//! Records a dump by compiling `tests/fixture` with `WATT_CONTRIB_DUMP` set
+//! and replays it with `watt-expand --dump`, which has to find the module of
+//! the recorded crate and the same output as rustc did, token for token.
+
+use proc_macro2::TokenStream;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use watt_contrib_dump::{Dump, Kind, ENV};
+
+const DIFFERS: &str = "note: the expansion differs from the output recorded in the dump";
+
+fn record(dir: &Path) -> PathBuf {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixture");
+    let status = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .arg("build")
+        .arg("--manifest-path")
+        .arg(fixture.join("Cargo.toml"))
+        // A fresh target directory, so that rustc expands the derive even if
+        // the fixture was built before.
+        .arg("--target-dir")
+        .arg(dir.join("target"))
+        .env(ENV, dir.join("dumps"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+    dir.join("dumps")
+        .join("serde_derive")
+        .join("derive_serialize")
+        .join("watt_expand_fixture-src_main.rs_4_10.0.rs")
+}
+
+fn replay(dump: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_watt-expand"))
+        .arg("--raw")
+        .arg("--dump")
+        .arg(dump)
+        .output()
+        .unwrap()
+}
+
+fn tokens(source: &str) -> String {
+    source.parse::<TokenStream>().unwrap().to_string()
+}
+
+#[test]
+fn replays_recorded_dump() {
+    let dir = env::temp_dir().join(format!("watt-expand-dump-{}", std::process::id()));
+    let path = record(&dir);
+    // Locations are only recorded since Rust 1.88.
+    let path = if path.exists() {
+        path
+    } else {
+        path.with_file_name("watt_expand_fixture-unknown.0.rs")
+    };
+    let dump = Dump::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(dump.krate, "serde_derive");
+    assert!(dump.input.contains("struct S"));
+
+    let output = replay(&path);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+    assert_eq!(
+        tokens(&String::from_utf8(output.stdout).unwrap()),
+        tokens(dump.output.as_deref().unwrap()),
+    );
+
+    // Replaying still compares against the recorded output.
+    let changed = Dump {
+        output: dump
+            .output
+            .as_ref()
+            .map(|output| output.replacen("S", "T", 1)),
+        ..dump
+    };
+    let changed_path = dir.join("changed.rs");
+    fs::write(&changed_path, changed.render()).unwrap();
+    let output = replay(&changed_path);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap().trim_end(),
+        DIFFERS
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn rejects_mismatched_args() {
+    let dir = env::temp_dir().join(format!("watt-expand-args-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let dump = |kind, args: Option<&str>| Dump {
+        krate: "serde_derive".to_owned(),
+        version: "1.0.116".to_owned(),
+        entry_point: "derive_serialize".to_owned(),
+        kind,
+        caller: None,
+        location: None,
+        args: args.map(str::to_owned),
+        input: "struct S;".to_owned(),
+        output: None,
+        formatted_output: None,
+    };
+    let error = |dump: Dump| {
+        let path = dir.join("dump.rs");
+        fs::write(&path, dump.render()).unwrap();
+        let output = replay(&path);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        stderr
+            .trim_end()
+            .replace(&path.display().to_string(), "DUMP")
+    };
+
+    assert_eq!(
+        error(dump(Kind::Attribute, None)),
+        "error: DUMP is missing arguments for an attribute"
+    );
+    assert_eq!(
+        error(dump(Kind::Derive, Some("level = \"debug\""))),
+        "error: DUMP has arguments for a derive"
+    );
+    assert_eq!(
+        error(dump(Kind::Function, Some("x"))),
+        "error: DUMP has arguments for a function"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,9 @@
+//! Compiled by `tests/dump.rs` with `WATT_CONTRIB_DUMP` set, to record a dump
+//! of a real expansion.
+
+#[derive(serde_derive::Serialize)]
+pub struct S {
+    a: u8,
+}
+
+fn main() {}
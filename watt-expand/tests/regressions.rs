@@ -0,0 +1,73 @@
+//! Replays the inputs in `tests/regressions`, which once made a module or the
+//! interpreter fail. They are in the format of `WATT_CONTRIB_DUMP`, which is
+//! also what `cargo fuzz fmt` prints for a crash of the fuzz targets.
+//!
+//! Each input must expand or be rejected by the macro, except the ones in
+//! `STACK_OVERFLOWS`, which nest deeper than watt's interpreter allows and
+//! must keep failing like they do under watt. Either way wasmi and watt's
+//! interpreter must agree.
+
+use std::fs;
+use std::path::Path;
+use std::thread;
+use watt_contrib_dump::Dump;
+use watt_expand::host::{self, Expander, Panic, StackOverflow};
+use watt_expand::{repo_module, watt};
+
+const STACK_OVERFLOWS: &[&str] = &["serde_derive-nested-types.rs"];
+
+/// The stack of the thread rustc expands macros on, which watt's interpreter
+/// recurses on up to its call depth limit.
+const RUSTC_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+#[test]
+fn regressions() {
+    thread::Builder::new()
+        .stack_size(RUSTC_STACK_SIZE)
+        .spawn(replay)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+fn replay() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut paths: Vec<_> = fs::read_dir(manifest_dir.join("tests").join("regressions"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    for path in paths {
+        let dump = Dump::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        let module = repo_module(&dump.krate, &dump.version)
+            .unwrap_or_else(|err| panic!("{}: {:#}", path.display(), err));
+        let module = fs::read(module).unwrap();
+        let expander = Expander::with_fuel(&module, host::FUEL).unwrap();
+        let args = dump.args.as_deref().map(|args| args.parse().unwrap());
+        let input = dump.input.parse().unwrap();
+        let result = expander.expand(&dump.entry_point, args.clone(), input);
+        let on_watt = watt::expand(
+            &module,
+            &dump.entry_point,
+            args,
+            dump.input.parse().unwrap(),
+        );
+        if let Err(err) = watt::compare(&result, &on_watt) {
+            panic!("{}: {:#}", path.display(), err);
+        }
+        let name = path.file_name().unwrap().to_str().unwrap();
+        if STACK_OVERFLOWS.contains(&name) {
+            match result {
+                Err(err) if err.is::<StackOverflow>() => {}
+                _ => panic!("{}: expected a stack overflow", path.display()),
+            }
+            continue;
+        }
+        match result {
+            Err(err) if !err.is::<Panic>() => panic!("{}: {:#}", path.display(), err),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,10 @@
+// watt-contrib expansion dump
+// crate: serde_derive 1.0.116
+// entry point: derive_serialize
+// kind: derive
+
+// input
+#[serde(bound = "T: \"")]
+struct S<T> {
+    a: T,
+}
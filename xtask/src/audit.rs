@@ -0,0 +1,296 @@
+//! What the `.wasm` modules import from and export to the compiler they run
+//! in.
+//!
+//! The modules are meant to talk to rustc only through watt's host functions.
+//! Every import of a module has to be listed under its crate in
+//! `xtask/allowed-imports.txt`, so a module that starts importing anything
+//! else, like WASI or `env` functions pulled in by a new dependency, is caught
+//! by `cargo xtask audit` and by `regenerate`. Its exports have to be exactly
+//! the entry points its `src/lib.rs` calls and the functions watt itself
+//! calls.
+
+use anyhow::{Context, Result};
+use std::fmt::Write;
+use wasmparser::{CompositeInnerType, ExternalKind, FuncType, Parser, Payload, TypeRef};
+
+pub const ALLOWED_IMPORTS: &str = include_str!("../allowed-imports.txt");
+
+/// What every module exports for watt besides its entry points: its memory,
+/// the bounds of its heap and the conversions of token streams to and from
+/// the handles watt passes around.
+pub const RUNTIME_EXPORTS: &[&str] = &[
+    "memory memory",
+    "__heap_base global",
+    "__data_end global",
+    "raw_to_token_stream (i32) -> i32",
+    "token_stream_into_raw (i32) -> i32",
+];
+
+/// The imports listed under `[<dir>]` in `allowed`, which has the format of
+/// `xtask/allowed-imports.txt`, or `None` if the crate isn't listed.
+pub fn allowed_imports<'a>(allowed: &'a str, dir: &str) -> Option<Vec<&'a str>> {
+    let header = format!("[{}]", dir);
+    let mut lines = allowed
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .skip_while(|line| *line != header);
+    lines.next()?;
+    Some(lines.take_while(|line| !line.starts_with('[')).collect())
+}
+
+/// The exports a module has to have for the entry points its `src/lib.rs`
+/// calls, in the format of [`Surface::exports`].
+pub fn expected_exports(lib: &str) -> Vec<String> {
+    let mut exports: Vec<String> = RUNTIME_EXPORTS.iter().map(|&e| e.to_owned()).collect();
+    for (name, params) in watt_contrib_build::entry_points(lib) {
+        let params = vec!["i32"; params].join(", ");
+        exports.push(format!("{} ({}) -> i32", name, params));
+    }
+    exports
+}
+
+/// The imports and exports of a module, one line each, like
+/// `watt-0.4 string_new (i32, i32) -> i32` or `memory memory`.
+pub struct Surface {
+    pub imports: Vec<String>,
+    pub exports: Vec<String>,
+}
+
+impl Surface {
+    pub fn parse(module: &[u8]) -> Result<Self> {
+        let mut types = Vec::new();
+        // Type of every function, imports first.
+        let mut functions = Vec::new();
+        let mut imports = Vec::new();
+        let mut exports = Vec::new();
+
+        for payload in Parser::new(0).parse_all(module) {
+            match payload? {
+                Payload::TypeSection(reader) => {
+                    for group in reader {
+                        for ty in group?.into_types() {
+                            types.push(match ty.composite_type.inner {
+                                CompositeInnerType::Func(func) => Some(func),
+                                _ => None,
+                            });
+                        }
+                    }
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import?;
+                        let ty = match import.ty {
+                            TypeRef::Func(ty) => {
+                                functions.push(ty);
+                                signature(&types, ty)?
+                            }
+                            TypeRef::Table(_) => "table".to_owned(),
+                            TypeRef::Memory(_) => "memory".to_owned(),
+                            TypeRef::Global(_) => "global".to_owned(),
+                            TypeRef::Tag(_) => "tag".to_owned(),
+                            TypeRef::FuncExact(ty) => {
+                                functions.push(ty);
+                                signature(&types, ty)?
+                            }
+                        };
+                        imports.push(format!("{} {} {}", import.module, import.name, ty));
+                    }
+                }
+                Payload::FunctionSection(reader) => {
+                    for ty in reader {
+                        functions.push(ty?);
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export?;
+                        let ty = match export.kind {
+                            ExternalKind::Func | ExternalKind::FuncExact => {
+                                let ty =
+                                    functions.get(export.index as usize).with_context(|| {
+                                        format!("unknown function {}", export.index)
+                                    })?;
+                                signature(&types, *ty)?
+                            }
+                            ExternalKind::Table => "table".to_owned(),
+                            ExternalKind::Memory => "memory".to_owned(),
+                            ExternalKind::Global => "global".to_owned(),
+                            ExternalKind::Tag => "tag".to_owned(),
+                        };
+                        exports.push(format!("{} {}", export.name, ty));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Surface { imports, exports })
+    }
+
+    /// The imports that aren't in `allowed`.
+    pub fn disallowed_imports<'a>(&'a self, allowed: &[&str]) -> Vec<&'a str> {
+        self.imports
+            .iter()
+            .map(String::as_str)
+            .filter(|import| !allowed.contains(import))
+            .collect()
+    }
+
+    /// Every difference between the exports and `expected`, one message each.
+    pub fn export_mismatches(&self, expected: &[String]) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        for export in expected {
+            if !self.exports.contains(export) {
+                mismatches.push(format!("doesn't export `{}`", export));
+            }
+        }
+        for export in &self.exports {
+            if !expected.contains(export) {
+                mismatches.push(format!(
+                    "exports `{}`, which src/lib.rs doesn't call",
+                    export
+                ));
+            }
+        }
+        mismatches
+    }
+}
+
+fn signature(types: &[Option<FuncType>], ty: u32) -> Result<String> {
+    let func = types
+        .get(ty as usize)
+        .and_then(Option::as_ref)
+        .with_context(|| format!("unknown function type {}", ty))?;
+    let mut signature = String::from("(");
+    for (i, param) in func.params().iter().enumerate() {
+        if i > 0 {
+            signature.push_str(", ");
+        }
+        write!(signature, "{}", param)?;
+    }
+    signature.push(')');
+    match func.results() {
+        [] => {}
+        [result] => write!(signature, " -> {}", result)?,
+        results => {
+            let results: Vec<String> = results.iter().map(ToString::to_string).collect();
+            write!(signature, " -> ({})", results.join(", "))?;
+        }
+    }
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A module importing `fd_write` from WASI and one watt function, and
+    /// exporting a function and its memory.
+    #[rustfmt::skip]
+    const MODULE: &[u8] = &[
+        0, b'a', b's', b'm', 1, 0, 0, 0,
+        // types: (i32) -> i32, (i32, i32, i32, i32) -> i32
+        1, 14, 2,
+        0x60, 1, 0x7f, 1, 0x7f,
+        0x60, 4, 0x7f, 0x7f, 0x7f, 0x7f, 1, 0x7f,
+        // imports
+        2, 57, 2,
+        8, b'w', b'a', b't', b't', b'-', b'0', b'.', b'4',
+        10, b's', b't', b'r', b'i', b'n', b'g', b'_', b'l', b'e', b'n', 0, 0,
+        22, b'w', b'a', b's', b'i', b'_', b's', b'n', b'a', b'p', b's', b'h', b'o', b't',
+        b'_', b'p', b'r', b'e', b'v', b'i', b'e', b'w', b'1',
+        8, b'f', b'd', b'_', b'w', b'r', b'i', b't', b'e', 0, 1,
+        // one function of type 0
+        3, 2, 1, 0,
+        // memory with one page
+        5, 3, 1, 0, 1,
+        // exports
+        7, 17, 2,
+        6, b'm', b'e', b'm', b'o', b'r', b'y', 2, 0,
+        4, b'd', b'e', b'r', b'i', 0, 2,
+        // body of the function: local.get 0, end
+        10, 6, 1, 4, 0, 0x20, 0, 0x0b,
+    ];
+
+    #[test]
+    fn surface() {
+        let surface = Surface::parse(MODULE).unwrap();
+        assert_eq!(
+            surface.imports,
+            [
+                "watt-0.4 string_len (i32) -> i32",
+                "wasi_snapshot_preview1 fd_write (i32, i32, i32, i32) -> i32",
+            ]
+        );
+        assert_eq!(surface.exports, ["memory memory", "deri (i32) -> i32"]);
+        let allowed = allowed_imports(ALLOWED_IMPORTS, "serde_derive").unwrap();
+        assert_eq!(
+            surface.disallowed_imports(&allowed),
+            ["wasi_snapshot_preview1 fd_write (i32, i32, i32, i32) -> i32"]
+        );
+        assert_eq!(
+            surface.export_mismatches(&["memory memory".to_owned(), "ser (i32) -> i32".to_owned()]),
+            [
+                "doesn't export `ser (i32) -> i32`",
+                "exports `deri (i32) -> i32`, which src/lib.rs doesn't call",
+            ]
+        );
+    }
+
+    #[test]
+    fn allowed_imports_of_a_crate() {
+        let allowed = "\
+# comment
+[a]
+watt-0.4 string_len (i32) -> i32
+
+[b]
+watt-0.4 bytes_len (i32) -> i32
+watt-0.4 string_len (i32) -> i32
+";
+        assert_eq!(
+            allowed_imports(allowed, "a").unwrap(),
+            ["watt-0.4 string_len (i32) -> i32"]
+        );
+        assert_eq!(allowed_imports(allowed, "b").unwrap().len(), 2);
+        assert_eq!(allowed_imports(allowed, "c"), None);
+    }
+
+    #[test]
+    fn exports_of_a_shim() {
+        let lib = "MACRO.proc_macro_derive(stringify!(derive_error), input)\n\
+                   MACRO.proc_macro_attribute(stringify!(main), args, input)";
+        let expected = expected_exports(lib);
+        assert_eq!(
+            &expected[RUNTIME_EXPORTS.len()..],
+            ["derive_error (i32) -> i32", "main (i32, i32) -> i32"]
+        );
+    }
+
+    #[test]
+    fn repository_modules() {
+        let root = crate::repo_root();
+        for krate in crate::hosted_crates(&root).unwrap() {
+            let wasm = fs::read(krate.wasm(&root)).unwrap();
+            crate::mvp::check(&wasm).unwrap_or_else(|error| panic!("{}: {:#}", krate.dir, error));
+            let surface = Surface::parse(&wasm).unwrap();
+            let allowed = allowed_imports(ALLOWED_IMPORTS, &krate.dir)
+                .unwrap_or_else(|| panic!("{} isn't in allowed-imports.txt", krate.dir));
+            assert_eq!(
+                surface.disallowed_imports(&allowed),
+                Vec::<&str>::new(),
+                "{}",
+                krate.dir
+            );
+            let lib = fs::read_to_string(root.join(&krate.dir).join("src").join("lib.rs")).unwrap();
+            assert_eq!(
+                surface.export_mismatches(&expected_exports(&lib)),
+                Vec::<String>::new(),
+                "{}",
+                krate.dir
+            );
+        }
+    }
+}
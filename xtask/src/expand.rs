@@ -0,0 +1,268 @@
+//! Expansion of the `macro_rules!` macros with which some crates define
+//! several proc macros at once, like actix-web-codegen's
+//! `method_macro!(Get, get);`. The shim and the wasm exports only look at
+//! the `#[proc_macro*]` functions among the items of `lib.rs`, so the
+//! invocations are replaced by the items they expand to.
+//!
+//! Only the matchers used for this are supported: `ident`, `literal` and
+//! `tt` fragments, without repetitions.
+
+use anyhow::{bail, Context, Result};
+use proc_macro2::{Group, TokenStream, TokenTree};
+use std::collections::BTreeMap;
+use syn::{File, Item};
+
+struct Rule {
+    matcher: TokenStream,
+    transcriber: TokenStream,
+}
+
+/// Expands the item-level invocations of every `macro_rules!` in `file`
+/// whose body defines a proc macro. The definitions themselves are kept.
+pub fn entry_point_macros(file: &mut File) -> Result<()> {
+    let mut macros = BTreeMap::new();
+    for item in &file.items {
+        match item {
+            Item::Macro(item) if item.mac.path.is_ident("macro_rules") => {
+                if let Some(name) = &item.ident {
+                    if defines_proc_macro(item.mac.tokens.clone()) {
+                        let rules = rules(item.mac.tokens.clone())
+                            .with_context(|| format!("unsupported `macro_rules! {}`", name))?;
+                        macros.insert(name.to_string(), rules);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if macros.is_empty() {
+        return Ok(());
+    }
+
+    let mut items = Vec::new();
+    for item in file.items.drain(..) {
+        let invocation = match &item {
+            Item::Macro(item) if item.ident.is_none() => {
+                let name = item.mac.path.get_ident().map(ToString::to_string);
+                name.and_then(|name| macros.get_key_value(&name))
+                    .map(|(name, rules)| (name, rules, item.mac.tokens.clone()))
+            }
+            _ => None,
+        };
+        let (name, rules, input) = match invocation {
+            Some(invocation) => invocation,
+            None => {
+                items.push(item);
+                continue;
+            }
+        };
+        let tokens =
+            expand(rules, input).with_context(|| format!("failed to expand `{}!`", name))?;
+        let expanded: File =
+            syn::parse2(tokens).with_context(|| format!("`{}!` does not expand to items", name))?;
+        items.extend(expanded.items);
+    }
+    file.items = items;
+    Ok(())
+}
+
+fn defines_proc_macro(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => {
+            ident == "proc_macro" || ident == "proc_macro_derive" || ident == "proc_macro_attribute"
+        }
+        TokenTree::Group(group) => defines_proc_macro(group.stream()),
+        _ => false,
+    })
+}
+
+/// Splits the body of a `macro_rules!` into its `(matcher) => { transcriber }` rules.
+fn rules(tokens: TokenStream) -> Result<Vec<Rule>> {
+    let mut rules = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(matcher) = tokens.next() {
+        let arrow = (tokens.next(), tokens.next());
+        let transcriber = tokens.next();
+        match (matcher, arrow, transcriber) {
+            (
+                TokenTree::Group(matcher),
+                (Some(TokenTree::Punct(eq)), Some(TokenTree::Punct(gt))),
+                Some(TokenTree::Group(transcriber)),
+            ) if eq.as_char() == '=' && gt.as_char() == '>' => rules.push(Rule {
+                matcher: matcher.stream(),
+                transcriber: transcriber.stream(),
+            }),
+            _ => bail!("expected `(matcher) => {{ transcriber }}`"),
+        }
+        if let Some(TokenTree::Punct(semi)) = tokens.peek() {
+            if semi.as_char() == ';' {
+                tokens.next();
+            }
+        }
+    }
+    Ok(rules)
+}
+
+/// Transcribes the first rule whose matcher matches `input`.
+fn expand(rules: &[Rule], input: TokenStream) -> Result<TokenStream> {
+    for rule in rules {
+        let mut bindings = BTreeMap::new();
+        if matches(rule.matcher.clone(), input.clone(), &mut bindings)? {
+            return transcribe(rule.transcriber.clone(), &bindings);
+        }
+    }
+    bail!("no rule matches `{}`", input)
+}
+
+fn matches(
+    matcher: TokenStream,
+    input: TokenStream,
+    bindings: &mut BTreeMap<String, TokenTree>,
+) -> Result<bool> {
+    let mut matcher = matcher.into_iter();
+    let mut input = input.into_iter();
+    while let Some(token) = matcher.next() {
+        match token {
+            TokenTree::Punct(dollar) if dollar.as_char() == '$' => {
+                let name = match matcher.next() {
+                    Some(TokenTree::Ident(name)) => name,
+                    _ => bail!("repetitions are not supported"),
+                };
+                let fragment = match (matcher.next(), matcher.next()) {
+                    (Some(TokenTree::Punct(colon)), Some(TokenTree::Ident(fragment)))
+                        if colon.as_char() == ':' =>
+                    {
+                        fragment.to_string()
+                    }
+                    _ => bail!("expected a fragment specifier after `${}`", name),
+                };
+                let tree = match (fragment.as_str(), input.next()) {
+                    (_, None) => return Ok(false),
+                    ("ident", Some(tree @ TokenTree::Ident(_)))
+                    | ("literal", Some(tree @ TokenTree::Literal(_)))
+                    | ("tt", Some(tree)) => tree,
+                    ("ident" | "literal", Some(_)) => return Ok(false),
+                    (fragment, Some(_)) => bail!("`${}:{}` is not supported", name, fragment),
+                };
+                bindings.insert(name.to_string(), tree);
+            }
+            TokenTree::Group(group) => match input.next() {
+                Some(TokenTree::Group(other)) if other.delimiter() == group.delimiter() => {
+                    if !matches(group.stream(), other.stream(), bindings)? {
+                        return Ok(false);
+                    }
+                }
+                _ => return Ok(false),
+            },
+            token => match input.next() {
+                Some(other) if other.to_string() == token.to_string() => {}
+                _ => return Ok(false),
+            },
+        }
+    }
+    Ok(input.next().is_none())
+}
+
+fn transcribe(
+    transcriber: TokenStream,
+    bindings: &BTreeMap<String, TokenTree>,
+) -> Result<TokenStream> {
+    let mut out = TokenStream::new();
+    let mut tokens = transcriber.into_iter();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(dollar) if dollar.as_char() == '$' => match tokens.next() {
+                Some(TokenTree::Ident(name)) => match bindings.get(&name.to_string()) {
+                    Some(tree) => out.extend([tree.clone()]),
+                    None => bail!("`${}` is not bound by the matcher", name),
+                },
+                _ => bail!("repetitions are not supported"),
+            },
+            TokenTree::Group(group) => {
+                let mut transcribed =
+                    Group::new(group.delimiter(), transcribe(group.stream(), bindings)?);
+                transcribed.set_span(group.span());
+                out.extend([TokenTree::Group(transcribed)]);
+            }
+            token => out.extend([token]),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::entry_point_macros;
+    use quote::quote;
+
+    #[test]
+    fn expands_invocations() {
+        let mut file = syn::parse2(quote! {
+            macro_rules! method_macro {
+                ($variant:ident, $method:ident) => {
+                    #[doc = concat!("Routes `", stringify!($method), "` requests.")]
+                    #[proc_macro_attribute]
+                    pub fn $method(args: TokenStream, input: TokenStream) -> TokenStream {
+                        route::with_method(Some(route::MethodType::$variant), args, input)
+                    }
+                };
+            }
+
+            method_macro!(Get, get);
+            method_macro!(Post, post);
+
+            macro_rules! unrelated {
+                ($e:expr) => { $e };
+            }
+        })
+        .unwrap();
+        entry_point_macros(&mut file).unwrap();
+        let expected = quote! {
+            macro_rules! method_macro {
+                ($variant:ident, $method:ident) => {
+                    #[doc = concat!("Routes `", stringify!($method), "` requests.")]
+                    #[proc_macro_attribute]
+                    pub fn $method(args: TokenStream, input: TokenStream) -> TokenStream {
+                        route::with_method(Some(route::MethodType::$variant), args, input)
+                    }
+                };
+            }
+
+            #[doc = concat!("Routes `", stringify!(get), "` requests.")]
+            #[proc_macro_attribute]
+            pub fn get(args: TokenStream, input: TokenStream) -> TokenStream {
+                route::with_method(Some(route::MethodType::Get), args, input)
+            }
+
+            #[doc = concat!("Routes `", stringify!(post), "` requests.")]
+            #[proc_macro_attribute]
+            pub fn post(args: TokenStream, input: TokenStream) -> TokenStream {
+                route::with_method(Some(route::MethodType::Post), args, input)
+            }
+
+            macro_rules! unrelated {
+                ($e:expr) => { $e };
+            }
+        };
+        assert_eq!(quote!(#file).to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn rejects_repetitions() {
+        let mut file = syn::parse2(quote! {
+            macro_rules! derives {
+                ($($name:ident),*) => {
+                    $(
+                        #[proc_macro_derive($name)]
+                        pub fn $name(input: TokenStream) -> TokenStream { input }
+                    )*
+                };
+            }
+
+            derives!(Foo, Bar);
+        })
+        .unwrap();
+        let err = entry_point_macros(&mut file).unwrap_err();
+        assert_eq!(err.to_string(), "failed to expand `derives!`");
+    }
+}
@@ -0,0 +1,181 @@
+//! Traces of the machine a `.wasm` module was built on.
+//!
+//! Panic messages embed the path of the source file they come from, so
+//! without remapping a module contains the builder's home directory, cargo
+//! home and toolchain location, and two builds of the same `Cargo.watt.lock`
+//! on different machines differ. [`scan`] looks for those paths, and for
+//! dates, in the data segments and custom sections.
+
+use anyhow::Result;
+use wasmparser::{Parser, Payload};
+
+/// Where `wasm::Build` remaps the cargo home to.
+pub const CARGO_HOME: &str = "/cargo";
+
+#[derive(Debug, PartialEq)]
+pub struct Leak {
+    pub kind: &'static str,
+    pub text: String,
+    /// Number of occurrences.
+    pub count: usize,
+}
+
+/// Directories whose next component is a user name.
+const HOME_DIRS: &[&str] = &["/home/", "/Users/", "\\Users\\"];
+
+/// Directories that only exist on the build machine.
+const LOCAL_DIRS: &[&str] = &["/root/", "/tmp/", "/private/var/", "/var/folders/"];
+
+/// Directories below the cargo home, which has to be remapped to
+/// [`CARGO_HOME`].
+const CARGO_DIRS: &[&str] = &["/registry/src/", "/git/checkouts/"];
+
+/// The rust-src component of a local toolchain, which has to be remapped to
+/// `/rustc/<commit>` like in the precompiled standard library.
+const RUST_SRC: &str = "/lib/rustlib/src/rust/";
+
+/// Finds the leaks in `module`, in order of their first occurrence.
+pub fn scan(module: &[u8]) -> Result<Vec<Leak>> {
+    let mut leaks = Vec::new();
+    for payload in Parser::new(0).parse_all(module) {
+        match payload? {
+            Payload::DataSection(reader) => {
+                for data in reader {
+                    scan_bytes(data?.data, &mut leaks);
+                }
+            }
+            Payload::CustomSection(reader) => scan_bytes(reader.data(), &mut leaks),
+            _ => {}
+        }
+    }
+    Ok(leaks)
+}
+
+fn scan_bytes(bytes: &[u8], leaks: &mut Vec<Leak>) {
+    for i in 0..bytes.len() {
+        let rest = &bytes[i..];
+        for dir in HOME_DIRS {
+            if rest.starts_with(dir.as_bytes()) {
+                let user = component(&rest[dir.len()..]);
+                if !user.is_empty() {
+                    add(leaks, "user name", format!("{}{}", dir, user));
+                }
+            }
+        }
+        for dir in LOCAL_DIRS {
+            if rest.starts_with(dir.as_bytes()) {
+                add(leaks, "local path", dir.trim_end_matches('/').to_owned());
+            }
+        }
+        for dir in CARGO_DIRS {
+            if rest.starts_with(dir.as_bytes()) {
+                let home = last_component(&bytes[..i]);
+                if format!("/{}", home) != CARGO_HOME {
+                    add(leaks, "cargo home", format!("/{}", home));
+                }
+            }
+        }
+        if rest.starts_with(RUST_SRC.as_bytes()) {
+            add(
+                leaks,
+                "toolchain path",
+                RUST_SRC.trim_end_matches('/').to_owned(),
+            );
+        }
+        if i == 0 || !bytes[i - 1].is_ascii_digit() {
+            if let Some(date) = date(rest) {
+                add(leaks, "date", date);
+            }
+        }
+    }
+}
+
+fn add(leaks: &mut Vec<Leak>, kind: &'static str, text: String) {
+    match leaks
+        .iter_mut()
+        .find(|leak| leak.kind == kind && leak.text == text)
+    {
+        Some(leak) => leak.count += 1,
+        None => leaks.push(Leak {
+            kind,
+            text,
+            count: 1,
+        }),
+    }
+}
+
+fn is_path_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'.' || b == b'_' || b == b'-'
+}
+
+fn component(bytes: &[u8]) -> String {
+    let len = bytes.iter().take_while(|b| is_path_char(**b)).count();
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+fn last_component(bytes: &[u8]) -> String {
+    let len = bytes.iter().rev().take_while(|b| is_path_char(**b)).count();
+    String::from_utf8_lossy(&bytes[bytes.len() - len..]).into_owned()
+}
+
+/// A `YYYY-MM-DD` date at the start of `bytes`, not followed by another
+/// digit.
+fn date(bytes: &[u8]) -> Option<String> {
+    let date = bytes.get(..10)?;
+    let number = |digits: &[u8]| {
+        digits.iter().try_fold(0, |n, d| {
+            d.is_ascii_digit().then(|| n * 10 + u32::from(d - b'0'))
+        })
+    };
+    let year = number(&date[0..4])?;
+    let month = number(&date[5..7])?;
+    let day = number(&date[8..10])?;
+    let plausible = (1990..2100).contains(&year)
+        && (1..=12).contains(&month)
+        && (1..=31).contains(&day)
+        && date[4] == b'-'
+        && date[7] == b'-'
+        && !bytes.get(10).is_some_and(u8::is_ascii_digit);
+    if plausible {
+        Some(String::from_utf8_lossy(date).into_owned())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        let data = b"already borrowed/home/jakob/.rustup/toolchains/nightly/lib/rustlib/src/rust/library/core/src/str/mod.rs\
+            expected lifetime/cargo_home/git/checkouts/syn-343fb4274b36443a/a3b9abd/src/lit.rs\
+            /cargo/registry/src/github.com-1ecc6299db9ec823/quote-1.0.7/src/lib.rs\
+            /home/jakob/watt-contrib/target/xtask/upstream/serde_derive-1.0.116/src/de.rs";
+        let mut leaks = Vec::new();
+        scan_bytes(data, &mut leaks);
+        let leaks: Vec<_> = leaks
+            .iter()
+            .map(|leak| (leak.kind, leak.text.as_str(), leak.count))
+            .collect();
+        assert_eq!(
+            leaks,
+            [
+                ("user name", "/home/jakob", 2),
+                ("toolchain path", "/lib/rustlib/src/rust", 1),
+                ("cargo home", "/cargo_home", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn dates() {
+        let mut leaks = Vec::new();
+        scan_bytes(b"rustc 1.46.0-nightly (16957bd4d 2020-06-30)", &mut leaks);
+        scan_bytes(b"2021-01-05", &mut leaks);
+        scan_bytes(b"12020-06-30 2020-13-01 0.2020-06-3", &mut leaks);
+        let dates: Vec<_> = leaks.iter().map(|leak| leak.text.as_str()).collect();
+        assert_eq!(dates, ["2020-06-30", "2021-01-05"]);
+    }
+}
@@ -0,0 +1,588 @@
+//! Maintenance tasks for this repository, run with `cargo xtask <task>`.
+
+mod audit;
+mod expand;
+mod leaks;
+mod manifest;
+mod mvp;
+mod native;
+mod patch;
+mod registry;
+mod shim;
+mod wasm;
+
+use anyhow::{bail, Context, Result};
+use manifest::Source;
+use registry::Registry;
+use semver::Version;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const USAGE: &str = "\
+usage: cargo xtask regenerate [OPTIONS] [CRATE[@VERSION]]...
+       cargo xtask audit [CRATE]...
+       cargo xtask patch [--apply] [--local] PROJECT
+
+Regenerates the given crates, or every crate in the repository, from the
+upstream `.crate` archives in a local registry mirror. A crate without a
+version keeps the version it currently has, or gets the newest version in the
+mirror if it doesn't exist yet. A version that isn't semver compatible with
+the one in the repository is added next to it, in a directory like
+tokio-macros-1, and a crate without a version regenerates all of them.
+
+options:
+    --registry DIR       directory containing `<name>-<version>.crate` files
+                         (default: cargo's download cache)
+    --proc-macro2 SRC    git url or local path of the proc-macro2 used inside
+                         the wasm (default: watt-contrib-proc-macro2)
+    --syn SRC            git url or local path of a syn that works inside the
+                         wasm (default: syn from crates.io, patched to use
+                         that proc-macro2 for its `proc_macro`)
+    --no-wasm            keep the existing .wasm and Cargo.watt.lock
+
+The audit lists the imports and exports of the .wasm module of the given
+crates, or of every crate, and fails if a module imports anything that isn't
+in xtask/allowed-imports.txt or uses wasm features past the MVP, which watt
+can't decode. It also warns about paths of the build machine
+and dates in the modules, which regenerate doesn't accept in a new module.
+
+The patch task prints the [patch.crates-io] entries for the crates of this
+repository that are in the Cargo.lock of PROJECT (its directory or the
+lockfile itself), and explains the ones whose locked version the crate here
+can't replace, like a serde_derive that serde pins to another version. The
+requirements are read from cargo's download cache. --apply adds the entries
+to PROJECT's Cargo.toml instead, and --local patches with the paths of this
+checkout instead of its git url.";
+
+/// Directory of the default `--proc-macro2`, in the repository.
+const DEFAULT_PROC_MACRO2: &str = "watt-contrib-proc-macro2";
+const REPOSITORY: &str = "https://github.com/jakobhellermann/watt-contrib";
+
+struct Options {
+    registry: Option<PathBuf>,
+    proc_macro2: Source,
+    syn: Option<Source>,
+    no_wasm: bool,
+    crates: Vec<(String, Option<Version>)>,
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("error: {:#}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("regenerate") => {}
+        Some("audit") => return audit(args),
+        Some("patch") => return patch(args),
+        Some("-h") | Some("--help") | None => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Some(task) => bail!("unknown task `{}`\n\n{}", task, USAGE),
+    }
+
+    let mut options = Options {
+        registry: None,
+        proc_macro2: Source::Path(repo_root().join(DEFAULT_PROC_MACRO2).display().to_string()),
+        syn: None,
+        no_wasm: false,
+        crates: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .with_context(|| format!("{} needs a value", name))
+        };
+        match arg.as_str() {
+            "--registry" => options.registry = Some(value("--registry")?.into()),
+            "--proc-macro2" => options.proc_macro2 = Source::parse(&value("--proc-macro2")?),
+            "--syn" => options.syn = Some(Source::parse(&value("--syn")?)),
+            "--no-wasm" => options.no_wasm = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') => bail!("unknown option `{}`\n\n{}", flag, USAGE),
+            krate => {
+                let (name, version) = match krate.split_once('@') {
+                    Some((name, version)) => (name, Some(version.parse()?)),
+                    None => (krate, None),
+                };
+                options.crates.push((name.to_owned(), version));
+            }
+        }
+    }
+
+    let root = repo_root();
+    let hosted = hosted_crates(&root)?;
+    // Directory, crate and version of each crate to regenerate.
+    let mut targets = Vec::new();
+    if options.crates.is_empty() {
+        for krate in &hosted {
+            targets.push((krate.dir.clone(), krate.name.clone(), None));
+        }
+    }
+    for (name, version) in &options.crates {
+        let dirs: Vec<&Hosted> = hosted
+            .iter()
+            .filter(|krate| krate.name == *name || krate.dir == *name)
+            .collect();
+        match version {
+            None if dirs.is_empty() => targets.push((name.clone(), name.clone(), None)),
+            None => {
+                for krate in dirs {
+                    targets.push((krate.dir.clone(), krate.name.clone(), None));
+                }
+            }
+            Some(version) => {
+                let name = dirs.first().map_or(name, |krate| &krate.name);
+                let dir = match dirs
+                    .iter()
+                    .find(|krate| patch::compatible(&krate.version, version))
+                {
+                    Some(krate) => krate.dir.clone(),
+                    None if dirs.is_empty() => name.clone(),
+                    None => format!("{}-{}", name, patch::requirement(version)),
+                };
+                targets.push((dir, name.clone(), Some(version.clone())));
+            }
+        }
+    }
+
+    let registry = Registry::new(options.registry.clone())?;
+    for (dir, name, version) in &targets {
+        regenerate(&root, &registry, &options, dir, name, version.as_ref())
+            .with_context(|| format!("failed to regenerate {}", dir))?;
+    }
+    Ok(())
+}
+
+fn audit(args: impl Iterator<Item = String>) -> Result<()> {
+    let root = repo_root();
+    let mut crates = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') => bail!("unknown option `{}`\n\n{}", flag, USAGE),
+            krate => crates.push(krate.to_owned()),
+        }
+    }
+    let hosted = hosted_crates(&root)?;
+    let mut selected = Vec::new();
+    for krate in &crates {
+        let len = selected.len();
+        selected.extend(
+            hosted
+                .iter()
+                .filter(|hosted| hosted.name == *krate || hosted.dir == *krate),
+        );
+        if selected.len() == len {
+            bail!("{} is not in this repository", krate);
+        }
+    }
+    if crates.is_empty() {
+        selected.extend(&hosted);
+    }
+
+    let mut failed = Vec::new();
+    for krate in selected {
+        let name = &krate.dir;
+        let path = krate.wasm(&root);
+        let wasm = fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        let surface = audit::Surface::parse(&wasm)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        println!("{}", name);
+        for import in &surface.imports {
+            println!("    import {}", import);
+        }
+        for export in &surface.exports {
+            println!("    export {}", export);
+        }
+        for leak in leaks::scan(&wasm)? {
+            eprintln!(
+                "warning: {} contains the {} `{}` ({} time{})",
+                name,
+                leak.kind,
+                leak.text,
+                leak.count,
+                if leak.count == 1 { "" } else { "s" },
+            );
+        }
+        let lib_path = root.join(name).join("src").join("lib.rs");
+        let lib = fs::read_to_string(&lib_path)
+            .with_context(|| format!("failed to read {}", lib_path.display()))?;
+        let mut errors = Vec::new();
+        match audit::allowed_imports(audit::ALLOWED_IMPORTS, name) {
+            Some(allowed) => {
+                for import in surface.disallowed_imports(&allowed) {
+                    errors.push(format!("imports `{}`", import));
+                }
+            }
+            None => errors.push("isn't in xtask/allowed-imports.txt".to_owned()),
+        }
+        errors.extend(surface.export_mismatches(&audit::expected_exports(&lib)));
+        if let Err(error) = mvp::check(&wasm) {
+            errors.push(format!("can't be decoded by watt: {:#}", error));
+        }
+        for error in &errors {
+            eprintln!("error: {} {}", name, error);
+        }
+        if !errors.is_empty() {
+            failed.push(name.as_str());
+        }
+    }
+    if !failed.is_empty() {
+        bail!(
+            "{} {} imports, exports or wasm features that aren't allowed",
+            failed.join(", "),
+            if failed.len() == 1 { "has" } else { "have" },
+        );
+    }
+    Ok(())
+}
+
+fn patch(args: impl Iterator<Item = String>) -> Result<()> {
+    let root = repo_root();
+    let mut apply = false;
+    let mut local = false;
+    let mut project = None;
+    for arg in args {
+        match arg.as_str() {
+            "--apply" => apply = true,
+            "--local" => local = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') => bail!("unknown option `{}`\n\n{}", flag, USAGE),
+            _ if project.is_some() => bail!("{}", USAGE),
+            path => project = Some(PathBuf::from(path)),
+        }
+    }
+    let project = project.with_context(|| format!("missing PROJECT\n\n{}", USAGE))?;
+    let (lock_path, project) = if project.is_dir() {
+        (project.join("Cargo.lock"), project)
+    } else {
+        let dir = project.parent().unwrap_or(Path::new(".")).to_owned();
+        (project, dir)
+    };
+    let lock = fs::read_to_string(&lock_path).with_context(|| {
+        format!(
+            "failed to read {}, run `cargo generate-lockfile` first",
+            lock_path.display()
+        )
+    })?;
+
+    let hosted = hosted_crates(&root)?;
+    let mut shims = Vec::new();
+    for krate in &hosted {
+        let mut source = if local {
+            format!(
+                "{{ path = {:?}",
+                root.join(&krate.dir).display().to_string()
+            )
+        } else {
+            format!("{{ git = {:?}", REPOSITORY)
+        };
+        if krate.dir != krate.name {
+            source.push_str(&format!(", package = {:?}", krate.name));
+        }
+        // With several versions in the repository, a git patch has to
+        // select one of them.
+        let versions = hosted.iter().filter(|other| other.name == krate.name);
+        if !local && versions.count() > 1 {
+            source.push_str(&format!(
+                ", version = {:?}",
+                patch::requirement(&krate.version)
+            ));
+        }
+        source.push_str(" }");
+        shims.push(patch::Shim {
+            key: krate.dir.clone(),
+            name: krate.name.clone(),
+            version: krate.version.clone(),
+            source,
+        });
+    }
+    // The requirements of the packages depending on a shim are read from
+    // the archives cargo downloaded when building the project.
+    let registry = Registry::new(None).ok();
+    let manifest = |name: &str, version: &Version| match &registry {
+        Some(registry) => registry.manifest(name, version),
+        None => Ok(None),
+    };
+    let plan = patch::plan(&shims, &lock, manifest)
+        .with_context(|| format!("failed to read {}", lock_path.display()))?;
+    if !plan.unchecked.is_empty() {
+        eprintln!(
+            "warning: the requirements of {} couldn't be checked, run `cargo fetch` in {} first",
+            plan.unchecked.join(", "),
+            project.display(),
+        );
+    }
+    for reason in &plan.skipped {
+        eprintln!("skipping {}", reason);
+    }
+    if plan.patch.is_empty() {
+        eprintln!("nothing to patch");
+        return Ok(());
+    }
+    if !apply {
+        print!("{}", plan.render());
+        return Ok(());
+    }
+
+    let manifest_path = project.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+    let (manifest, kept) = patch::apply(&manifest, &plan)?;
+    for shim in &kept {
+        eprintln!(
+            "skipping {}, which {} already patches",
+            shim.key,
+            manifest_path.display()
+        );
+    }
+    fs::write(&manifest_path, manifest)?;
+    for shim in &plan.patch {
+        if !kept.iter().any(|kept| kept.key == shim.key) {
+            eprintln!("patched {} with {}", shim.name, shim.version);
+        }
+    }
+    Ok(())
+}
+
+/// The cargo running this task, so that `+toolchain` overrides carry over.
+fn cargo() -> Command {
+    Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+}
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_owned()
+}
+
+/// A generated crate. The directory is named after the crate, except for
+/// further versions of a crate that is already in the repository, which are
+/// in `<crate>-<major version>` (or `-0.<minor version>`) next to it.
+struct Hosted {
+    dir: String,
+    name: String,
+    version: Version,
+}
+
+impl Hosted {
+    fn wasm(&self, root: &Path) -> PathBuf {
+        root.join(&self.dir)
+            .join("src")
+            .join(format!("{}.wasm", self.name))
+    }
+}
+
+/// Every directory holding a generated crate.
+fn hosted_crates(root: &Path) -> Result<Vec<Hosted>> {
+    let mut crates = Vec::new();
+    for entry in fs::read_dir(root)? {
+        let path = entry?.path();
+        if path.join("Cargo.watt.lock").is_file() {
+            let info = manifest::package_info(&fs::read_to_string(path.join("Cargo.toml"))?)?;
+            crates.push(Hosted {
+                dir: path.file_name().unwrap().to_string_lossy().into_owned(),
+                name: info.name,
+                version: info.version.parse()?,
+            });
+        }
+    }
+    crates.sort_by(|a, b| a.dir.cmp(&b.dir));
+    Ok(crates)
+}
+
+fn regenerate(
+    root: &Path,
+    registry: &Registry,
+    options: &Options,
+    dir: &str,
+    name: &str,
+    version: Option<&Version>,
+) -> Result<()> {
+    let dest = root.join(dir);
+    let current = match fs::read_to_string(dest.join("Cargo.toml")) {
+        Ok(manifest) => Some(
+            manifest::package_info(&manifest)?
+                .version
+                .parse::<Version>()?,
+        ),
+        Err(_) => None,
+    };
+    let version = version.or(current.as_ref());
+    let (archive, version) = registry.find(name, version)?;
+    eprintln!("regenerating {} {}", name, version);
+    let same_version = current.as_ref() == Some(&version);
+
+    let work = root.join("target").join("xtask");
+    let upstream = registry.unpack(&archive, &work.join("upstream"))?;
+    let staged = work.join("staged").join(dir);
+    if staged.exists() {
+        fs::remove_dir_all(&staged)?;
+    }
+    fs::create_dir_all(&staged)?;
+
+    // Docs, licenses, build scripts and tests are kept as published.
+    for entry in fs::read_dir(&upstream)? {
+        let path = entry?.path();
+        let file_name = path.file_name().unwrap();
+        if file_name == "src" || file_name == "Cargo.toml" || file_name == "Cargo.lock" {
+            continue;
+        }
+        copy(&path, &staged.join(file_name))?;
+    }
+    // Tests of our own, for crates that publish none, are kept. So are files
+    // of our own next to upstream's tests, like stand-ins for unpublished
+    // test helpers, as long as the version stays the same; otherwise they
+    // can't be told apart from tests that upstream dropped.
+    let tests = dest.join("tests");
+    if !upstream.join("tests").exists() && tests.is_dir() {
+        copy(&tests, &staged.join("tests"))?;
+    } else if same_version && tests.is_dir() {
+        for entry in fs::read_dir(&tests)? {
+            let path = entry?.path();
+            let file_name = path.file_name().unwrap();
+            if !upstream.join("tests").join(file_name).exists() {
+                copy(&path, &staged.join("tests").join(file_name))?;
+            }
+        }
+    }
+
+    let upstream_manifest = fs::read_to_string(upstream.join("Cargo.toml"))?;
+    let info = manifest::package_info(&upstream_manifest)?;
+    let wasm_file = format!("{}.wasm", info.name);
+
+    let shim = shim::Shim::parse(&fs::read_to_string(upstream.join("src").join("lib.rs"))?)?;
+    fs::create_dir_all(staged.join("src"))?;
+    fs::write(staged.join("src").join("lib.rs"), shim.render(&wasm_file)?)?;
+    fs::write(
+        staged.join("Cargo.toml"),
+        manifest::shim(&upstream_manifest, &shim.imported_crates())?,
+    )?;
+    native::vendor(&upstream.join("src"), &staged.join("native"))?;
+    let build_script = staged.join(&info.build);
+    let upstream_build_script = if build_script.is_file() {
+        Some(fs::read_to_string(&build_script)?)
+    } else {
+        None
+    };
+    fs::write(
+        &build_script,
+        shim::build_script(upstream_build_script.as_deref())?,
+    )?;
+    // Formats the shim and the copied tests, but not `native/`, which is
+    // only reachable through `include!`.
+    let status = cargo()
+        .arg("fmt")
+        .current_dir(&staged)
+        .status()
+        .context("failed to run cargo fmt")?;
+    if !status.success() {
+        eprintln!("warning: cargo fmt failed, leaving the copied tests unformatted");
+    }
+
+    let (wasm, lock) = if options.no_wasm {
+        if !same_version {
+            bail!("--no-wasm needs an existing build of {} {}", name, version);
+        }
+        (
+            dest.join("src").join(&wasm_file),
+            dest.join("Cargo.watt.lock"),
+        )
+    } else {
+        let build = wasm::Build {
+            proc_macro2: &options.proc_macro2,
+            syn: options.syn.as_ref(),
+            target_dir: &work.join("wasm"),
+        };
+        let seed = dest.join("Cargo.watt.lock");
+        let seed = if same_version && seed.is_file() {
+            Some(seed.as_path())
+        } else {
+            None
+        };
+        let output = build.run(&upstream, seed)?;
+        let module = fs::read(&output.wasm)?;
+        let leaks = leaks::scan(&module)?;
+        if !leaks.is_empty() {
+            let leaks: Vec<String> = leaks
+                .iter()
+                .map(|leak| format!("the {} `{}`", leak.kind, leak.text))
+                .collect();
+            bail!(
+                "the new module isn't reproducible, it contains {}",
+                leaks.join(", "),
+            );
+        }
+        let surface = audit::Surface::parse(&module)?;
+        let allowed = match audit::allowed_imports(audit::ALLOWED_IMPORTS, dir) {
+            Some(allowed) => allowed,
+            None => bail!(
+                "{} isn't in xtask/allowed-imports.txt yet, check that the imports of the new \
+                 module are watt host functions and list them under [{}]:\n{}",
+                dir,
+                dir,
+                surface.imports.join("\n"),
+            ),
+        };
+        let disallowed = surface.disallowed_imports(&allowed);
+        if !disallowed.is_empty() {
+            bail!(
+                "the new module imports {}, which {} not listed for {} in xtask/allowed-imports.txt",
+                disallowed.join(", "),
+                if disallowed.len() == 1 { "is" } else { "are" },
+                dir,
+            );
+        }
+        let lib = fs::read_to_string(staged.join("src").join("lib.rs"))?;
+        let mismatches = surface.export_mismatches(&audit::expected_exports(&lib));
+        if !mismatches.is_empty() {
+            bail!("the new module {}", mismatches.join(", "));
+        }
+        (output.wasm, output.lock)
+    };
+    fs::copy(&wasm, staged.join("src").join(&wasm_file))
+        .with_context(|| format!("failed to copy {}", wasm.display()))?;
+    fs::copy(&lock, staged.join("Cargo.watt.lock"))?;
+
+    if dest.exists() {
+        fs::remove_dir_all(&dest)?;
+    }
+    fs::rename(&staged, &dest)?;
+    Ok(())
+}
+
+fn copy(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        copy_dir(from, to)
+    } else {
+        fs::copy(from, to)?;
+        Ok(())
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        copy(&path, &to.join(path.file_name().unwrap()))?;
+    }
+    Ok(())
+}
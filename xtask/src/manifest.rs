@@ -0,0 +1,691 @@
+//! Rewriting of the upstream (cargo-normalized) `Cargo.toml`, once for the
+//! shim and once for compiling the macro to wasm.
+//!
+//! The normalized manifests have one `[dependencies.<name>]` table per
+//! dependency, so both rewrites work on whole tables and leave the rest of
+//! the file as published.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Native dependency requirements that differ from upstream because the
+/// upstream requirement admits versions that no longer compile the vendored
+/// source.
+struct Override {
+    krate: &'static str,
+    version: &'static str,
+    dependency: &'static str,
+    requirement: &'static str,
+    reason: &'static [&'static str],
+}
+
+const NATIVE_OVERRIDES: &[Override] = &[Override {
+    krate: "pin-project-internal",
+    version: "0.4.23",
+    dependency: "syn",
+    requirement: ">=1.0.13, <1.0.84",
+    reason: &[
+        "syn 1.0.84 exports its own `parse_quote_spanned!`, which clashes with the",
+        "macro of the same name in native/utils.rs.",
+    ],
+}];
+
+/// Dev-dependencies of the shim beyond upstream's, for tests that upstream
+/// runs in a workspace which provides them.
+struct DevOverride {
+    krate: &'static str,
+    version: &'static str,
+    dependency: &'static str,
+    /// A `version` or `path` key, which replaces upstream's requirement, or
+    /// adds the dependency if upstream has none.
+    source: Option<&'static str>,
+    features: &'static [&'static str],
+    /// The dependency is a facade of the macro crate, which is then patched
+    /// back to the shim so that the tests don't expand with the registry
+    /// copy.
+    facade: bool,
+    /// Cfgs that the tests check, declared so that cargo doesn't warn about
+    /// them.
+    check_cfg: &'static [&'static str],
+    reason: &'static [&'static str],
+}
+
+const DEV_OVERRIDES: &[DevOverride] = &[
+    DevOverride {
+        krate: "tokio-macros",
+        version: "1.8.2",
+        dependency: "tokio",
+        source: None,
+        features: &["test-util"],
+        facade: false,
+        check_cfg: &[],
+        reason: &["The doctests of `start_paused` need tokio's test-util feature."],
+    },
+    DevOverride {
+        krate: "futures-macro",
+        version: "0.3.31",
+        dependency: "futures",
+        source: Some("version = \"=0.3.31\""),
+        features: &[],
+        facade: true,
+        check_cfg: &[],
+        reason: &["The tests are futures', which use the macros through `futures`."],
+    },
+    DevOverride {
+        krate: "futures-macro",
+        version: "0.3.31",
+        dependency: "futures-test",
+        source: Some("version = \"=0.3.31\""),
+        features: &[],
+        facade: true,
+        check_cfg: &[],
+        reason: &["For `#[futures_test::test]`, which is `test_internal`."],
+    },
+    DevOverride {
+        krate: "futures-macro",
+        version: "0.3.31",
+        dependency: "futures-util",
+        source: Some("version = \"=0.3.31\""),
+        features: &[],
+        facade: false,
+        check_cfg: &[],
+        reason: &[
+            "futures takes any later futures-util 0.3, which would bring the futures-macro of",
+            "the same version instead of the patch.",
+        ],
+    },
+    DevOverride {
+        krate: "pin-project-internal",
+        version: "1.1.13",
+        dependency: "pin-project",
+        source: Some("version = \"=1.1.13\""),
+        features: &[],
+        facade: true,
+        check_cfg: &[],
+        reason: &["The expansions refer to `::pin_project`."],
+    },
+    DevOverride {
+        krate: "thiserror-impl",
+        version: "1.0.69",
+        dependency: "thiserror",
+        source: Some("version = \"=1.0.69\""),
+        features: &[],
+        facade: true,
+        check_cfg: &["thiserror_nightly_testing"],
+        reason: &["The tests are thiserror's, which derive through `thiserror`."],
+    },
+    DevOverride {
+        krate: "thiserror-impl",
+        version: "1.0.69",
+        dependency: "anyhow",
+        source: Some("version = \"1.0.73\""),
+        features: &[],
+        facade: false,
+        check_cfg: &[],
+        reason: &["For thiserror's tests of `#[error(transparent)]`."],
+    },
+    DevOverride {
+        krate: "thiserror-impl",
+        version: "1.0.69",
+        dependency: "ref-cast",
+        source: Some("version = \"1.0.18\""),
+        features: &[],
+        facade: false,
+        check_cfg: &[],
+        reason: &["For thiserror's tests of `Path` fields."],
+    },
+    DevOverride {
+        krate: "tracing-attributes",
+        version: "0.1.31",
+        dependency: "tracing-mock",
+        source: Some("version = \"0.1.0-beta.3\""),
+        features: &[],
+        facade: false,
+        check_cfg: &[],
+        reason: &["A path dependency in the tracing repository, published since."],
+    },
+    DevOverride {
+        krate: "tracing-attributes",
+        version: "0.1.31",
+        dependency: "tracing-test",
+        source: Some("path = \"tests/tracing-test\""),
+        features: &[],
+        facade: false,
+        check_cfg: &[],
+        reason: &["A path dependency in the tracing repository that isn't published."],
+    },
+    DevOverride {
+        krate: "actix-web-codegen",
+        version: "4.4.0",
+        dependency: "actix-web",
+        source: None,
+        features: &[],
+        facade: true,
+        check_cfg: &[],
+        reason: &["The tests use the macros through `actix_web`'s re-exports."],
+    },
+    DevOverride {
+        krate: "actix-web-codegen",
+        version: "4.4.0",
+        dependency: "actix-utils",
+        source: Some("version = \"=3.0.1\""),
+        features: &[],
+        facade: false,
+        check_cfg: &[],
+        reason: &["actix-utils 3.0.2 deprecates `future::ok`, which the tests use."],
+    },
+    DevOverride {
+        krate: "strum_macros",
+        version: "0.27.2",
+        dependency: "strum",
+        source: Some("version = \"=0.27.2\""),
+        features: &[],
+        facade: true,
+        check_cfg: &[],
+        reason: &["A path dependency in the strum repository, which the doctests use."],
+    },
+];
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Package,
+    lib: Option<Lib>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    version: String,
+    /// A path, or `false` as published by cargo 1.80 and later for a crate
+    /// without a build script.
+    build: Option<toml::Value>,
+}
+
+#[derive(Deserialize)]
+struct Lib {
+    name: Option<String>,
+}
+
+pub struct PackageInfo {
+    pub name: String,
+    pub version: String,
+    /// Name of the library target, i.e. of the compiled `.wasm` file.
+    pub lib_name: String,
+    /// Path of the build script, whether or not the file exists.
+    pub build: String,
+}
+
+pub fn package_info(manifest: &str) -> Result<PackageInfo> {
+    let manifest: Manifest = toml::from_str(manifest).context("failed to parse Cargo.toml")?;
+    let lib_name = manifest
+        .lib
+        .and_then(|lib| lib.name)
+        .unwrap_or_else(|| manifest.package.name.replace('-', "_"));
+    Ok(PackageInfo {
+        name: manifest.package.name,
+        version: manifest.package.version,
+        lib_name,
+        build: match manifest.package.build {
+            Some(toml::Value::String(path)) => path,
+            _ => "build.rs".to_owned(),
+        },
+    })
+}
+
+/// A top-level table of the manifest. As in `toml_edit`, the blank lines
+/// before a header belong to the table they precede.
+pub struct Table<'a> {
+    pub header: &'a str,
+    pub text: String,
+}
+
+impl Table<'_> {
+    fn dependency(&self) -> Option<&str> {
+        self.header
+            .strip_prefix("[dependencies.")
+            .and_then(|rest| rest.strip_suffix(']'))
+    }
+
+    fn dev_dependency(&self) -> Option<&str> {
+        self.header
+            .strip_prefix("[dev-dependencies.")
+            .and_then(|rest| rest.strip_suffix(']'))
+    }
+}
+
+pub fn tables(manifest: &str) -> (String, Vec<Table<'_>>) {
+    let mut preamble = String::new();
+    let mut tables: Vec<Table> = Vec::new();
+    for line in manifest.split_inclusive('\n') {
+        if line.starts_with('[') {
+            let previous = match tables.last_mut() {
+                Some(table) => &mut table.text,
+                None => &mut preamble,
+            };
+            let content = previous.trim_end_matches('\n').len() + 1;
+            let blank_lines = previous.split_off(content.min(previous.len()));
+            tables.push(Table {
+                header: line.trim_end(),
+                text: blank_lines,
+            });
+        }
+        match tables.last_mut() {
+            Some(table) => table.text.push_str(line),
+            None => preamble.push_str(line),
+        }
+    }
+    (preamble, tables)
+}
+
+/// Builds the shim's `Cargo.toml`: every dependency except those in `keep`
+/// becomes an optional dependency enabled by the `native` feature, and
+/// the shared `watt-contrib-runtime` is added. Targets like examples that
+/// upstream keeps in `src/` point into `native/`, where that now lives.
+pub fn shim(manifest: &str, keep: &[String]) -> Result<String> {
+    let info = package_info(manifest)?;
+    let (mut out, tables) = tables(manifest);
+
+    let mut native = Vec::new();
+    let mut native_tables = String::new();
+    for table in &tables {
+        let dependency = match table.dependency() {
+            Some(dependency) if !keep.contains(&dependency.replace('-', "_")) => dependency,
+            _ => continue,
+        };
+        native.push(dependency);
+        let mut text = table.text.trim().to_owned();
+        let overridden = NATIVE_OVERRIDES.iter().find(|o| {
+            o.krate == info.name && o.version == info.version && o.dependency == dependency
+        });
+        if let Some(o) = overridden {
+            let mut lines: Vec<String> =
+                o.reason.iter().map(|line| format!("# {}", line)).collect();
+            for line in text.lines() {
+                if line.starts_with("version = ") {
+                    lines.push(format!("version = \"{}\"", o.requirement));
+                } else {
+                    lines.push(line.to_owned());
+                }
+            }
+            text = lines.join("\n");
+        }
+        native_tables.push_str(&text);
+        native_tables.push_str("\noptional = true\n");
+    }
+
+    let native_feature = format!(
+        "native = [{}]\n",
+        native
+            .iter()
+            .map(|dependency| format!("\"{}\"", dependency))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let dev_overrides: Vec<&DevOverride> = DEV_OVERRIDES
+        .iter()
+        .filter(|o| o.krate == info.name && o.version == info.version)
+        .collect();
+    let mut has_features = false;
+    for table in &tables {
+        if table.dependency().is_some_and(|dep| native.contains(&dep)) {
+            continue;
+        }
+        if table.header == "[features]" {
+            has_features = true;
+            for line in table.text.lines() {
+                out.push_str(&weak_feature(line, &native));
+                out.push('\n');
+                if line == table.header {
+                    out.push_str(&native_feature);
+                }
+            }
+        } else if table.header == "[package]" {
+            // The shim always has a build script, and keeps or adds tests
+            // that upstream didn't publish.
+            for line in table.text.split_inclusive('\n') {
+                if !matches!(line.trim_end(), "build = false" | "autotests = false") {
+                    out.push_str(line);
+                }
+            }
+        } else if table.header.starts_with("[[") {
+            out.push_str(&table.text.replace("path = \"src/", "path = \"native/"));
+        } else if let Some(o) = dev_overrides
+            .iter()
+            .find(|o| table.dev_dependency() == Some(o.dependency))
+        {
+            out.push_str(&add_features(&table.text, o));
+        } else {
+            out.push_str(&table.text);
+        }
+    }
+    for o in &dev_overrides {
+        let upstream = tables
+            .iter()
+            .any(|table| table.dev_dependency() == Some(o.dependency));
+        if let (false, Some(source)) = (upstream, o.source) {
+            out.truncate(out.trim_end().len());
+            out.push_str("\n\n");
+            out.push_str(&add_features(
+                &format!("[dev-dependencies.{}]\n{}\n", o.dependency, source),
+                o,
+            ));
+        }
+    }
+    if !has_features {
+        out.truncate(out.trim_end().len());
+        out.push_str("\n\n[features]\n");
+        out.push_str(&native_feature);
+    }
+    out.push_str(&native_tables);
+    // Not optional: a `wasm` feature that `native` leaves off would be
+    // enabled anyway by the facade crates, which depend on the shim with
+    // default features, and cargo features can only add dependencies.
+    out.push_str("[build-dependencies.watt-contrib-build]\npath = \"../watt-contrib-build\"\n");
+    out.push_str("[dependencies.watt-contrib-runtime]\npath = \"../watt-contrib-runtime\"\n");
+    let check_cfg: Vec<String> = dev_overrides
+        .iter()
+        .flat_map(|o| o.check_cfg)
+        .map(|cfg| format!("\"cfg({})\"", cfg))
+        .collect();
+    if !check_cfg.is_empty() {
+        if tables
+            .iter()
+            .any(|table| table.header == "[lints.rust.unexpected_cfgs]")
+        {
+            bail!("upstream already configures unexpected_cfgs, merge the check-cfg of the tests by hand");
+        }
+        out.push_str(&format!(
+            "\n[lints.rust.unexpected_cfgs]\nlevel = \"warn\"\ncheck-cfg = [{}]\n",
+            check_cfg.join(", ")
+        ));
+    }
+    if dev_overrides.iter().any(|o| o.facade) {
+        out.push_str(&format!(
+            "\n[patch.crates-io]\n{} = {{ path = \".\" }}\n",
+            info.name
+        ));
+    }
+    Ok(out)
+}
+
+/// Adds the features of `o`, if any, to the dev-dependency `table` and
+/// replaces its requirement with the source of `o`, if any, with the reason
+/// as a comment.
+fn add_features(table: &str, o: &DevOverride) -> String {
+    let features: Vec<String> = o.features.iter().map(|f| format!("\"{}\"", f)).collect();
+    let mut out = String::new();
+    let mut added = false;
+    for line in table.lines() {
+        if line.starts_with('[') {
+            out.push_str(line);
+            out.push('\n');
+            for reason in o.reason {
+                out.push_str(&format!("# {}\n", reason));
+            }
+        } else if let Some(existing) = line
+            .strip_prefix("features = [")
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            let mut all: Vec<&str> = existing.split(", ").filter(|f| !f.is_empty()).collect();
+            all.extend(features.iter().map(String::as_str));
+            out.push_str(&format!("features = [{}]\n", all.join(", ")));
+            added = true;
+        } else if let (true, Some(source)) = (line.starts_with("version = "), o.source) {
+            out.push_str(source);
+            out.push('\n');
+        } else {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    if !added && !features.is_empty() {
+        out.push_str(&format!("features = [{}]\n", features.join(", ")));
+    }
+    out
+}
+
+/// Turns `"dep/feature"` into `"dep?/feature"` for optional dependencies, so
+/// that the feature doesn't enable `native` by itself.
+fn weak_feature(line: &str, optional: &[&str]) -> String {
+    let mut line = line.to_owned();
+    for dependency in optional {
+        line = line.replace(
+            &format!("\"{}/", dependency),
+            &format!("\"{}?/", dependency),
+        );
+    }
+    line
+}
+
+/// Where to get a patched crate from when building the wasm module.
+pub enum Source {
+    Git(String),
+    Path(String),
+}
+
+impl Source {
+    pub fn parse(spec: &str) -> Self {
+        if std::path::Path::new(spec).is_dir() {
+            Source::Path(spec.to_owned())
+        } else {
+            Source::Git(spec.to_owned())
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        match self {
+            Source::Git(url) => format!("{{ git = \"{}\" }}", url),
+            Source::Path(path) => format!("{{ path = \"{}\" }}", path),
+        }
+    }
+}
+
+/// Builds the manifest used to compile the upstream crate to a `cdylib`,
+/// with `patches` as the `[patch.crates-io]` entries that replace
+/// proc-macro2 and syn.
+pub fn wasm(manifest: &str, patches: &[(String, String)]) -> String {
+    let (mut out, tables) = tables(manifest);
+    for table in &tables {
+        if table.header == "[lib]" {
+            for line in table.text.lines() {
+                if line.starts_with("proc-macro = ") {
+                    out.push_str("crate-type = [\"cdylib\"]\n");
+                } else {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        } else {
+            out.push_str(&table.text);
+        }
+    }
+    // The entry points need proc-macro2 even in a crate that only uses
+    // `proc_macro`, like proc-macro-hack.
+    if !tables
+        .iter()
+        .any(|table| table.dependency() == Some("proc-macro2"))
+    {
+        out.truncate(out.trim_end().len());
+        out.push_str("\n[dependencies.proc-macro2]\nversion = \"1\"\n");
+    }
+    out.truncate(out.trim_end().len());
+    out.push_str("\n\n[patch.crates-io]\n");
+    for (key, source) in patches {
+        out.push_str(&format!("{} = {}\n", key, source));
+    }
+    // The copies of path dependencies below the package aren't members.
+    out.push_str(&format!(
+        "\n[profile.release]\nlto = true\n\n[workspace]\nexclude = [\"{}\"]\n",
+        crate::wasm::DEPS
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPSTREAM: &str = r#"[package]
+name = "wasm-bindgen-macro"
+version = "0.2.68"
+
+[lib]
+proc-macro = true
+[dependencies.proc-macro-hack]
+version = "0.5"
+
+[dependencies.quote]
+version = "1.0"
+
+[dependencies.wasm-bindgen-macro-support]
+version = "=0.2.68"
+[dev-dependencies.trybuild]
+version = "1.0"
+
+[features]
+spans = ["wasm-bindgen-macro-support/spans"]
+xxx_debug_only_print_generated_code = []
+"#;
+
+    #[test]
+    fn shim_manifest() {
+        let shim = shim(UPSTREAM, &["proc_macro_hack".to_owned()]).unwrap();
+        assert_eq!(
+            shim,
+            r#"[package]
+name = "wasm-bindgen-macro"
+version = "0.2.68"
+
+[lib]
+proc-macro = true
+[dependencies.proc-macro-hack]
+version = "0.5"
+[dev-dependencies.trybuild]
+version = "1.0"
+
+[features]
+native = ["quote", "wasm-bindgen-macro-support"]
+spans = ["wasm-bindgen-macro-support?/spans"]
+xxx_debug_only_print_generated_code = []
+[dependencies.quote]
+version = "1.0"
+optional = true
+[dependencies.wasm-bindgen-macro-support]
+version = "=0.2.68"
+optional = true
+[build-dependencies.watt-contrib-build]
+path = "../watt-contrib-build"
+[dependencies.watt-contrib-runtime]
+path = "../watt-contrib-runtime"
+"#
+        );
+    }
+
+    #[test]
+    fn dev_dependency_features() {
+        let upstream = "[package]\nname = \"tokio-macros\"\nversion = \"1.8.2\"\n\n\
+                        [dev-dependencies.tokio]\nversion = \"1.0.0\"\nfeatures = [\"full\"]\n\n\
+                        [features]\n";
+        let shim = shim(upstream, &[]).unwrap();
+        assert!(
+            shim.contains(
+                "[dev-dependencies.tokio]\n\
+                 # The doctests of `start_paused` need tokio's test-util feature.\n\
+                 version = \"1.0.0\"\n\
+                 features = [\"full\", \"test-util\"]\n"
+            ),
+            "{}",
+            shim,
+        );
+    }
+
+    #[test]
+    fn facade_dev_dependency() {
+        let upstream = "[package]\nname = \"pin-project-internal\"\nversion = \"1.1.13\"\n\n\
+                        [dev-dependencies]\n\n[features]\n";
+        let shim = shim(upstream, &[]).unwrap();
+        assert!(
+            shim.contains(
+                "native = []\n\n\
+                 [dev-dependencies.pin-project]\n\
+                 # The expansions refer to `::pin_project`.\n\
+                 version = \"=1.1.13\"\n"
+            ),
+            "{}",
+            shim,
+        );
+        assert!(
+            shim.ends_with("\n[patch.crates-io]\npin-project-internal = { path = \".\" }\n"),
+            "{}",
+            shim,
+        );
+    }
+
+    #[test]
+    fn dev_dependency_requirement() {
+        let upstream = "[package]\nname = \"actix-web-codegen\"\nversion = \"4.4.0\"\n\n\
+                        [dev-dependencies.actix-utils]\nversion = \"3\"\n";
+        let shim = shim(upstream, &[]).unwrap();
+        assert!(
+            shim.contains(
+                "[dev-dependencies.actix-utils]\n\
+                 # actix-utils 3.0.2 deprecates `future::ok`, which the tests use.\n\
+                 version = \"=3.0.1\"\n"
+            ),
+            "{}",
+            shim,
+        );
+    }
+
+    #[test]
+    fn examples_point_into_native() {
+        let upstream = "[package]\nname = \"ctor\"\nversion = \"0.1.15\"\n\n\
+                        [[example]]\nname = \"example\"\npath = \"src/example.rs\"\n\
+                        [dependencies.syn]\nversion = \"1\"\n";
+        let shim = shim(upstream, &[]).unwrap();
+        assert!(
+            shim.contains("[[example]]\nname = \"example\"\npath = \"native/example.rs\"\n"),
+            "{}",
+            shim,
+        );
+    }
+
+    #[test]
+    fn recent_package_table() {
+        let upstream = "[package]\nname = \"thiserror-impl\"\nversion = \"1.0.69\"\n\
+                        build = false\nautobins = false\nautotests = false\n";
+        assert_eq!(package_info(upstream).unwrap().build, "build.rs");
+        let shim = shim(upstream, &[]).unwrap();
+        assert!(
+            shim.starts_with(
+                "[package]\nname = \"thiserror-impl\"\nversion = \"1.0.69\"\nautobins = false\n\n"
+            ),
+            "{}",
+            shim,
+        );
+    }
+
+    #[test]
+    fn wasm_manifest() {
+        let wasm = wasm(
+            UPSTREAM,
+            &[
+                (
+                    "proc-macro2".to_owned(),
+                    Source::Git("https://github.com/dtolnay/watt".to_owned()).to_toml(),
+                ),
+                (
+                    "syn-1".to_owned(),
+                    "{ path = \"watt-deps/syn-1.0.109\", package = \"syn\" }".to_owned(),
+                ),
+            ],
+        );
+        assert!(wasm.contains("[lib]\ncrate-type = [\"cdylib\"]\n[dependencies.proc-macro-hack]"));
+        assert!(wasm.contains("\n[dependencies.proc-macro2]\nversion = \"1\"\n\n[patch.crates-io]"));
+        assert!(wasm.ends_with(
+            "[patch.crates-io]\nproc-macro2 = { git = \"https://github.com/dtolnay/watt\" }\n\
+             syn-1 = { path = \"watt-deps/syn-1.0.109\", package = \"syn\" }\n\n\
+             [profile.release]\nlto = true\n\n[workspace]\nexclude = [\"watt-deps\"]\n"
+        ));
+    }
+}
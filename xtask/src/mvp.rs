@@ -0,0 +1,341 @@
+//! Lowering of modules to the instruction set that watt decodes.
+//!
+//! watt 0.4 only decodes the wasm MVP. Rust compiles wasm32-unknown-unknown
+//! with the sign-extension and bulk-memory proposals enabled, and while
+//! `-Ctarget-cpu=mvp` turns them off for the crates `regenerate` compiles,
+//! the precompiled standard library still uses them. So the sign-extension
+//! instructions are rewritten into shifts, and `memory.copy` and
+//! `memory.fill` into calls of two functions appended to the module.
+//! Anything else past the MVP is an error.
+
+use anyhow::{bail, Context, Result};
+use wasm_encoder::reencode::{self, Reencode};
+use wasm_encoder::{
+    BlockType, CodeSection, Function, FunctionSection, Instruction, MemArg, TypeSection, ValType,
+};
+use wasmparser::{Operator, Parser, Payload, Validator, WasmFeatures};
+
+/// Fails if watt can't decode `module`.
+pub fn check(module: &[u8]) -> Result<()> {
+    Validator::new_with_features(WasmFeatures::MVP)
+        .validate_all(module)
+        .context("the module uses wasm features that watt doesn't decode")?;
+    Ok(())
+}
+
+pub fn lower(module: &[u8]) -> Result<Vec<u8>> {
+    let mut lowering = Lowering::scan(module)?;
+    let mut lowered = wasm_encoder::Module::new();
+    lowering
+        .parse_core_module(&mut lowered, Parser::new(0), module)
+        .map_err(|error| anyhow::anyhow!("failed to lower the module: {:?}", error))?;
+    let lowered = lowered.finish();
+    check(&lowered)?;
+    Ok(lowered)
+}
+
+struct Lowering {
+    /// The type `(i32, i32, i32) -> ()` of the helpers, if they are needed.
+    helper_type: Option<u32>,
+    /// The index of `memmove(dst, src, len)`; `memset(dst, byte, len)`
+    /// follows it.
+    memmove: u32,
+}
+
+impl Lowering {
+    fn scan(module: &[u8]) -> Result<Self> {
+        let mut types = 0;
+        let mut functions = 0;
+        let mut bulk_memory = false;
+        for payload in Parser::new(0).parse_all(module) {
+            match payload? {
+                Payload::TypeSection(reader) => types = reader.count(),
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        if let wasmparser::TypeRef::Func(_) = import?.ty {
+                            functions += 1;
+                        }
+                    }
+                }
+                Payload::FunctionSection(reader) => functions += reader.count(),
+                Payload::CodeSectionEntry(body) => {
+                    let mut operators = body.get_operators_reader()?;
+                    while !operators.eof() {
+                        if let Operator::MemoryCopy { .. } | Operator::MemoryFill { .. } =
+                            operators.read()?
+                        {
+                            bulk_memory = true;
+                        }
+                    }
+                }
+                Payload::DataCountSection { .. } => {
+                    bail!("the module has passive data segments, which can't be lowered")
+                }
+                _ => {}
+            }
+        }
+        Ok(Lowering {
+            helper_type: if bulk_memory { Some(types) } else { None },
+            memmove: functions,
+        })
+    }
+}
+
+impl Reencode for Lowering {
+    type Error = anyhow::Error;
+
+    fn parse_type_section(
+        &mut self,
+        types: &mut TypeSection,
+        section: wasmparser::TypeSectionReader<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        reencode::utils::parse_type_section(self, types, section)?;
+        if self.helper_type.is_some() {
+            types.ty().function([ValType::I32; 3], []);
+        }
+        Ok(())
+    }
+
+    fn parse_function_section(
+        &mut self,
+        functions: &mut FunctionSection,
+        section: wasmparser::FunctionSectionReader<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        reencode::utils::parse_function_section(self, functions, section)?;
+        if let Some(ty) = self.helper_type {
+            functions.function(ty);
+            functions.function(ty);
+        }
+        Ok(())
+    }
+
+    fn parse_code_section(
+        &mut self,
+        code: &mut CodeSection,
+        section: wasmparser::CodeSectionReader<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        reencode::utils::parse_code_section(self, code, section)?;
+        if self.helper_type.is_some() {
+            code.function(&memmove());
+            code.function(&memset());
+        }
+        Ok(())
+    }
+
+    fn parse_function_body(
+        &mut self,
+        code: &mut CodeSection,
+        body: wasmparser::FunctionBody<'_>,
+    ) -> Result<(), reencode::Error<Self::Error>> {
+        let mut function = self.new_function_with_parsed_locals(&body)?;
+        let mut operators = body.get_operators_reader()?;
+        while !operators.eof() {
+            let operator = operators.read()?;
+            match operator {
+                Operator::I32Extend8S => sign_extend(&mut function, ValType::I32, 24),
+                Operator::I32Extend16S => sign_extend(&mut function, ValType::I32, 16),
+                Operator::I64Extend8S => sign_extend(&mut function, ValType::I64, 56),
+                Operator::I64Extend16S => sign_extend(&mut function, ValType::I64, 48),
+                Operator::I64Extend32S => sign_extend(&mut function, ValType::I64, 32),
+                Operator::MemoryCopy { .. } => {
+                    function.instruction(&Instruction::Call(self.memmove));
+                }
+                Operator::MemoryFill { .. } => {
+                    function.instruction(&Instruction::Call(self.memmove + 1));
+                }
+                operator => {
+                    function.instruction(&reencode::utils::instruction(self, operator)?);
+                }
+            }
+        }
+        code.function(&function);
+        Ok(())
+    }
+}
+
+/// Sign-extends the low `64 - shift` or `32 - shift` bits of the value on
+/// the stack.
+fn sign_extend(function: &mut Function, ty: ValType, shift: u8) {
+    if ty == ValType::I32 {
+        function
+            .instruction(&Instruction::I32Const(shift.into()))
+            .instruction(&Instruction::I32Shl)
+            .instruction(&Instruction::I32Const(shift.into()))
+            .instruction(&Instruction::I32ShrS);
+    } else {
+        function
+            .instruction(&Instruction::I64Const(shift.into()))
+            .instruction(&Instruction::I64Shl)
+            .instruction(&Instruction::I64Const(shift.into()))
+            .instruction(&Instruction::I64ShrS);
+    }
+}
+
+const DST: u32 = 0;
+const SRC: u32 = 1;
+const LEN: u32 = 2;
+/// The second parameter of `memset`.
+const BYTE: u32 = 1;
+
+fn memarg(align: u32) -> MemArg {
+    MemArg {
+        offset: 0,
+        align,
+        memory_index: 0,
+    }
+}
+
+/// `memmove(dst, src, len)` with the semantics of `memory.copy`: forwards a
+/// word at a time if `dst` is below `src`, otherwise backwards.
+fn memmove() -> Function {
+    use Instruction::*;
+
+    let mut f = Function::new([]);
+    f.instruction(&LocalGet(DST))
+        .instruction(&LocalGet(SRC))
+        .instruction(&I32LeU)
+        .instruction(&If(BlockType::Empty));
+    // while len >= 4 { *dst = *src (4 bytes); dst += 4; src += 4; len -= 4 }
+    f.instruction(&Block(BlockType::Empty))
+        .instruction(&Loop(BlockType::Empty))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Const(4))
+        .instruction(&I32LtU)
+        .instruction(&BrIf(1))
+        .instruction(&LocalGet(DST))
+        .instruction(&LocalGet(SRC))
+        .instruction(&I32Load(memarg(0)))
+        .instruction(&I32Store(memarg(0)));
+    for (local, step) in [(DST, 4), (SRC, 4), (LEN, -4)] {
+        f.instruction(&LocalGet(local))
+            .instruction(&I32Const(step))
+            .instruction(&I32Add)
+            .instruction(&LocalSet(local));
+    }
+    f.instruction(&Br(0)).instruction(&End).instruction(&End);
+    // while len != 0 { *dst = *src; dst += 1; src += 1; len -= 1 }
+    f.instruction(&Block(BlockType::Empty))
+        .instruction(&Loop(BlockType::Empty))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Eqz)
+        .instruction(&BrIf(1))
+        .instruction(&LocalGet(DST))
+        .instruction(&LocalGet(SRC))
+        .instruction(&I32Load8U(memarg(0)))
+        .instruction(&I32Store8(memarg(0)));
+    for (local, step) in [(DST, 1), (SRC, 1), (LEN, -1)] {
+        f.instruction(&LocalGet(local))
+            .instruction(&I32Const(step))
+            .instruction(&I32Add)
+            .instruction(&LocalSet(local));
+    }
+    f.instruction(&Br(0)).instruction(&End).instruction(&End);
+    f.instruction(&Else);
+    // while len >= 4 { len -= 4; dst[len..] = src[len..] (4 bytes) }
+    f.instruction(&Block(BlockType::Empty))
+        .instruction(&Loop(BlockType::Empty))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Const(4))
+        .instruction(&I32LtU)
+        .instruction(&BrIf(1))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Const(-4))
+        .instruction(&I32Add)
+        .instruction(&LocalSet(LEN))
+        .instruction(&LocalGet(DST))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Add)
+        .instruction(&LocalGet(SRC))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Add)
+        .instruction(&I32Load(memarg(0)))
+        .instruction(&I32Store(memarg(0)))
+        .instruction(&Br(0))
+        .instruction(&End)
+        .instruction(&End);
+    // while len != 0 { len -= 1; dst[len] = src[len] }
+    f.instruction(&Block(BlockType::Empty))
+        .instruction(&Loop(BlockType::Empty))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Eqz)
+        .instruction(&BrIf(1))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Const(-1))
+        .instruction(&I32Add)
+        .instruction(&LocalSet(LEN))
+        .instruction(&LocalGet(DST))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Add)
+        .instruction(&LocalGet(SRC))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Add)
+        .instruction(&I32Load8U(memarg(0)))
+        .instruction(&I32Store8(memarg(0)))
+        .instruction(&Br(0))
+        .instruction(&End)
+        .instruction(&End);
+    f.instruction(&End).instruction(&End);
+    f
+}
+
+/// `memset(dst, byte, len)` with the semantics of `memory.fill`.
+fn memset() -> Function {
+    use Instruction::*;
+
+    let mut f = Function::new([]);
+    f.instruction(&Block(BlockType::Empty))
+        .instruction(&Loop(BlockType::Empty))
+        .instruction(&LocalGet(LEN))
+        .instruction(&I32Eqz)
+        .instruction(&BrIf(1))
+        .instruction(&LocalGet(DST))
+        .instruction(&LocalGet(BYTE))
+        .instruction(&I32Store8(memarg(0)));
+    for (local, step) in [(DST, 1), (LEN, -1)] {
+        f.instruction(&LocalGet(local))
+            .instruction(&I32Const(step))
+            .instruction(&I32Add)
+            .instruction(&LocalSet(local));
+    }
+    f.instruction(&Br(0))
+        .instruction(&End)
+        .instruction(&End)
+        .instruction(&End);
+    f
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, lower};
+
+    /// A module whose function `f(dst, src, len)` copies `len` bytes from
+    /// `src` to `dst`, fills the first byte of the result with the sign
+    /// extension of the byte at `src`, then returns it.
+    #[rustfmt::skip]
+    const MODULE: &[u8] = &[
+        0, b'a', b's', b'm', 1, 0, 0, 0,
+        // types: (i32, i32, i32) -> i32
+        1, 8, 1, 0x60, 3, 0x7f, 0x7f, 0x7f, 1, 0x7f,
+        // one function of type 0
+        3, 2, 1, 0,
+        // memory with one page
+        5, 3, 1, 0, 1,
+        // exports: f
+        7, 5, 1, 1, b'f', 0, 0,
+        10, 32, 1, 30, 0,
+        // memory.copy dst src len
+        0x20, 0, 0x20, 1, 0x20, 2, 0xfc, 10, 0, 0,
+        // memory.fill dst (i32.extend8_s (i32.load8_u src)) 1
+        0x20, 0, 0x20, 1, 0x2d, 0, 0, 0xc0, 0x41, 1, 0xfc, 11, 0,
+        // i32.load8_s dst
+        0x20, 0, 0x2c, 0, 0, 0x0b,
+    ];
+
+    #[test]
+    fn lowers_to_mvp() {
+        assert!(check(MODULE).is_err());
+        let lowered = lower(MODULE).unwrap();
+        check(&lowered).unwrap();
+        assert_eq!(lower(&lowered).unwrap(), lowered);
+    }
+}
@@ -0,0 +1,124 @@
+//! Vendoring of the upstream sources into `native/`, which the shim
+//! `include!`s when its `native` feature is enabled.
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+/// Copies the upstream `src/` tree to `dest` and makes its `lib.rs`
+/// includable from the shim's `src/lib.rs`.
+pub fn vendor(upstream_src: &Path, dest: &Path) -> Result<()> {
+    crate::copy_dir(upstream_src, dest)?;
+    let lib = dest.join("lib.rs");
+    let source = fs::read_to_string(&lib)?;
+    fs::write(&lib, strip_crate_attributes(&source))?;
+    Ok(())
+}
+
+/// Removes the inner attributes and `//!` docs from the top of a crate root,
+/// together with the `//` comments directly above each attribute. Everything
+/// after the first item is left untouched.
+fn strip_crate_attributes(source: &str) -> String {
+    let lines: Vec<&str> = source.split('\n').collect();
+    let mut header = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line.starts_with("#![") {
+            let mut depth = bracket_depth(lines[i]);
+            while depth > 0 && i + 1 < lines.len() {
+                i += 1;
+                depth += bracket_depth(lines[i]);
+            }
+        } else if !line.starts_with("//!") && !comments_attribute(&lines, i) {
+            if !line.is_empty() && !line.starts_with("//") {
+                break;
+            }
+            header.push(lines[i]);
+        }
+        i += 1;
+    }
+
+    let mut stripped: Vec<&str> = Vec::new();
+    for line in header.into_iter().skip_while(|line| line.trim().is_empty()) {
+        let repeated_blank =
+            line.trim().is_empty() && stripped.last().is_some_and(|last| last.trim().is_empty());
+        if !repeated_blank {
+            stripped.push(line);
+        }
+    }
+    stripped.extend(&lines[i..]);
+    stripped.join("\n")
+}
+
+fn comments_attribute(lines: &[&str], i: usize) -> bool {
+    lines[i].trim().starts_with("//")
+        && lines
+            .get(i + 1)
+            .is_some_and(|next| next.trim().starts_with("#!["))
+}
+
+fn bracket_depth(line: &str) -> isize {
+    line.chars().fold(0, |depth, c| match c {
+        '[' => depth + 1,
+        ']' => depth - 1,
+        _ => depth,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_crate_attributes;
+
+    #[test]
+    fn vendored() {
+        let root = crate::repo_root();
+        for krate in crate::hosted_crates(&root).unwrap() {
+            assert!(
+                root.join(&krate.dir).join("native").is_dir(),
+                "{} has no native/ directory",
+                krate.dir,
+            );
+        }
+    }
+
+    #[test]
+    fn strips_header() {
+        let source = "\
+//! Crate docs.
+//!
+// Clippy lints we don't care about
+#![allow(
+    clippy::a,
+    clippy::b,
+)]
+#![recursion_limit = \"128\"]
+
+// Required for older rustc.
+extern crate proc_macro;
+
+
+
+#[proc_macro]
+pub fn f(input: TokenStream) -> TokenStream {
+    //! not a header
+    input
+}
+";
+        assert_eq!(
+            strip_crate_attributes(source),
+            "\
+// Required for older rustc.
+extern crate proc_macro;
+
+
+
+#[proc_macro]
+pub fn f(input: TokenStream) -> TokenStream {
+    //! not a header
+    input
+}
+"
+        );
+    }
+}
@@ -0,0 +1,701 @@
+//! `[patch.crates-io]` entries that switch a project's proc macros over to
+//! the crates in this repository.
+//!
+//! Only crates that are in the project's `Cargo.lock` are patched, and only
+//! if the shim can replace the locked version: cargo ignores a patch whose
+//! version doesn't match the requirements on the crate, and says so only
+//! with a warning. Facade crates like `serde` depend on exactly their own
+//! version of the derive crate, so the requirements are read from the
+//! manifests of the locked packages rather than assumed to be `^version`.
+
+use crate::manifest;
+use anyhow::{Context, Result};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+/// The sources cargo records for packages from crates.io.
+const CRATES_IO: &[&str] = &[
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+/// A crate in this repository.
+pub struct Shim {
+    /// Key of the patch entry, the directory of the crate.
+    pub key: String,
+    pub name: String,
+    pub version: Version,
+    /// What to patch the crate with, like `{ git = "..." }`.
+    pub source: String,
+}
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<Locked>,
+}
+
+#[derive(Deserialize)]
+struct Locked {
+    name: String,
+    version: String,
+    source: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+impl Locked {
+    fn version(&self) -> Result<Version> {
+        self.version
+            .parse()
+            .with_context(|| format!("invalid version of {} in Cargo.lock", self.name))
+    }
+}
+
+pub struct Plan<'a> {
+    /// The shims to patch in.
+    pub patch: Vec<&'a Shim>,
+    /// Crates in the lockfile that stay as they are, and why.
+    pub skipped: Vec<String>,
+    /// Packages depending on a shim whose requirements couldn't be checked
+    /// because their manifest isn't available.
+    pub unchecked: Vec<String>,
+}
+
+impl Plan<'_> {
+    /// The `[patch.crates-io]` table.
+    pub fn render(&self) -> String {
+        let mut out = String::from("[patch.crates-io]\n");
+        for shim in &self.patch {
+            out.push_str(&entry(shim));
+        }
+        out
+    }
+}
+
+fn entry(shim: &Shim) -> String {
+    format!("{} = {}\n", shim.key, shim.source)
+}
+
+/// Decides which of `shims` to patch into the project with the lockfile
+/// `lock`. `manifest` returns the `Cargo.toml` of a locked package, if it is
+/// available, to check what the packages depending on a shim require.
+pub fn plan<'a>(
+    shims: &'a [Shim],
+    lock: &str,
+    manifest: impl Fn(&str, &Version) -> Result<Option<String>>,
+) -> Result<Plan<'a>> {
+    let lock: Lockfile = toml::from_str(lock).context("failed to parse Cargo.lock")?;
+    let mut plan = Plan {
+        patch: Vec::new(),
+        skipped: Vec::new(),
+        unchecked: Vec::new(),
+    };
+    let mut names: Vec<&str> = Vec::new();
+    for shim in shims {
+        if !names.contains(&shim.name.as_str()) {
+            names.push(&shim.name);
+        }
+    }
+    for name in names {
+        let hosted: Vec<&Shim> = shims.iter().filter(|shim| shim.name == name).collect();
+        let mut patched: Vec<&Shim> = Vec::new();
+        let mut skipped = Vec::new();
+        for locked in lock.package.iter().filter(|locked| locked.name == name) {
+            let version = locked.version()?;
+            let from_crates_io = locked
+                .source
+                .as_deref()
+                .is_some_and(|source| CRATES_IO.contains(&source));
+            if !from_crates_io {
+                let source = match &locked.source {
+                    Some(source) => source.split('#').next().unwrap(),
+                    None => "a path",
+                };
+                skipped.push(format!(
+                    "{} {}: locked from {}, not from crates.io",
+                    name, version, source,
+                ));
+                continue;
+            }
+            let shim = match hosted
+                .iter()
+                .find(|shim| compatible(&shim.version, &version))
+            {
+                Some(shim) => *shim,
+                None => {
+                    let versions: Vec<String> =
+                        hosted.iter().map(|shim| shim.version.to_string()).collect();
+                    skipped.push(format!(
+                        "{} {}: not semver compatible with the {} in this repository",
+                        name,
+                        version,
+                        versions.join(" or "),
+                    ));
+                    continue;
+                }
+            };
+
+            let mut pins = Vec::new();
+            for dependent in lock.package.iter().filter(|dependent| {
+                dependent
+                    .dependencies
+                    .iter()
+                    .any(|dependency| depends_on(dependency, name, &version))
+            }) {
+                let dependent_version = dependent.version()?;
+                let dependent_manifest = match manifest(&dependent.name, &dependent_version)? {
+                    Some(manifest) => manifest,
+                    None => {
+                        let dependent = format!("{} {}", dependent.name, dependent_version);
+                        if !plan.unchecked.contains(&dependent) {
+                            plan.unchecked.push(dependent);
+                        }
+                        continue;
+                    }
+                };
+                for requirement in requirements(&dependent_manifest, name)? {
+                    if !requirement.matches(&shim.version) {
+                        let chain = facade(&lock, dependent, &manifest)?;
+                        let reason = pin(shim, &chain, &requirement, &manifest)?;
+                        if !pins.contains(&reason) {
+                            pins.push(reason);
+                        }
+                    }
+                }
+            }
+
+            if !pins.is_empty() {
+                skipped.push(format!("{} {}: {}", name, version, pins.join("; ")));
+            } else if caret(&version).matches(&shim.version) {
+                if !patched.iter().any(|patched| patched.key == shim.key) {
+                    patched.push(shim);
+                }
+            } else {
+                skipped.push(format!(
+                    "{} {}: newer than the {} in this repository, \
+                     `cargo update -p {}@{} --precise {}` may work if nothing needs the newer version",
+                    name, version, shim.version, name, version, shim.version,
+                ));
+            }
+        }
+        if !patched.is_empty() {
+            let versions: Vec<String> = patched
+                .iter()
+                .map(|shim| shim.version.to_string())
+                .collect();
+            for reason in &mut skipped {
+                reason.push_str(&format!(
+                    ", so it stays on crates.io while {} {} patched",
+                    versions.join(" and "),
+                    if versions.len() == 1 { "is" } else { "are" },
+                ));
+            }
+        }
+        plan.patch.extend(patched);
+        plan.skipped.extend(skipped);
+    }
+    Ok(plan)
+}
+
+/// Whether an entry of the `dependencies` of a locked package, which is
+/// `name`, `name version` or `name version (source)`, refers to `name` at
+/// `version`.
+fn depends_on(dependency: &str, name: &str, version: &Version) -> bool {
+    let mut words = dependency.split(' ');
+    words.next() == Some(name)
+        && words
+            .next()
+            .is_none_or(|locked| locked == version.to_string())
+}
+
+/// The packages from `dependent` up to the facade a project depends on.
+/// Facades like `serde` are split into crates like `serde_core`, which they
+/// require at exactly their own version, so a pin in one of those is the
+/// facade's to resolve.
+fn facade<'a>(
+    lock: &'a Lockfile,
+    dependent: &'a Locked,
+    manifest: &impl Fn(&str, &Version) -> Result<Option<String>>,
+) -> Result<Vec<&'a Locked>> {
+    let mut chain = vec![dependent];
+    'up: loop {
+        let current = chain[chain.len() - 1];
+        let version = current.version()?;
+        let lockstep = VersionReq::parse(&format!("={}", version)).unwrap();
+        for parent in lock.package.iter().filter(|parent| {
+            parent
+                .dependencies
+                .iter()
+                .any(|dependency| depends_on(dependency, &current.name, &version))
+        }) {
+            if chain.iter().any(|locked| locked.name == parent.name) {
+                continue;
+            }
+            if let Some(manifest) = manifest(&parent.name, &parent.version()?)? {
+                if requirements(&manifest, &current.name)?.contains(&lockstep) {
+                    chain.push(parent);
+                    continue 'up;
+                }
+            }
+        }
+        return Ok(chain);
+    }
+}
+
+/// Explains why the packages of `chain`, from the one requiring the shim up
+/// to its facade, keep the shim from being used. Facade crates like `serde`
+/// depend on exactly their own version of the derive crate, so the way out
+/// is moving the facade to the version in this repository, which only
+/// works if that version of the facade requires it.
+fn pin(
+    shim: &Shim,
+    chain: &[&Locked],
+    requirement: &VersionReq,
+    manifest: &impl Fn(&str, &Version) -> Result<Option<String>>,
+) -> Result<String> {
+    let dependent = chain[chain.len() - 1];
+    let version = dependent.version()?;
+    let mut reason = format!(
+        "{} {} requires {} {}",
+        dependent.name, version, shim.name, requirement,
+    );
+    if chain.len() > 1 {
+        let through: Vec<&str> = chain[..chain.len() - 1]
+            .iter()
+            .map(|locked| locked.name.as_str())
+            .collect();
+        reason.push_str(&format!(" through {}", through.join(" and ")));
+    }
+    reason.push_str(&format!(
+        ", which the {} in this repository doesn't match",
+        shim.version,
+    ));
+    let pinned = chain[0].version()?;
+    let lockstep = *requirement == VersionReq::parse(&format!("={}", pinned)).unwrap();
+    if !lockstep || !compatible(&shim.version, &pinned) {
+        return Ok(reason);
+    }
+    let moves = match manifest(&dependent.name, &shim.version)? {
+        Some(manifest) => requirements(&manifest, &shim.name)?
+            .iter()
+            .all(|requirement| requirement.matches(&shim.version)),
+        None => false,
+    };
+    if moves {
+        reason.push_str(&format!(
+            ", `cargo update -p {}@{} --precise {}` moves {} and {} to {}",
+            dependent.name, version, shim.version, dependent.name, shim.name, shim.version,
+        ));
+        if version > shim.version {
+            reason.push_str(" if nothing needs a newer one");
+        }
+    } else {
+        reason.push_str(&format!(
+            ", so {} has to be {} to a version that requires {} {}",
+            dependent.name,
+            if version > shim.version {
+                "downgraded"
+            } else {
+                "upgraded"
+            },
+            shim.name,
+            shim.version,
+        ));
+    }
+    Ok(reason)
+}
+
+/// Whether `a` and `b` are semver compatible.
+pub fn compatible(a: &Version, b: &Version) -> bool {
+    caret(a).matches(b) || caret(b).matches(a)
+}
+
+/// The requirements of `manifest` on the package `name`, in all dependency
+/// tables that are used when building for the host.
+fn requirements(manifest: &str, name: &str) -> Result<Vec<VersionReq>> {
+    let manifest: toml::Table = toml::from_str(manifest)?;
+    let mut tables = vec![manifest.get("dependencies")];
+    if let Some(targets) = manifest.get("target").and_then(toml::Value::as_table) {
+        for target in targets.values() {
+            tables.push(target.get("dependencies"));
+        }
+    }
+
+    let mut requirements = Vec::new();
+    for table in tables
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_table)
+    {
+        for (key, dependency) in table {
+            let package = dependency
+                .get("package")
+                .and_then(toml::Value::as_str)
+                .unwrap_or(key);
+            if package != name {
+                continue;
+            }
+            let requirement = match dependency {
+                toml::Value::String(requirement) => Some(requirement.as_str()),
+                dependency => dependency.get("version").and_then(toml::Value::as_str),
+            };
+            if let Some(requirement) = requirement {
+                requirements.push(VersionReq::parse(requirement)?);
+            }
+        }
+    }
+    Ok(requirements)
+}
+
+/// The shortest requirement that matches the versions compatible with
+/// `version`, like `1` or `0.4`.
+pub fn requirement(version: &Version) -> String {
+    match version {
+        Version {
+            major: 0, minor: 0, ..
+        } => format!("0.0.{}", version.patch),
+        Version { major: 0, .. } => format!("0.{}", version.minor),
+        _ => version.major.to_string(),
+    }
+}
+
+/// The requirement `^version`, which is what a dependency on `version`
+/// accepts.
+fn caret(version: &Version) -> VersionReq {
+    VersionReq::parse(&format!("^{}", version)).unwrap()
+}
+
+/// Adds the entries of `plan` to the `[patch.crates-io]` table of
+/// `manifest`, creating the table if needed. Crates the manifest already
+/// patches are left alone and returned.
+pub fn apply<'a>(manifest: &str, plan: &Plan<'a>) -> Result<(String, Vec<&'a Shim>)> {
+    let value: toml::Table = toml::from_str(manifest).context("failed to parse Cargo.toml")?;
+    let existing = value
+        .get("patch")
+        .and_then(|patch| patch.get("crates-io"))
+        .and_then(toml::Value::as_table);
+    let (new, kept): (Vec<&Shim>, Vec<&Shim>) = plan
+        .patch
+        .iter()
+        .partition(|shim| !existing.is_some_and(|table| table.contains_key(&shim.key)));
+    let entries: String = new.iter().map(|shim| entry(shim)).collect();
+    if entries.is_empty() {
+        return Ok((manifest.to_owned(), kept));
+    }
+
+    let (mut out, mut tables) = manifest::tables(manifest);
+    match tables
+        .iter_mut()
+        .find(|table| table.header == "[patch.crates-io]")
+    {
+        Some(table) => {
+            let end = table.text.trim_end().len();
+            let entries = format!("\n{}", entries.trim_end_matches('\n'));
+            table.text.insert_str(end, &entries);
+        }
+        None => {
+            let last = match tables.last_mut() {
+                Some(table) => &mut table.text,
+                None => &mut out,
+            };
+            if !last.is_empty() && !last.ends_with('\n') {
+                last.push('\n');
+            }
+            if !last.is_empty() && !last.ends_with("\n\n") {
+                last.push('\n');
+            }
+            last.push_str("[patch.crates-io]\n");
+            last.push_str(&entries);
+        }
+    }
+    for table in &tables {
+        out.push_str(&table.text);
+    }
+    Ok((out, kept))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shims() -> Vec<Shim> {
+        [
+            ("serde_derive", "1.0.116"),
+            ("thiserror-impl", "1.0.20"),
+            ("tokio-macros", "0.2.5"),
+            ("pin-project-internal", "0.4.23"),
+            ("ctor", "0.1.15"),
+        ]
+        .iter()
+        .map(|(name, version)| Shim {
+            key: name.to_string(),
+            name: name.to_string(),
+            version: version.parse().unwrap(),
+            source: "{ git = \"https://github.com/jakobhellermann/watt-contrib\" }".to_owned(),
+        })
+        .collect()
+    }
+
+    const LOCK: &str = r#"
+version = 3
+
+[[package]]
+name = "my-crate"
+version = "0.1.0"
+
+[[package]]
+name = "serde_derive"
+version = "1.0.110"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "thiserror-impl"
+version = "1.0.30"
+source = "sparse+https://index.crates.io/"
+
+[[package]]
+name = "tokio-macros"
+version = "1.8.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "pin-project-internal"
+version = "0.4.23"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "pin-project-internal"
+version = "1.0.2"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    #[test]
+    fn plan_lockfile() {
+        let shims = shims();
+        let plan = plan(&shims, LOCK, |_, _| Ok(None)).unwrap();
+        let patch: Vec<&str> = plan.patch.iter().map(|shim| shim.name.as_str()).collect();
+        assert_eq!(patch, ["serde_derive", "pin-project-internal"]);
+        assert_eq!(
+            plan.skipped,
+            [
+                "thiserror-impl 1.0.30: newer than the 1.0.20 in this repository, \
+                 `cargo update -p thiserror-impl@1.0.30 --precise 1.0.20` may work if nothing needs the newer version",
+                "tokio-macros 1.8.0: not semver compatible with the 0.2.5 in this repository",
+                "pin-project-internal 1.0.2: not semver compatible with the 0.4.23 in this repository, \
+                 so it stays on crates.io while 0.4.23 is patched",
+            ]
+        );
+        assert_eq!(
+            plan.render(),
+            "[patch.crates-io]\n\
+             serde_derive = { git = \"https://github.com/jakobhellermann/watt-contrib\" }\n\
+             pin-project-internal = { git = \"https://github.com/jakobhellermann/watt-contrib\" }\n"
+        );
+    }
+
+    #[test]
+    fn apply_to_manifest() {
+        let shims = shims();
+        let plan = plan(&shims, LOCK, |_, _| Ok(None)).unwrap();
+
+        let (manifest, kept) = apply("[package]\nname = \"my-crate\"\n", &plan).unwrap();
+        assert!(kept.is_empty());
+        assert_eq!(
+            manifest,
+            "[package]\nname = \"my-crate\"\n\n[patch.crates-io]\n\
+             serde_derive = { git = \"https://github.com/jakobhellermann/watt-contrib\" }\n\
+             pin-project-internal = { git = \"https://github.com/jakobhellermann/watt-contrib\" }\n"
+        );
+
+        let (manifest, kept) = apply(
+            "[package]\nname = \"my-crate\"\n\n[patch.crates-io]\nserde_derive = { path = \"../serde\" }\n\n[profile.dev]\nopt-level = 1\n",
+            &plan,
+        )
+        .unwrap();
+        let kept: Vec<&str> = kept.iter().map(|shim| shim.name.as_str()).collect();
+        assert_eq!(kept, ["serde_derive"]);
+        assert_eq!(
+            manifest,
+            "[package]\nname = \"my-crate\"\n\n[patch.crates-io]\nserde_derive = { path = \"../serde\" }\n\
+             pin-project-internal = { git = \"https://github.com/jakobhellermann/watt-contrib\" }\n\
+             \n[profile.dev]\nopt-level = 1\n"
+        );
+    }
+
+    #[test]
+    fn exact_pins() {
+        // serde 1.0.220 and later pin serde_derive through serde_core.
+        let split = |version: &Version| version.minor == 0 && version.patch >= 220;
+        let lock = |version: &str| {
+            let core = if split(&version.parse().unwrap()) {
+                format!(
+                    r#"
+[[package]]
+name = "serde_core"
+version = "{0}"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["serde_derive"]
+"#,
+                    version,
+                )
+            } else {
+                String::new()
+            };
+            format!(
+                r#"
+[[package]]
+name = "serde"
+version = "{0}"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["serde_core", "serde_derive"]
+{1}
+[[package]]
+name = "serde_derive"
+version = "{0}"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "my-crate"
+version = "0.1.0"
+dependencies = ["serde", "thiserror-impl"]
+
+[[package]]
+name = "thiserror-impl"
+version = "1.0.20"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+                version, core,
+            )
+        };
+        let cached = |cached: &'static [&'static str]| {
+            move |name: &str, version: &Version| {
+                if !cached.contains(&version.to_string().as_str()) {
+                    return Ok(None);
+                }
+                Ok(match name {
+                    "serde" if split(version) => Some(format!(
+                        "[package]\nname = \"serde\"\n\
+                         [dependencies.serde_core]\nversion = \"={}\"\n\
+                         [dependencies.serde_derive]\nversion = \"1\"\noptional = true\n",
+                        version,
+                    )),
+                    "serde" => Some(format!(
+                        "[package]\nname = \"serde\"\n\
+                         [dependencies.serde_derive]\nversion = \"={}\"\noptional = true\n\
+                         [target.\"cfg(any())\".dependencies]\nserde_derive = \"=1.0.116\"\n",
+                        version,
+                    )),
+                    "serde_core" => Some(format!(
+                        "[package]\nname = \"serde_core\"\n\
+                         [target.\"cfg(any())\".dependencies]\nserde_derive = \"={}\"\n",
+                        version,
+                    )),
+                    _ => None,
+                })
+            }
+        };
+        let shims = shims();
+
+        let plan = super::plan(&shims, &lock("1.0.116"), cached(&["1.0.116"])).unwrap();
+        let patch: Vec<&str> = plan.patch.iter().map(|shim| shim.name.as_str()).collect();
+        assert_eq!(patch, ["serde_derive", "thiserror-impl"]);
+        assert!(plan.skipped.is_empty());
+        assert_eq!(plan.unchecked, ["my-crate 0.1.0"]);
+
+        let plan = super::plan(&shims, &lock("1.0.110"), cached(&["1.0.110", "1.0.116"])).unwrap();
+        let patch: Vec<&str> = plan.patch.iter().map(|shim| shim.name.as_str()).collect();
+        assert_eq!(patch, ["thiserror-impl"]);
+        assert_eq!(
+            plan.skipped,
+            [
+                "serde_derive 1.0.110: serde 1.0.110 requires serde_derive =1.0.110, \
+              which the 1.0.116 in this repository doesn't match, \
+              `cargo update -p serde@1.0.110 --precise 1.0.116` moves serde and serde_derive to 1.0.116"
+            ]
+        );
+
+        // The pin is serde_core's, but the project depends on serde.
+        let plan = super::plan(&shims, &lock("1.0.229"), cached(&["1.0.229", "1.0.116"])).unwrap();
+        assert_eq!(
+            plan.skipped,
+            [
+                "serde_derive 1.0.229: serde 1.0.229 requires serde_derive =1.0.229 through serde_core, \
+              which the 1.0.116 in this repository doesn't match, \
+              `cargo update -p serde@1.0.229 --precise 1.0.116` moves serde and serde_derive to 1.0.116 \
+              if nothing needs a newer one"
+            ]
+        );
+
+        // Without serde 1.0.116 in the cache, there's no telling whether
+        // it exists and requires serde_derive 1.0.116.
+        let plan = super::plan(&shims, &lock("1.0.229"), cached(&["1.0.229"])).unwrap();
+        assert_eq!(
+            plan.skipped,
+            [
+                "serde_derive 1.0.229: serde 1.0.229 requires serde_derive =1.0.229 through serde_core, \
+              which the 1.0.116 in this repository doesn't match, \
+              so serde has to be downgraded to a version that requires serde_derive 1.0.116"
+            ]
+        );
+    }
+
+    #[test]
+    fn several_versions() {
+        let shim = |key: &str, version: &str, source: &str| Shim {
+            key: key.to_owned(),
+            name: "tokio-macros".to_owned(),
+            version: version.parse().unwrap(),
+            source: source.to_owned(),
+        };
+        let shims = [
+            shim(
+                "tokio-macros",
+                "0.2.5",
+                "{ git = \"..\", version = \"0.2\" }",
+            ),
+            shim(
+                "tokio-macros-1",
+                "1.8.0",
+                "{ git = \"..\", package = \"tokio-macros\", version = \"1\" }",
+            ),
+        ];
+        let lock = |versions: &[&str]| {
+            let mut lock = String::new();
+            for version in versions {
+                lock.push_str(&format!(
+                    "[[package]]\nname = \"tokio-macros\"\nversion = \"{}\"\n\
+                     source = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+                    version,
+                ));
+            }
+            lock
+        };
+
+        let plan = super::plan(&shims, &lock(&["0.2.4", "1.7.0"]), |_, _| Ok(None)).unwrap();
+        assert!(plan.skipped.is_empty());
+        assert_eq!(
+            plan.render(),
+            "[patch.crates-io]\n\
+             tokio-macros = { git = \"..\", version = \"0.2\" }\n\
+             tokio-macros-1 = { git = \"..\", package = \"tokio-macros\", version = \"1\" }\n"
+        );
+
+        let plan = super::plan(&shims, &lock(&["1.7.0", "2.1.0"]), |_, _| Ok(None)).unwrap();
+        let patch: Vec<&str> = plan.patch.iter().map(|shim| shim.key.as_str()).collect();
+        assert_eq!(patch, ["tokio-macros-1"]);
+        assert_eq!(
+            plan.skipped,
+            ["tokio-macros 2.1.0: not semver compatible with the 0.2.5 or 1.8.0 in this repository, \
+              so it stays on crates.io while 1.8.0 is patched"]
+        );
+    }
+
+    #[test]
+    fn requirements_of_versions() {
+        let requirement = |version: &str| requirement(&version.parse().unwrap());
+        assert_eq!(requirement("1.8.0"), "1");
+        assert_eq!(requirement("0.4.23"), "0.4");
+        assert_eq!(requirement("0.0.3"), "0.0.3");
+    }
+}
@@ -0,0 +1,146 @@
+//! Lookup and extraction of `.crate` archives from a local registry mirror.
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use semver::Version;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub fn cargo_home() -> Option<PathBuf> {
+    env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")))
+}
+
+/// A directory (or several) of `<name>-<version>.crate` files, like cargo's
+/// own `~/.cargo/registry/cache/<index>/` or the output of `cargo local-registry`.
+pub struct Registry {
+    dirs: Vec<PathBuf>,
+}
+
+impl Registry {
+    /// Uses `dir` if given, otherwise every index directory in cargo's download cache.
+    pub fn new(dir: Option<PathBuf>) -> Result<Self> {
+        let dirs = match dir {
+            Some(dir) => vec![dir],
+            None => {
+                let cargo_home =
+                    cargo_home().context("cannot locate cargo home, pass --registry")?;
+                let cache = cargo_home.join("registry").join("cache");
+                let mut dirs = Vec::new();
+                for entry in fs::read_dir(&cache)
+                    .with_context(|| format!("failed to read {}", cache.display()))?
+                {
+                    dirs.push(entry?.path());
+                }
+                dirs
+            }
+        };
+        Ok(Registry { dirs })
+    }
+
+    /// Returns the path of the archive for `name`, at `version` or at the
+    /// newest version in the mirror.
+    pub fn find(&self, name: &str, version: Option<&Version>) -> Result<(PathBuf, Version)> {
+        let mut best: Option<(PathBuf, Version)> = None;
+        for dir in &self.dirs {
+            let entries = match fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries {
+                let path = entry?.path();
+                let found = match parse_file_name(&path, name) {
+                    Some(found) => found,
+                    None => continue,
+                };
+                match version {
+                    Some(version) if *version == found => return Ok((path, found)),
+                    Some(_) => {}
+                    None if !found.pre.is_empty() => {}
+                    None => {
+                        if best.as_ref().is_none_or(|(_, best)| found > *best) {
+                            best = Some((path, found));
+                        }
+                    }
+                }
+            }
+        }
+        match (best, version) {
+            (Some(best), _) => Ok(best),
+            (None, Some(version)) => bail!("{} {} not found in the registry mirror", name, version),
+            (None, None) => bail!("{} not found in the registry mirror", name),
+        }
+    }
+
+    /// Extracts `archive` below `dest`, returning the package root.
+    pub fn unpack(&self, archive: &Path, dest: &Path) -> Result<PathBuf> {
+        let file =
+            File::open(archive).with_context(|| format!("failed to open {}", archive.display()))?;
+        let stem = archive
+            .file_stem()
+            .context("archive without file name")?
+            .to_owned();
+        let root = dest.join(&stem);
+        if root.exists() {
+            fs::remove_dir_all(&root)?;
+        }
+        tar::Archive::new(GzDecoder::new(file))
+            .unpack(dest)
+            .with_context(|| format!("failed to unpack {}", archive.display()))?;
+        Ok(root)
+    }
+
+    /// The `Cargo.toml` of `name` at `version`, or `None` if the mirror
+    /// doesn't have that version.
+    pub fn manifest(&self, name: &str, version: &Version) -> Result<Option<String>> {
+        let archive = match self.find(name, Some(version)) {
+            Ok((archive, _)) => archive,
+            Err(_) => return Ok(None),
+        };
+        let file = File::open(&archive)
+            .with_context(|| format!("failed to open {}", archive.display()))?;
+        let path = Path::new(&format!("{}-{}", name, version)).join("Cargo.toml");
+        let mut tar = tar::Archive::new(GzDecoder::new(file));
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            if entry.path()? == path {
+                let mut manifest = String::new();
+                entry.read_to_string(&mut manifest)?;
+                return Ok(Some(manifest));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn parse_file_name(path: &Path, name: &str) -> Option<Version> {
+    let file_name = path.file_name()?.to_str()?;
+    let version = file_name
+        .strip_suffix(".crate")?
+        .strip_prefix(name)?
+        .strip_prefix('-')?;
+    Version::parse(version).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names() {
+        let parse = |file: &str, name| parse_file_name(Path::new(file), name);
+        assert_eq!(
+            parse("syn-1.0.41.crate", "syn"),
+            Some(Version::new(1, 0, 41))
+        );
+        assert_eq!(
+            parse("futures-macro-0.3.5.crate", "futures-macro"),
+            Some(Version::new(0, 3, 5))
+        );
+        assert_eq!(parse("futures-macro-0.3.5.crate", "futures"), None);
+        assert_eq!(parse("syn-1.0.41.tar.gz", "syn"), None);
+    }
+}
@@ -0,0 +1,330 @@
+//! Generation of the `src/lib.rs` wrapper that forwards every proc macro of
+//! the upstream crate to its wasm module.
+
+use anyhow::{bail, Context, Result};
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::quote;
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use syn::{Attribute, Item, Lit, Meta, UseTree};
+
+/// Attributes copied from the upstream function onto its wrapper.
+const KEPT_ATTRIBUTES: &[&str] = &[
+    "doc",
+    "cfg",
+    "cfg_attr",
+    "deprecated",
+    "proc_macro",
+    "proc_macro_derive",
+    "proc_macro_attribute",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Derive,
+    Attribute,
+    Function,
+}
+
+pub struct EntryPoint {
+    pub ident: Ident,
+    pub kind: Kind,
+    attrs: Vec<Attribute>,
+}
+
+/// The parts of an upstream `lib.rs` that end up in the shim.
+pub struct Shim {
+    pub entry_points: Vec<EntryPoint>,
+    /// `use` items that bring attribute macros like `#[proc_macro_hack]` into scope.
+    imports: Vec<syn::ItemUse>,
+    recursion_limit: Option<String>,
+}
+
+impl Shim {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut file = syn::parse_file(source).context("failed to parse upstream lib.rs")?;
+        crate::expand::entry_point_macros(&mut file)?;
+
+        let mut entry_points = Vec::new();
+        let mut macro_attributes = BTreeSet::new();
+        for item in &file.items {
+            let func = match item {
+                Item::Fn(func) => func,
+                _ => continue,
+            };
+            let kind = match func.attrs.iter().find_map(kind_of) {
+                Some(kind) => kind,
+                None => continue,
+            };
+            let mut attrs = Vec::new();
+            for attr in &func.attrs {
+                let name = attr_name(attr);
+                if KEPT_ATTRIBUTES.contains(&name.as_str()) {
+                    attrs.push(attr.clone());
+                } else if name == "proc_macro_hack" {
+                    macro_attributes.insert(name);
+                    attrs.push(attr.clone());
+                }
+            }
+            entry_points.push(EntryPoint {
+                ident: func.sig.ident.clone(),
+                kind,
+                attrs,
+            });
+        }
+        if entry_points.is_empty() {
+            bail!("upstream lib.rs does not define any proc macros");
+        }
+
+        let imports = file
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Use(item) if macro_attributes.contains(&use_leaf(&item.tree)) => {
+                    Some(item.clone())
+                }
+                _ => None,
+            })
+            .collect();
+
+        let recursion_limit = file.attrs.iter().find_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("recursion_limit") => match &meta.value {
+                syn::Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(lit) => Some(lit.value()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        });
+
+        Ok(Shim {
+            entry_points,
+            imports,
+            recursion_limit,
+        })
+    }
+
+    /// Names of the crates whose `use` items are kept, i.e. dependencies the
+    /// shim itself still needs besides `watt-contrib-runtime`.
+    pub fn imported_crates(&self) -> Vec<String> {
+        self.imports
+            .iter()
+            .filter_map(|item| match &item.tree {
+                UseTree::Path(path) => Some(path.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Renders the shim's `src/lib.rs`, loading the snapshot of `wasm_file`
+    /// next to it.
+    pub fn render(&self, wasm_file: &str) -> Result<String> {
+        let wasm_only = quote!(#[cfg(not(feature = "native"))]);
+        let mut tokens = TokenStream::new();
+        if let Some(limit) = &self.recursion_limit {
+            tokens.extend(quote!(#![cfg_attr(feature = "native", recursion_limit = #limit)]));
+        }
+        tokens.extend(quote! {
+            #wasm_only
+            static WASM: &[u8] = watt_contrib_runtime::include_snapshot!(#wasm_file);
+            #wasm_only
+            static MACRO: watt_contrib_runtime::WasmMacro = watt_contrib_runtime::WasmMacro::new(
+                env!("CARGO_PKG_NAME"),
+                env!("CARGO_PKG_VERSION"),
+                WASM,
+            );
+        });
+        for import in &self.imports {
+            tokens.extend(quote!(#wasm_only #import));
+        }
+        for entry in &self.entry_points {
+            let EntryPoint { ident, kind, attrs } = entry;
+            let function = match kind {
+                Kind::Derive => quote! {
+                    pub fn #ident(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+                        MACRO.proc_macro_derive(stringify!(#ident), input)
+                    }
+                },
+                Kind::Attribute => quote! {
+                    pub fn #ident(
+                        args: proc_macro::TokenStream,
+                        input: proc_macro::TokenStream,
+                    ) -> proc_macro::TokenStream {
+                        MACRO.proc_macro_attribute(stringify!(#ident), args, input)
+                    }
+                },
+                Kind::Function => quote! {
+                    pub fn #ident(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+                        MACRO.proc_macro(stringify!(#ident), input)
+                    }
+                },
+            };
+            tokens.extend(quote!(#wasm_only #(#attrs)* #function));
+        }
+        tokens.extend(quote! {
+            #[cfg(feature = "native")]
+            include!("../native/lib.rs");
+        });
+        rustfmt(&tokens.to_string())
+    }
+}
+
+fn attr_name(attr: &Attribute) -> String {
+    attr.path()
+        .get_ident()
+        .map(Ident::to_string)
+        .unwrap_or_default()
+}
+
+fn kind_of(attr: &Attribute) -> Option<Kind> {
+    match attr_name(attr).as_str() {
+        "proc_macro_derive" => Some(Kind::Derive),
+        "proc_macro_attribute" => Some(Kind::Attribute),
+        "proc_macro" | "proc_macro_hack" => Some(Kind::Function),
+        // e.g. strum's `#[cfg_attr(feature = "...", proc_macro_derive(...))]`
+        "cfg_attr" => match &attr.meta {
+            Meta::List(list) => list
+                .tokens
+                .clone()
+                .into_iter()
+                .find_map(|token| match token {
+                    TokenTree::Ident(ident) => match ident.to_string().as_str() {
+                        "proc_macro_derive" => Some(Kind::Derive),
+                        "proc_macro_attribute" => Some(Kind::Attribute),
+                        "proc_macro" => Some(Kind::Function),
+                        _ => None,
+                    },
+                    _ => None,
+                }),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn use_leaf(tree: &UseTree) -> String {
+    match tree {
+        UseTree::Path(path) => use_leaf(&path.tree),
+        UseTree::Name(name) => name.ident.to_string(),
+        UseTree::Rename(rename) => rename.rename.to_string(),
+        UseTree::Glob(_) | UseTree::Group(_) => String::new(),
+    }
+}
+
+const BUILD_STEPS: &str =
+    "watt_contrib_build::check_entry_points();\n    watt_contrib_build::write_snapshot();";
+
+/// The shim's build script: the upstream one, if any, with calls to
+/// `watt_contrib_build::check_entry_points` and `write_snapshot` as the first
+/// statements of `main`, so they run before any early return.
+pub fn build_script(upstream: Option<&str>) -> Result<String> {
+    let upstream = match upstream {
+        Some(upstream) => upstream,
+        None => return Ok(format!("fn main() {{\n    {}\n}}\n", BUILD_STEPS)),
+    };
+    let mut out = String::new();
+    let mut found = false;
+    for line in upstream.split_inclusive('\n') {
+        out.push_str(line);
+        if !found && line == "fn main() {\n" {
+            out.push_str(&format!("    {}\n\n", BUILD_STEPS));
+            found = true;
+        }
+    }
+    if !found {
+        bail!("no `fn main() {{` in the upstream build script");
+    }
+    Ok(out)
+}
+
+fn rustfmt(source: &str) -> Result<String> {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2018"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("failed to run rustfmt")?;
+    child
+        .stdin
+        .take()
+        .context("rustfmt stdin")?
+        .write_all(source.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("rustfmt failed on the generated shim");
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_point_kinds() {
+        let shim = Shim::parse(
+            r#"
+            #![recursion_limit = "128"]
+            use proc_macro_hack::proc_macro_hack;
+
+            /// Derives.
+            #[proc_macro_derive(Foo, attributes(foo))]
+            pub fn derive_foo(input: TokenStream) -> TokenStream { input }
+
+            #[cfg_attr(feature = "verbose", proc_macro_derive(StrumBar))]
+            #[cfg_attr(not(feature = "verbose"), proc_macro_derive(Bar))]
+            pub fn bar(input: TokenStream) -> TokenStream { input }
+
+            #[allow(clippy::all)]
+            #[proc_macro_attribute]
+            pub fn attr(args: TokenStream, input: TokenStream) -> TokenStream { input }
+
+            #[proc_macro_hack]
+            pub fn hack(input: TokenStream) -> TokenStream { input }
+
+            fn helper() {}
+            "#,
+        )
+        .unwrap();
+
+        let kinds: Vec<_> = shim
+            .entry_points
+            .iter()
+            .map(|entry| (entry.ident.to_string(), entry.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("derive_foo".to_owned(), Kind::Derive),
+                ("bar".to_owned(), Kind::Derive),
+                ("attr".to_owned(), Kind::Attribute),
+                ("hack".to_owned(), Kind::Function),
+            ]
+        );
+        assert_eq!(shim.imported_crates(), ["proc_macro_hack"]);
+        assert_eq!(shim.recursion_limit.as_deref(), Some("128"));
+        assert!(shim.entry_points[2]
+            .attrs
+            .iter()
+            .all(|attr| !attr.path().is_ident("allow")));
+    }
+
+    #[test]
+    fn build_scripts() {
+        assert_eq!(
+            build_script(None).unwrap(),
+            "fn main() {\n    watt_contrib_build::check_entry_points();\n    \
+             watt_contrib_build::write_snapshot();\n}\n",
+        );
+        assert_eq!(
+            build_script(Some(
+                "use std::env;\n\nfn main() {\n    let minor = 1;\n}\n"
+            ))
+            .unwrap(),
+            "use std::env;\n\nfn main() {\n    watt_contrib_build::check_entry_points();\n    \
+             watt_contrib_build::write_snapshot();\n\n    let minor = 1;\n}\n",
+        );
+    }
+}
@@ -0,0 +1,475 @@
+//! Compilation of an upstream proc-macro crate into the `.wasm` module that
+//! `watt` interprets.
+
+use crate::manifest::{self, Source};
+use crate::registry::{self, Registry};
+use crate::{leaks, mvp, patch};
+use anyhow::{bail, Context, Result};
+use quote::quote;
+use semver::Version;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syn::{parse_quote, Attribute, Item, Meta};
+
+/// Directory in the package that the path dependencies of the build are
+/// copied to. Cargo derives the `-C metadata` of a path dependency from its
+/// path relative to the package if it's inside, and from its absolute path
+/// otherwise; the metadata ends up in symbol names and type ids, and from
+/// there in the layout of the module.
+pub const DEPS: &str = "watt-deps";
+
+pub struct Build<'a> {
+    pub proc_macro2: &'a Source,
+    /// `None` patches every syn from crates.io with [`rebind_proc_macro`].
+    pub syn: Option<&'a Source>,
+    /// Shared by all builds so the dependencies from crates.io are only
+    /// compiled once.
+    pub target_dir: &'a Path,
+}
+
+pub struct Output {
+    pub wasm: PathBuf,
+    pub lock: PathBuf,
+}
+
+impl Build<'_> {
+    /// Builds the unpacked crate at `package` in place. `lock` seeds the
+    /// dependency resolution, so regenerating a crate at the same version
+    /// keeps the versions of syn and friends.
+    pub fn run(&self, package: &Path, lock: Option<&Path>) -> Result<Output> {
+        let manifest_path = package.join("Cargo.toml");
+        let upstream = fs::read_to_string(&manifest_path)?;
+        let info = manifest::package_info(&upstream)?;
+        let deps = package.join(DEPS);
+        if deps.exists() {
+            fs::remove_dir_all(&deps)?;
+        }
+        let mut patches = vec![(
+            "proc-macro2".to_owned(),
+            stage(self.proc_macro2, package, "proc-macro2")?,
+        )];
+        if let Some(syn) = self.syn {
+            patches.push(("syn".to_owned(), stage(syn, package, "syn")?));
+        }
+        fs::write(&manifest_path, manifest::wasm(&upstream, &patches))?;
+
+        let lib = package.join("src").join("lib.rs");
+        let source = fs::read_to_string(&lib)?;
+        fs::write(&lib, export_entry_points(&source)?)?;
+
+        let lock_path = package.join("Cargo.lock");
+        match lock {
+            Some(lock) => {
+                fs::copy(lock, &lock_path)?;
+            }
+            // A lockfile published with the crate would keep proc-macro2
+            // from the registry instead of the patch.
+            None if lock_path.exists() => fs::remove_file(&lock_path)?,
+            None => {}
+        }
+        if self.syn.is_none() {
+            patches.extend(self.patch_syn(package, &lock_path)?);
+            fs::write(&manifest_path, manifest::wasm(&upstream, &patches))?;
+        }
+
+        let status = crate::cargo()
+            .current_dir(package)
+            .args([
+                "build",
+                "--release",
+                "--lib",
+                "--target",
+                "wasm32-unknown-unknown",
+            ])
+            .env("CARGO_TARGET_DIR", self.target_dir)
+            .env("CARGO_ENCODED_RUSTFLAGS", self.rustflags(package, &info)?)
+            .status()
+            .context("failed to run cargo")?;
+        if !status.success() {
+            bail!("failed to compile {} {} to wasm", info.name, info.version);
+        }
+
+        let wasm = self
+            .target_dir
+            .join("wasm32-unknown-unknown")
+            .join("release")
+            .join(format!("{}.wasm", info.lib_name));
+        let module = fs::read(&wasm)?;
+        fs::write(&wasm, mvp::lower(&strip_custom_sections(&module)?)?)?;
+        Ok(Output {
+            wasm,
+            lock: lock_path,
+        })
+    }
+}
+
+impl Build<'_> {
+    /// Resolves the dependencies of `package`, copies each version of syn in
+    /// them from cargo's download cache to [`DEPS`] with [`rebind_proc_macro`]
+    /// applied, and returns the patches that replace them.
+    fn patch_syn(&self, package: &Path, lock_path: &Path) -> Result<Vec<(String, String)>> {
+        let status = crate::cargo()
+            .current_dir(package)
+            .args(["fetch", "--target", "wasm32-unknown-unknown"])
+            .status()
+            .context("failed to run cargo")?;
+        if !status.success() {
+            bail!("failed to fetch the dependencies of {}", package.display());
+        }
+        let lock: Lockfile = toml::from_str(&fs::read_to_string(lock_path)?)?;
+        let registry = Registry::new(None)?;
+        let mut patches = Vec::new();
+        for dependency in lock.package {
+            let from_registry = dependency
+                .source
+                .as_deref()
+                .is_some_and(|source| source.starts_with("registry+"));
+            if dependency.name != "syn" || !from_registry {
+                continue;
+            }
+            let version: Version = dependency.version.parse()?;
+            let (archive, _) = registry.find("syn", Some(&version))?;
+            let root = registry.unpack(&archive, &package.join(DEPS))?;
+            rebind_sources(&root.join("src"))?;
+            // A patch is named like a package, so `syn-0_15` for 0.15.
+            patches.push((
+                format!("syn-{}", patch::requirement(&version).replace('.', "_")),
+                format!(
+                    "{{ path = \"{}/{}\", package = \"syn\" }}",
+                    DEPS,
+                    root.file_name().unwrap().to_string_lossy(),
+                ),
+            ));
+        }
+        Ok(patches)
+    }
+
+    /// Rustc flags that restrict the module to what watt can decode, silence
+    /// the warnings of the patched dependencies, and replace the paths of the
+    /// build machine in panic messages with fixed ones, so the module only
+    /// depends on the sources.
+    fn rustflags(&self, package: &Path, info: &manifest::PackageInfo) -> Result<String> {
+        let version = rustc(&["-vV"])?;
+        let commit = version
+            .lines()
+            .find_map(|line| line.strip_prefix("commit-hash: "))
+            .context("rustc -vV doesn't print a commit hash")?;
+        let sysroot = PathBuf::from(rustc(&["--print", "sysroot"])?.trim());
+
+        let mut remap = vec![
+            (
+                sysroot.join("lib").join("rustlib").join("src").join("rust"),
+                format!("/rustc/{}", commit),
+            ),
+            (
+                package.to_owned(),
+                format!("/{}-{}", info.name, info.version),
+            ),
+            (self.target_dir.to_owned(), "/target".to_owned()),
+        ];
+        for (name, source) in [("proc-macro2", Some(self.proc_macro2)), ("syn", self.syn)] {
+            if let Some(Source::Path(_)) = source {
+                remap.push((package.join(DEPS).join(name), format!("/{}", name)));
+            }
+        }
+        if let Some(cargo_home) = registry::cargo_home() {
+            remap.push((cargo_home, leaks::CARGO_HOME.to_owned()));
+        }
+        let mut flags = vec!["-Ctarget-cpu=mvp".to_owned(), "-Awarnings".to_owned()];
+        flags.extend(
+            remap
+                .iter()
+                .map(|(from, to)| format!("--remap-path-prefix={}={}", from.display(), to)),
+        );
+        Ok(flags.join("\x1f"))
+    }
+}
+
+/// The patch for `source`. A local path is copied to `name` in [`DEPS`] first.
+fn stage(source: &Source, package: &Path, name: &str) -> Result<String> {
+    match source {
+        Source::Git(_) => Ok(source.to_toml()),
+        Source::Path(path) => {
+            copy_sources(Path::new(path), &package.join(DEPS).join(name))?;
+            Ok(Source::Path(format!("{}/{}", DEPS, name)).to_toml())
+        }
+    }
+}
+
+/// Copies a crate without its build output and lockfile.
+fn copy_sources(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        let file_name = path.file_name().unwrap();
+        if file_name == "target" || file_name == "Cargo.lock" {
+            continue;
+        }
+        crate::copy(&path, &to.join(file_name))?;
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Lockfile {
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+}
+
+fn rustc(args: &[&str]) -> Result<String> {
+    let output = Command::new(env::var_os("RUSTC").unwrap_or_else(|| "rustc".into()))
+        .args(args)
+        .output()
+        .context("failed to run rustc")?;
+    if !output.status.success() {
+        bail!("rustc {} failed", args.join(" "));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Removes the custom sections, like `cargo watt` did for the modules that
+/// were committed before `regenerate`. watt doesn't read them, and rustc
+/// writes a `producers` section naming its version and commit date, along
+/// with the function names and, for some crates, debug info.
+fn strip_custom_sections(module: &[u8]) -> Result<Vec<u8>> {
+    let mut stripped = module.get(..8).context("truncated module")?.to_vec();
+    let mut rest = &module[8..];
+    while !rest.is_empty() {
+        let id = rest[0];
+        let mut size = 0usize;
+        let mut len = 1;
+        loop {
+            let byte = *rest.get(len).context("truncated section header")?;
+            size |= usize::from(byte & 0x7f) << (7 * (len - 1));
+            len += 1;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let section = rest.get(..len + size).context("truncated section")?;
+        if id != 0 {
+            stripped.extend_from_slice(section);
+        }
+        rest = &rest[len + size..];
+    }
+    Ok(stripped)
+}
+
+/// The cfg with which syn leaves out everything that takes or returns a
+/// `proc_macro::TokenStream`, like `syn::parse` and `parse_macro_input!`, on
+/// wasm targets.
+const NOT_WASM: &str =
+    r#"not(all(target_arch = "wasm32", any(target_os = "unknown", target_os = "wasi")))"#;
+
+fn rebind_sources(dir: &Path) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            rebind_sources(&path)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let source = fs::read_to_string(&path)?;
+            fs::write(&path, rebind_proc_macro(&source))?;
+        }
+    }
+    Ok(())
+}
+
+/// Points syn's `proc_macro` at proc-macro2, as [`export_entry_points`]
+/// does for the macro crate, so that syn's functions for
+/// `proc_macro::TokenStream` exist in the wasm and take the token streams
+/// the entry points get.
+fn rebind_proc_macro(source: &str) -> String {
+    source.replace(NOT_WASM, "all()").replace(
+        "extern crate proc_macro;",
+        "extern crate proc_macro2 as proc_macro;",
+    )
+}
+
+/// Turns every `#[proc_macro*]` function, including those that a local
+/// `macro_rules!` defines, into an `extern "C"` export that watt can call by
+/// name, and points `proc_macro` at watt's proc-macro2.
+/// `#![forbid(unsafe_code)]` becomes a `deny`, which the exports, being
+/// `#[unsafe(no_mangle)]`, allow. The `unsafe(...)` form is what edition 2024
+/// requires and earlier editions accept.
+fn export_entry_points(source: &str) -> Result<String> {
+    let mut file = syn::parse_file(source).context("failed to parse upstream lib.rs")?;
+    crate::expand::entry_point_macros(&mut file)?;
+    for attr in &mut file.attrs {
+        if let Meta::List(list) = &mut attr.meta {
+            if list.path.is_ident("forbid") {
+                list.path = parse_quote!(deny);
+            }
+        }
+    }
+    file.items.retain(|item| match item {
+        Item::ExternCrate(krate) => krate.ident != "proc_macro",
+        _ => true,
+    });
+    for item in &mut file.items {
+        if let Item::Fn(func) = item {
+            let len = func.attrs.len();
+            func.attrs.retain(|attr| !is_proc_macro(attr));
+            if func.attrs.len() == len {
+                continue;
+            }
+            func.attrs.push(parse_quote!(#[unsafe(no_mangle)]));
+            func.attrs.push(parse_quote!(#[allow(unsafe_code)]));
+            func.vis = parse_quote!(pub);
+            func.sig.abi = Some(parse_quote!(extern "C"));
+        }
+    }
+    file.items.insert(
+        0,
+        parse_quote!(
+            extern crate proc_macro2 as proc_macro;
+        ),
+    );
+    Ok(quote!(#file).to_string())
+}
+
+fn is_proc_macro(attr: &Attribute) -> bool {
+    let path = attr.path();
+    if path.is_ident("proc_macro")
+        || path.is_ident("proc_macro_derive")
+        || path.is_ident("proc_macro_attribute")
+        || path.is_ident("proc_macro_hack")
+    {
+        return true;
+    }
+    // `#[cfg_attr(feature = "...", proc_macro_derive(...))]`
+    path.is_ident("cfg_attr")
+        && attr
+            .meta
+            .require_list()
+            .is_ok_and(|list| list.tokens.to_string().contains("proc_macro"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        copy_sources, export_entry_points, rebind_proc_macro, strip_custom_sections, Build,
+    };
+    use crate::manifest::Source;
+    use crate::registry::Registry;
+    use crate::{leaks, repo_root, DEFAULT_PROC_MACRO2};
+    use semver::Version;
+    use std::{env, fs, process};
+
+    #[test]
+    fn strips_producers() {
+        let producers = b"rustc 1.95.0 (59807616e 2026-04-14)";
+        let mut module = b"\0asm\x01\0\0\0".to_vec();
+        // A custom section named `producers`.
+        module.push(0);
+        module.push(1 + 9 + producers.len() as u8);
+        module.push(9);
+        module.extend_from_slice(b"producers");
+        module.extend_from_slice(producers);
+        // A type section with the function type () -> ().
+        let types = [1, 4, 1, 0x60, 0, 0];
+        module.extend_from_slice(&types);
+
+        assert_eq!(leaks::scan(&module).unwrap().len(), 1);
+        let stripped = strip_custom_sections(&module).unwrap();
+        assert_eq!(stripped, [&module[..8], &types].concat());
+        assert_eq!(leaks::scan(&stripped).unwrap(), []);
+        assert!(strip_custom_sections(&module[..12]).is_err());
+    }
+
+    #[test]
+    fn rebinds_proc_macro() {
+        let source = r#"
+#[cfg(all(
+    not(all(target_arch = "wasm32", any(target_os = "unknown", target_os = "wasi"))),
+    feature = "proc-macro"
+))]
+extern crate proc_macro;
+extern crate proc_macro2;
+"#;
+        assert_eq!(
+            rebind_proc_macro(source),
+            r#"
+#[cfg(all(
+    all(),
+    feature = "proc-macro"
+))]
+extern crate proc_macro2 as proc_macro;
+extern crate proc_macro2;
+"#
+        );
+    }
+
+    #[test]
+    fn exports() {
+        let source = r#"
+            #![forbid(unsafe_code)]
+            extern crate proc_macro;
+            use proc_macro::TokenStream;
+
+            #[cfg_attr(feature = "verbose", proc_macro_derive(StrumFoo))]
+            #[cfg_attr(not(feature = "verbose"), proc_macro_derive(Foo))]
+            pub fn foo(input: TokenStream) -> TokenStream { input }
+
+            #[proc_macro_attribute]
+            pub fn attr(_args: TokenStream, input: TokenStream) -> TokenStream { input }
+
+            fn helper() {}
+        "#;
+        let expected = quote::quote! {
+            #![deny(unsafe_code)]
+            extern crate proc_macro2 as proc_macro;
+            use proc_macro::TokenStream;
+
+            #[unsafe(no_mangle)]
+            #[allow(unsafe_code)]
+            pub extern "C" fn foo(input: TokenStream) -> TokenStream { input }
+
+            #[unsafe(no_mangle)]
+            #[allow(unsafe_code)]
+            pub extern "C" fn attr(_args: TokenStream, input: TokenStream) -> TokenStream { input }
+
+            fn helper() {}
+        };
+        assert_eq!(export_entry_points(source).unwrap(), expected.to_string());
+    }
+
+    // The same lockfile builds the same module in two checkouts in different
+    // directories, each with its own target directory.
+    #[test]
+    fn reproducible_across_directories() {
+        let registry = Registry::new(None).unwrap();
+        let (archive, _) = registry
+            .find("derive-new", Some(&Version::new(0, 7, 0)))
+            .unwrap();
+        let seed = repo_root().join("derive-new-0.7").join("Cargo.watt.lock");
+        let scratch = env::temp_dir().join(format!("xtask-reproducible-{}", process::id()));
+        let modules: Vec<Vec<u8>> = [scratch.join("a"), scratch.join("b").join("checkout")]
+            .iter()
+            .map(|checkout| {
+                let proc_macro2 = checkout.join(DEFAULT_PROC_MACRO2);
+                copy_sources(&repo_root().join(DEFAULT_PROC_MACRO2), &proc_macro2).unwrap();
+                let package = registry
+                    .unpack(&archive, &checkout.join("upstream"))
+                    .unwrap();
+                let build = Build {
+                    proc_macro2: &Source::Path(proc_macro2.display().to_string()),
+                    syn: None,
+                    target_dir: &checkout.join("target"),
+                };
+                let output = build.run(&package, Some(&seed)).unwrap();
+                fs::read(output.wasm).unwrap()
+            })
+            .collect();
+        fs::remove_dir_all(&scratch).unwrap();
+        assert!(modules[0] == modules[1], "the modules differ");
+    }
+}